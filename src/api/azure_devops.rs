@@ -0,0 +1,223 @@
+use super::http;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+const AZURE_DEVOPS_URL: &str = "https://dev.azure.com";
+const API_VERSION: &str = "7.1";
+
+/// Azure Repos client, authenticated with a personal access token
+pub struct AzureDevOpsClient {
+    client: Client,
+    api_url: String,
+    organization: String,
+    project: String,
+    token: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreatePullRequestPayload {
+    source_ref_name: String,
+    target_ref_name: String,
+    title: String,
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    pub pull_request_id: u64,
+    repository: Repository,
+}
+
+impl PullRequest {
+    pub fn url(&self) -> String {
+        format!("{}/pullrequest/{}", self.repository.web_url, self.pull_request_id)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Repository {
+    name: String,
+    web_url: String,
+}
+
+/// Organization, project and repository from an Azure Repos remote:
+/// `https://dev.azure.com/<org>/<project>/_git/<repo>` or
+/// `git@ssh.dev.azure.com:v3/<org>/<project>/<repo>`
+pub fn parse_remote_path(path: &str) -> Option<(String, String, String)> {
+    let parts: Vec<&str> = path.split('/').collect();
+
+    let (org, project, repo) = match parts.as_slice() {
+        [org, project, "_git", repo] => (org, project, repo),
+        ["v3", org, project, repo] => (org, project, repo),
+        _ => return None,
+    };
+
+    // Remote URLs carry the project percent-encoded ("My%20Project")
+    let decode = |part: &str| urlencoding::decode(part).map(|p| p.into_owned()).unwrap_or_else(|_| part.to_string());
+    Some((decode(org), decode(project), decode(repo)))
+}
+
+impl AzureDevOpsClient {
+    pub fn new(organization: String, project: String, token: String) -> Self {
+        Self {
+            client: http::client(),
+            api_url: AZURE_DEVOPS_URL.to_string(),
+            organization,
+            project,
+            token,
+        }
+    }
+
+    fn repo_url(&self, repo_id: &str) -> String {
+        format!(
+            "{}/{}/{}/_apis/git/repositories/{}",
+            self.api_url,
+            self.organization,
+            urlencoding::encode(&self.project),
+            urlencoding::encode(repo_id)
+        )
+    }
+
+    /// `repo_id` may be the repository name or its GUID. Refs are branch
+    /// names; `refs/heads/` is added when missing.
+    pub async fn create_pull_request(
+        &self,
+        repo_id: &str,
+        source_ref: &str,
+        target_ref: &str,
+        title: &str,
+        description: &str,
+    ) -> Result<PullRequest> {
+        let full_ref = |name: &str| {
+            if name.starts_with("refs/") {
+                name.to_string()
+            } else {
+                format!("refs/heads/{}", name)
+            }
+        };
+
+        let payload = CreatePullRequestPayload {
+            source_ref_name: full_ref(source_ref),
+            target_ref_name: full_ref(target_ref),
+            title: title.to_string(),
+            description: description.to_string(),
+        };
+
+        let url = format!("{}/pullrequests", self.repo_url(repo_id));
+
+        // PATs go in basic auth with an empty username
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("api-version", API_VERSION)])
+            .basic_auth("", Some(&self.token))
+            .json(&payload)
+            .send()
+            .await
+            .context("Failed to send pull request creation request")?;
+
+        let response = http::check_response("Azure DevOps", response).await?;
+
+        response
+            .json::<PullRequest>()
+            .await
+            .context("Failed to parse pull request response")
+    }
+
+    pub async fn get_repo_info(&self, repo_id: &str) -> Result<String> {
+        let response = self
+            .client
+            .get(self.repo_url(repo_id))
+            .query(&[("api-version", API_VERSION)])
+            .basic_auth("", Some(&self.token))
+            .send()
+            .await
+            .context("Failed to fetch repository information")?;
+
+        let response = http::check_response("Azure DevOps", response).await?;
+
+        let repo = response
+            .json::<Repository>()
+            .await
+            .context("Failed to parse repository response")?;
+
+        Ok(format!("{}/{}/{}", self.organization, self.project, repo.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_client(url: String) -> AzureDevOpsClient {
+        let mut client = AzureDevOpsClient::new(
+            "contoso".to_string(),
+            "Fabrikam App".to_string(),
+            "test-pat".to_string(),
+        );
+        client.api_url = url;
+        client
+    }
+
+    #[test]
+    fn test_parse_remote_path() {
+        let expected = Some(("contoso".to_string(), "Fabrikam".to_string(), "app".to_string()));
+        assert_eq!(parse_remote_path("contoso/Fabrikam/_git/app"), expected);
+        assert_eq!(parse_remote_path("v3/contoso/Fabrikam/app"), expected);
+        assert_eq!(parse_remote_path("owner/repo"), None);
+        assert_eq!(
+            parse_remote_path("contoso/Fabrikam%20App/_git/app").map(|(_, project, _)| project),
+            Some("Fabrikam App".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request_success() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("POST", "/contoso/Fabrikam%20App/_apis/git/repositories/app/pullrequests")
+            .match_query(mockito::Matcher::UrlEncoded("api-version".into(), "7.1".into()))
+            // base64(":test-pat")
+            .match_header("authorization", "Basic OnRlc3QtcGF0")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "sourceRefName": "refs/heads/feat/WAB-1/x",
+                "targetRefName": "refs/heads/main"
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"pullRequestId":17,"repository":{"name":"app","webUrl":"https://dev.azure.com/contoso/Fabrikam%20App/_git/app"}}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pr = client
+            .create_pull_request("app", "feat/WAB-1/x", "main", "WAB-1: X", "Resolves WAB-1")
+            .await
+            .unwrap();
+
+        assert_eq!(pr.url(), "https://dev.azure.com/contoso/Fabrikam%20App/_git/app/pullrequest/17");
+        assert_eq!(pr.pull_request_id, 17);
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_repo_info_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/contoso/Fabrikam%20App/_apis/git/repositories/app")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let err = client.get_repo_info("app").await.unwrap_err();
+        assert_eq!(http::status_of(&err), Some(401));
+    }
+}
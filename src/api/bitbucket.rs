@@ -0,0 +1,191 @@
+use super::http;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+const BITBUCKET_API_URL: &str = "https://api.bitbucket.org/2.0";
+
+/// Bitbucket Cloud client, authenticated with a repository or workspace
+/// access token
+pub struct BitbucketClient {
+    client: Client,
+    api_url: String,
+    token: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BranchRef {
+    branch: BranchName,
+}
+
+#[derive(Debug, Serialize)]
+struct BranchName {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CreatePullRequestPayload {
+    title: String,
+    description: String,
+    source: BranchRef,
+    destination: BranchRef,
+    close_source_branch: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequest {
+    pub id: u64,
+    links: PullRequestLinks,
+}
+
+impl PullRequest {
+    pub fn url(&self) -> &str {
+        &self.links.html.href
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestLinks {
+    html: Link,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Repository {
+    full_name: String,
+}
+
+impl BitbucketClient {
+    pub fn new(token: String) -> Self {
+        Self {
+            client: http::client(),
+            api_url: BITBUCKET_API_URL.to_string(),
+            token,
+        }
+    }
+
+    pub async fn create_pull_request(
+        &self,
+        workspace: &str,
+        repo_slug: &str,
+        source: &str,
+        dest: &str,
+        title: &str,
+        description: &str,
+    ) -> Result<PullRequest> {
+        let branch = |name: &str| BranchRef {
+            branch: BranchName {
+                name: name.to_string(),
+            },
+        };
+
+        let payload = CreatePullRequestPayload {
+            title: title.to_string(),
+            description: description.to_string(),
+            source: branch(source),
+            destination: branch(dest),
+            close_source_branch: true,
+        };
+
+        let url = format!(
+            "{}/repositories/{}/{}/pullrequests",
+            self.api_url, workspace, repo_slug
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .json(&payload)
+            .send()
+            .await
+            .context("Failed to send pull request creation request")?;
+
+        let response = http::check_response("Bitbucket", response).await?;
+
+        response
+            .json::<PullRequest>()
+            .await
+            .context("Failed to parse pull request response")
+    }
+
+    pub async fn get_repo_info(&self, workspace: &str, repo_slug: &str) -> Result<String> {
+        let url = format!("{}/repositories/{}/{}", self.api_url, workspace, repo_slug);
+
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .context("Failed to fetch repository information")?;
+
+        let response = http::check_response("Bitbucket", response).await?;
+
+        let repo = response
+            .json::<Repository>()
+            .await
+            .context("Failed to parse repository response")?;
+
+        Ok(repo.full_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_client(url: String) -> BitbucketClient {
+        let mut client = BitbucketClient::new("test-token".to_string());
+        client.api_url = url;
+        client
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request_success() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("POST", "/repositories/team/app/pullrequests")
+            .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "title": "WAB-1: X",
+                "source": { "branch": { "name": "feat/WAB-1/x" } },
+                "destination": { "branch": { "name": "main" } }
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":9,"links":{"html":{"href":"https://bitbucket.org/team/app/pull-requests/9"}}}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pr = client
+            .create_pull_request("team", "app", "feat/WAB-1/x", "main", "WAB-1: X", "Resolves WAB-1")
+            .await
+            .unwrap();
+
+        assert_eq!(pr.url(), "https://bitbucket.org/team/app/pull-requests/9");
+        assert_eq!(pr.id, 9);
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_repo_info_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repositories/team/app")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let err = client.get_repo_info("team", "app").await.unwrap_err();
+        assert_eq!(http::status_of(&err), Some(401));
+    }
+}
@@ -2,9 +2,404 @@ use anyhow::Context;
 use colored::*;
 use git2::Repository;
 use crate::errors::{DevFlowError, Result};
+use crate::utils::output::say;
 
 pub struct GitClient {
     repo: Repository,
+    signing: Signing,
+}
+
+/// Whether `commit` and `commit_staged` sign
+#[derive(Debug, Clone, Default)]
+enum Signing {
+    /// Sign when git's `commit.gpgsign` is set, like `git commit`
+    #[default]
+    GitConfig,
+    /// Always sign (`preferences.sign_commits`); OpenPGP signatures come from
+    /// this program rather than git's `gpg.program`
+    Always(String),
+    /// `--no-sign`
+    Never,
+}
+
+/// How to sign a commit, from git's `gpg.format` and `user.signingkey`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Signer {
+    OpenPgp { program: String, key: String },
+    /// `key` is a key file, or a literal public key held by ssh-agent
+    Ssh { program: String, key: String },
+}
+
+impl Signer {
+    /// Signature to store in the commit's `gpgsig` header
+    fn sign(&self, content: &str) -> Result<String> {
+        match self {
+            Signer::OpenPgp { program, key } => gpg_sign(program, key, content),
+            Signer::Ssh { program, key } => ssh_sign(program, key, content),
+        }
+    }
+}
+
+/// A commit as listed by `get_log`
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub message: String,
+    pub is_merge: bool,
+}
+
+/// How a file differs, between HEAD and the index or the index and the worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    TypeChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FileStatus {
+    pub path: String,
+    /// Previous path of a renamed file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    pub change: FileChange,
+}
+
+/// Working tree state as returned by `status_report`; rendering is left to callers
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct StatusReport {
+    pub staged: Vec<FileStatus>,
+    pub unstaged: Vec<FileStatus>,
+    pub untracked: Vec<String>,
+    pub conflicted: Vec<String>,
+    /// e.g. `origin/feat/WAB-1/x`; `None` without a (fetched) upstream
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+impl StatusReport {
+    pub fn is_clean(&self) -> bool {
+        self.staged.is_empty() && self.unstaged.is_empty() && self.untracked.is_empty() && self.conflicted.is_empty()
+    }
+}
+
+fn new_path(delta: &git2::DiffDelta) -> Option<String> {
+    delta.new_file().path().map(|path| path.to_string_lossy().into_owned())
+}
+
+fn renamed_from(delta: &git2::DiffDelta) -> Option<String> {
+    let old = delta.old_file().path()?;
+    (delta.status() == git2::Delta::Renamed && Some(old) != delta.new_file().path())
+        .then(|| old.to_string_lossy().into_owned())
+}
+
+/// How `sync` brings the base branch into the current one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncMode {
+    Rebase,
+    Merge,
+}
+
+impl SyncMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SyncMode::Rebase => "rebase",
+            SyncMode::Merge => "merge",
+        }
+    }
+}
+
+/// What `sync` did to the current branch
+#[derive(Debug, PartialEq)]
+pub enum SyncOutcome {
+    UpToDate,
+    /// The branch had no commits of its own and now points at the base
+    FastForwarded,
+    /// Replayed this many commits on top of the base
+    Rebased(usize),
+    Merged,
+    /// Stopped on conflicts in these paths, leaving the rebase or merge in
+    /// progress for git to finish
+    Conflicts(Vec<String>),
+}
+
+/// Paths with conflicts in `index`, sorted and deduplicated
+fn conflicted_paths(index: &git2::Index) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for conflict in index.conflicts().context("Failed to read index conflicts")? {
+        let conflict = conflict.context("Failed to read index conflict")?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(String::from_utf8_lossy(&entry.path).into_owned());
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Host and repository path extracted from a git remote URL
+#[derive(Debug, PartialEq)]
+pub struct RemoteInfo {
+    pub host: String,
+    /// e.g. `group/subgroup/repo`, without a trailing `.git`
+    pub path: String,
+}
+
+/// Parse the common remote URL shapes: `git@host:path.git`,
+/// `ssh://git@host[:port]/path.git` and `https://host/path.git`
+pub fn parse_remote_url(url: &str) -> Option<RemoteInfo> {
+    let url = url.trim();
+
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        (host, path)
+    } else {
+        let (user_host, path) = url.split_once(':')?;
+        let host = user_host.rsplit('@').next()?;
+        (host, path)
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some(RemoteInfo {
+        host: host.to_string(),
+        path: path.to_string(),
+    })
+}
+
+/// Git config flag on branches whose upstream was set before their first push
+fn unpushed_key(branch_name: &str) -> String {
+    format!("branch.{}.devflowUnpushed", branch_name)
+}
+
+/// ASCII-armored detached signature of `content` made by `key`
+fn gpg_sign(program: &str, key: &str, content: &str) -> Result<String> {
+    run_signer(program, &["--armor", "--detach-sign", "--local-user", key], content).map_err(|failure| match failure {
+        SignFailure::NotFound => DevFlowError::Other(format!(
+            "GPG program '{}' not found. Install GnuPG, or point devflow at it with \
+             `devflow config set preferences.gpg_program <path>`",
+            program
+        )),
+        SignFailure::Failed(stderr) => DevFlowError::Other(format!(
+            "{} could not sign with key '{}': {}. Check the key with `gpg --list-secret-keys {}`",
+            program, key, stderr, key
+        )),
+        SignFailure::Other(e) => e,
+    })
+}
+
+/// `ssh-keygen -Y sign` signature of `content`, as git makes for
+/// `gpg.format = ssh`. A literal public key is signed with by ssh-agent.
+fn ssh_sign(program: &str, key: &str, content: &str) -> Result<String> {
+    let key = key.strip_prefix("key::").unwrap_or(key);
+    let literal = key.starts_with("ssh-") || key.starts_with("ecdsa-") || key.starts_with("sk-");
+
+    // ssh-keygen only takes keys from files; git writes literal keys out too.
+    // The temp file is created exclusively, readable only by us, and removed
+    // when dropped
+    let temp_key = if literal {
+        use std::io::Write;
+        let mut file = tempfile::Builder::new()
+            .prefix(".devflow-signing-key-")
+            .suffix(".pub")
+            .tempfile()
+            .context("Failed to create a temp file for the signing key")?;
+        writeln!(file, "{}", key).context("Failed to write the signing key to a temp file")?;
+        Some(file)
+    } else {
+        None
+    };
+    let key_file = match &temp_key {
+        Some(file) => file.path().to_path_buf(),
+        None => match (key.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => std::path::PathBuf::from(key),
+        },
+    };
+    let key_path = key_file.display().to_string();
+
+    let mut args = vec!["-Y", "sign", "-n", "git", "-f", key_path.as_str()];
+    if literal {
+        args.push("-U");
+    }
+    let signed = run_signer(program, &args, content);
+    drop(temp_key);
+
+    signed.map_err(|failure| match failure {
+        SignFailure::NotFound => DevFlowError::Other(format!(
+            "SSH signing program '{}' not found. Install OpenSSH, or set it with `git config gpg.ssh.program <path>`",
+            program
+        )),
+        SignFailure::Failed(stderr) => DevFlowError::Other(format!(
+            "{} could not sign with SSH key '{}': {}. Check that the key file exists, or that ssh-agent holds the key (`ssh-add -L`)",
+            program, key, stderr
+        )),
+        SignFailure::Other(e) => e,
+    })
+}
+
+enum SignFailure {
+    NotFound,
+    /// Exited non-zero, with this stderr
+    Failed(String),
+    Other(DevFlowError),
+}
+
+/// Pipe `content` through a signing program and return what it prints
+fn run_signer(program: &str, args: &[&str], content: &str) -> std::result::Result<String, SignFailure> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => SignFailure::NotFound,
+            _ => SignFailure::Other(DevFlowError::Other(format!("Failed to run '{}': {}", program, e))),
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes()).map_err(|e| {
+            SignFailure::Other(DevFlowError::Other(format!("Failed to send the commit to '{}': {}", program, e)))
+        })?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| SignFailure::Other(DevFlowError::Other(format!("Failed to run '{}': {}", program, e))))?;
+
+    if !output.status.success() {
+        return Err(SignFailure::Failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    String::from_utf8(output.stdout).map_err(|_| {
+        SignFailure::Other(DevFlowError::Other(format!("{} returned a signature that is not valid UTF-8", program)))
+    })
+}
+
+/// Signer described by `gpg.format`, `user.signingkey` and the program
+/// settings, as `git commit -S` would use. `gpg_program` overrides
+/// `gpg.program` for OpenPGP.
+fn signer_from_config(config: &git2::Config, gpg_program: Option<String>) -> Result<Signer> {
+    let format = config.get_string("gpg.format").unwrap_or_else(|_| "openpgp".to_string());
+    let key = config.get_string("user.signingkey").map_err(|_| {
+        DevFlowError::Other(
+            "Commit signing is on but git has no signing key. Set one with \
+             `git config user.signingkey <key>`, or commit without signing with `devflow commit --no-sign`"
+                .to_string(),
+        )
+    })?;
+
+    match format.as_str() {
+        "openpgp" => {
+            let program = gpg_program
+                .or_else(|| config.get_string("gpg.openpgp.program").ok())
+                .or_else(|| config.get_string("gpg.program").ok())
+                .unwrap_or_else(|| "gpg".to_string());
+            Ok(Signer::OpenPgp { program, key })
+        }
+        "ssh" => {
+            let program = config.get_string("gpg.ssh.program").unwrap_or_else(|_| "ssh-keygen".to_string());
+            Ok(Signer::Ssh { program, key })
+        }
+        other => Err(DevFlowError::Other(format!(
+            "gpg.format '{}' isn't supported; devflow signs with openpgp or ssh. Commit with `devflow commit --no-sign` or `git commit`",
+            other
+        ))),
+    }
+}
+
+/// The link GitHub, GitLab and Bitbucket print after pushing a new branch,
+/// e.g. "Create a pull request for 'x' on GitHub by visiting: <url>"
+fn compare_url(messages: &str) -> Option<String> {
+    let mut lines = messages.lines().map(|line| line.trim_start_matches("remote:").trim());
+    lines.find(|line| {
+        let line = line.to_lowercase();
+        line.contains("pull request") || line.contains("merge request")
+    })?;
+    lines
+        .take(2)
+        .flat_map(str::split_whitespace)
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(String::from)
+}
+
+/// Tell credential problems, rejected pushes and network failures apart
+fn classify_push_error(error: &git2::Error, branch_name: &str) -> DevFlowError {
+    use git2::{ErrorClass, ErrorCode};
+
+    match (error.code(), error.class()) {
+        (ErrorCode::NotFastForward, _) => DevFlowError::PushRejected(branch_name.to_string()),
+        (ErrorCode::Auth | ErrorCode::Certificate, _) | (_, ErrorClass::Ssh) => {
+            DevFlowError::NoPushAccess(error.message().to_string())
+        }
+        // Refused or failed connections surface as OS errors
+        (_, ErrorClass::Net | ErrorClass::Http | ErrorClass::Os) => {
+            DevFlowError::NetworkError(error.message().to_string())
+        }
+        _ => DevFlowError::Other(format!("Failed to push branch '{}': {}", branch_name, error.message())),
+    }
+}
+
+fn remote_callbacks<'a>() -> git2::RemoteCallbacks<'a> {
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(|_url, username_from_url, _allowed_types| {
+        git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
+    });
+    callbacks
+}
+
+/// Push one refspec, turning refs the server rejected into errors. Returns
+/// the `remote: ...` output.
+fn push_refspec(remote: &mut git2::Remote<'_>, refspec: &str, name: &str) -> Result<Vec<u8>> {
+    // Servers report rejected refs here rather than failing the push
+    let mut rejection = None;
+    // `remote: ...` lines, which may arrive split across calls
+    let mut messages = Vec::new();
+    {
+        let mut callbacks = remote_callbacks();
+        callbacks.push_update_reference(|_refname, status| {
+            if let Some(status) = status {
+                rejection = Some(status.to_string());
+            }
+            Ok(())
+        });
+        callbacks.sideband_progress(|data| {
+            messages.extend_from_slice(data);
+            true
+        });
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+
+        remote
+            .push(&[refspec], Some(&mut push_options))
+            .map_err(|e| classify_push_error(&e, name))?;
+    }
+    if let Some(status) = rejection {
+        return Err(if status.contains("fast-forward") || status.contains("fetch first") {
+            DevFlowError::PushRejected(name.to_string())
+        } else {
+            let remote_name = remote.name().unwrap_or("the remote");
+            DevFlowError::Other(format!("{} rejected the push of '{}': {}", remote_name, name, status))
+        });
+    }
+    Ok(messages)
 }
 
 impl GitClient {
@@ -12,7 +407,37 @@ impl GitClient {
         let repo = Repository::open_from_env()
             .map_err(|_| DevFlowError::NotInGitRepo)?;
 
-        Ok(Self { repo })
+        Ok(Self { repo, signing: Signing::GitConfig })
+    }
+
+    /// Sign commits made by `commit` and `commit_staged` with
+    /// `user.signingkey` even when git's `commit.gpgsign` is off. OpenPGP
+    /// signatures are made with `gpg_program`.
+    pub fn with_signing(mut self, gpg_program: &str) -> Self {
+        self.signing = Signing::Always(gpg_program.to_string());
+        self
+    }
+
+    /// Don't sign, whatever `commit.gpgsign` says
+    pub fn without_signing(mut self) -> Self {
+        self.signing = Signing::Never;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn open(path: &std::path::Path) -> Result<Self> {
+        let repo = Repository::open(path)
+            .map_err(|_| DevFlowError::NotInGitRepo)?;
+
+        Ok(Self { repo, signing: Signing::GitConfig })
+    }
+
+    /// Another handle on this repository, e.g. to use on a blocking thread
+    pub fn reopen(&self) -> Result<Self> {
+        let repo = Repository::open(self.repo.path())
+            .map_err(|_| DevFlowError::NotInGitRepo)?;
+
+        Ok(Self { repo, signing: self.signing.clone() })
     }
 
     pub fn is_clean(&self) -> Result<bool> {
@@ -21,45 +446,70 @@ impl GitClient {
         Ok(statuses.is_empty())
     }
 
+    /// Name of the checked-out branch, including an unborn one in a repository
+    /// without commits. Fails with `DetachedHead` when no branch is checked out.
     pub fn current_branch(&self) -> Result<String> {
-        let head = self.repo.head()
+        let head = self.repo.find_reference("HEAD")
             .map_err(|e| DevFlowError::Other(format!("Failed to get HEAD reference: {}", e)))?;
 
-        if head.is_branch() {
-            let branch_name = head
-                .shorthand()
-                .ok_or_else(|| DevFlowError::Other("Branch name contains invalid UTF-8".to_string()))?
-                .to_string();
+        match head.symbolic_target_bytes() {
+            Some(target) => {
+                let target = std::str::from_utf8(target)
+                    .map_err(|_| DevFlowError::Other("Branch name contains invalid UTF-8".to_string()))?;
+                Ok(target.strip_prefix("refs/heads/").unwrap_or(target).to_string())
+            }
+            None => {
+                let sha = head
+                    .target()
+                    .map(|oid| oid.to_string()[..7].to_string())
+                    .unwrap_or_default();
+                Err(DevFlowError::DetachedHead(sha))
+            }
+        }
+    }
+
+    /// Whether HEAD is a branch with no commits yet, as right after `git init`
+    pub fn is_unborn(&self) -> bool {
+        matches!(self.repo.head(), Err(e) if e.code() == git2::ErrorCode::UnbornBranch)
+    }
 
-            Ok(branch_name)
-        } else {
-            Err(DevFlowError::Other("Not currently on a branch (detached HEAD state)".to_string()))
+    /// The commit HEAD points at; `None` when the branch is unborn
+    fn head_commit(&self) -> Result<Option<git2::Commit<'_>>> {
+        match self.repo.head() {
+            Ok(head) => Ok(Some(head.peel_to_commit().context("HEAD is not a commit")?)),
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+            Err(e) => Err(anyhow::Error::new(e).context("Failed to get HEAD").into()),
         }
     }
 
-    pub fn create_branch(&self, branch_name: &str) -> Result<()> {
-        let head_commit = self
+    /// Create a branch at `start_point` (any revspec, e.g. `origin/develop`) and switch to it
+    pub fn create_branch_from(&self, branch_name: &str, start_point: &str) -> Result<()> {
+        if self.is_unborn() {
+            return Err(DevFlowError::EmptyRepository);
+        }
+
+        let start_commit = self
             .repo
-            .head()
-            .context("Failed to get HEAD")?
+            .revparse_single(start_point)
+            .context(format!("Failed to resolve '{}'", start_point))?
             .peel_to_commit()
-            .context("Failed to get HEAD commit")?;
+            .context(format!("'{}' does not point to a commit", start_point))?;
 
         self.repo
-            .branch(branch_name, &head_commit, false)
+            .branch(branch_name, &start_commit, false)
             .context(format!("Failed to create branch '{}'", branch_name))?;
 
         let refname = format!("refs/heads/{}", branch_name);
 
         self.repo
-            .set_head(&refname)
-            .context("Failed to set HEAD to new branch")?;
+            .checkout_tree(start_commit.as_object(), None)
+            .context("Failed to checkout new branch")?;
 
         self.repo
-            .checkout_head(None)
-            .context("Failed to checkout new branch")?;
+            .set_head(&refname)
+            .context("Failed to set HEAD to new branch")?;
 
-        println!(
+        say!(
             "{}",
             format!("✓ Created and switched to branch '{}'", branch_name).green()
         );
@@ -67,120 +517,2284 @@ impl GitClient {
         Ok(())
     }
 
-    pub fn status_summary(&self) -> Result<String> {
-        let statuses = self.repo.statuses(None)
-            .map_err(|e| DevFlowError::Other(format!("Failed to get git status: {}", e)))?;
+    /// Fetch a single branch from a remote into `refs/remotes/<remote>/<branch>`
+    pub fn fetch_remote(&self, remote_name: &str, branch_name: &str) -> Result<()> {
+        let mut remote = self
+            .repo
+            .find_remote(remote_name)
+            .map_err(|_| DevFlowError::Other(format!("Remote '{}' not found", remote_name)))?;
 
-        if statuses.is_empty() {
-            return Ok("  Working directory clean".to_string());
+        let refspec = format!(
+            "+refs/heads/{}:refs/remotes/{}/{}",
+            branch_name, remote_name, branch_name
+        );
+
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(remote_callbacks());
+
+        remote
+            .fetch(&[&refspec], Some(&mut fetch_options), None)
+            .context(format!("Failed to fetch '{}' from {}", branch_name, remote_name))?;
+
+        Ok(())
+    }
+
+    /// Fetch `branch_name` and fast-forward the local branch to it, creating
+    /// it if needed. Fails rather than discard local commits.
+    pub fn fast_forward_from_origin(&self, branch_name: &str) -> Result<()> {
+        self.fetch_remote("origin", branch_name)?;
+
+        let remote = self
+            .repo
+            .find_reference(&format!("refs/remotes/origin/{}", branch_name))
+            .and_then(|reference| reference.peel_to_commit())
+            .context(format!("Failed to resolve 'origin/{}'", branch_name))?;
+
+        let refname = format!("refs/heads/{}", branch_name);
+        let local = match self.repo.find_reference(&refname) {
+            Ok(reference) => reference.peel_to_commit().context("Local branch is not a commit")?.id(),
+            Err(_) => {
+                self.repo
+                    .branch(branch_name, &remote, false)
+                    .context(format!("Failed to create branch '{}'", branch_name))?;
+                return Ok(());
+            }
+        };
+
+        if local == remote.id() {
+            return Ok(());
+        }
+        if !self.repo.graph_descendant_of(remote.id(), local).unwrap_or(false) {
+            return Err(DevFlowError::Other(format!(
+                "Local '{}' has commits that aren't on origin; not fast-forwarding",
+                branch_name
+            )));
         }
 
-        let mut summary = Vec::new();
+        if self.current_branch().ok().as_deref() == Some(branch_name) {
+            self.repo
+                .checkout_tree(remote.as_object(), None)
+                .context(format!("Failed to update the working tree to origin/{}", branch_name))?;
+        }
+        self.repo
+            .reference(&refname, remote.id(), true, "devflow: fast-forward")
+            .context(format!("Failed to update '{}'", branch_name))?;
 
-        for entry in statuses.iter() {
-            let status = entry.status();
-            let path = entry.path().unwrap_or("unknown");
+        Ok(())
+    }
 
-            if status.is_wt_modified() {
-                summary.push(format!("  {} {}", "M".yellow(), path));
-            } else if status.is_wt_new() {
-                summary.push(format!("  {} {}", "A".green(), path));
-            } else if status.is_wt_deleted() {
-                summary.push(format!("  {} {}", "D".red(), path));
+    /// The remote's default branch, from `origin/HEAD` or a `main`/`master` ref
+    pub fn default_branch(&self) -> Option<String> {
+        if let Ok(reference) = self.repo.find_reference("refs/remotes/origin/HEAD") {
+            if let Some(target) = reference.symbolic_target() {
+                if let Some(name) = target.strip_prefix("refs/remotes/origin/") {
+                    return Some(name.to_string());
+                }
             }
         }
 
-        Ok(summary.join("\n"))
+        ["main", "master"]
+            .into_iter()
+            .find(|name| {
+                self.repo.find_reference(&format!("refs/remotes/origin/{}", name)).is_ok()
+                    || self.repo.find_reference(&format!("refs/heads/{}", name)).is_ok()
+            })
+            .map(String::from)
     }
 
-    pub fn push(&self, branch_name: &str) -> Result<()> {
-        let mut remote = self
-            .repo
-            .find_remote("origin")
-            .context("Failed to find remote 'origin'")?;
+    /// Prefer the remote-tracking ref for `branch_name`, falling back to the local branch
+    pub fn resolve_base(&self, branch_name: &str) -> Result<String> {
+        let candidates = [
+            format!("refs/remotes/origin/{}", branch_name),
+            format!("refs/heads/{}", branch_name),
+        ];
 
-        let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
+        candidates
+            .into_iter()
+            .find(|refname| self.repo.find_reference(refname).is_ok())
+            .ok_or_else(|| DevFlowError::Other(format!("Base branch '{}' not found locally or on origin", branch_name)))
+    }
 
-        let mut callbacks = git2::RemoteCallbacks::new();
-        callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
-        });
+    pub fn branch_exists(&self, branch_name: &str) -> Result<bool> {
+        match self.repo.find_branch(branch_name, git2::BranchType::Local) {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(false),
+            Err(e) => Err(DevFlowError::Other(format!("Failed to look up branch '{}': {}", branch_name, e))),
+        }
+    }
 
-        let mut push_options = git2::PushOptions::new();
-        push_options.remote_callbacks(callbacks);
+    pub fn checkout_branch(&self, branch_name: &str) -> Result<()> {
+        let refname = format!("refs/heads/{}", branch_name);
 
-        remote
-            .push(&[&refspec], Some(&mut push_options))
-            .context(format!("Failed to push branch '{}'", branch_name))?;
+        let target = self
+            .repo
+            .revparse_single(&refname)
+            .context(format!("Failed to find branch '{}'", branch_name))?;
+
+        self.repo
+            .checkout_tree(&target, None)
+            .context(format!("Failed to checkout branch '{}'", branch_name))?;
 
-        println!(
+        self.repo
+            .set_head(&refname)
+            .context("Failed to set HEAD to branch")?;
+
+        say!(
             "{}",
-            format!("✓ Pushed branch '{}' to origin", branch_name).green()
+            format!("✓ Switched to branch '{}'", branch_name).green()
         );
 
         Ok(())
     }
 
-    pub fn commit(&self, message: &str) -> Result<()> {
-        let mut index = self.repo.index().context("Failed to get repository index")?;
-        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
-            .context("Failed to add files to index")?;
-        index.write().context("Failed to write index")?;
+    /// Staged, unstaged, untracked and conflicted files, plus how the
+    /// current branch compares with its upstream
+    pub fn status_report(&self) -> Result<StatusReport> {
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .include_ignored(false)
+            .renames_head_to_index(true)
+            .renames_index_to_workdir(true);
+        let statuses = self.repo.statuses(Some(&mut options))
+            .map_err(|e| DevFlowError::Other(format!("Failed to get git status: {}", e)))?;
 
-        let tree_id = index.write_tree().context("Failed to write tree")?;
-        let tree = self.repo.find_tree(tree_id).context("Failed to find tree")?;
+        let mut report = StatusReport::default();
+        for entry in statuses.iter() {
+            let status = entry.status();
+            let path = entry.path().unwrap_or("unknown").to_string();
 
-        let head = self.repo.head().context("Failed to get HEAD")?;
-        let parent_commit = head.peel_to_commit().context("Failed to get parent commit")?;
+            if status.is_conflicted() {
+                report.conflicted.push(path);
+                continue;
+            }
+            if status.is_wt_new() {
+                report.untracked.push(path);
+                continue;
+            }
 
-        let signature = self.repo.signature()
-            .context("Failed to get git signature. Make sure git user.name and user.email are configured")?;
+            let staged = if status.is_index_new() {
+                Some(FileChange::Added)
+            } else if status.is_index_modified() {
+                Some(FileChange::Modified)
+            } else if status.is_index_deleted() {
+                Some(FileChange::Deleted)
+            } else if status.is_index_renamed() {
+                Some(FileChange::Renamed)
+            } else if status.is_index_typechange() {
+                Some(FileChange::TypeChange)
+            } else {
+                None
+            };
+            if let Some(change) = staged {
+                let delta = entry.head_to_index();
+                report.staged.push(FileStatus {
+                    path: delta.as_ref().and_then(new_path).unwrap_or_else(|| path.clone()),
+                    from: delta.as_ref().and_then(renamed_from),
+                    change,
+                });
+            }
 
-        self.repo
-            .commit(
-                Some("HEAD"),
-                &signature,
-                &signature,
-                message,
-                &tree,
-                &[&parent_commit],
-            )
-            .context("Failed to create commit")?;
-
-        println!("{}", format!("✓ Created commit: {}", message).green());
+            let unstaged = if status.is_wt_modified() {
+                Some(FileChange::Modified)
+            } else if status.is_wt_deleted() {
+                Some(FileChange::Deleted)
+            } else if status.is_wt_renamed() {
+                Some(FileChange::Renamed)
+            } else if status.is_wt_typechange() {
+                Some(FileChange::TypeChange)
+            } else {
+                None
+            };
+            if let Some(change) = unstaged {
+                let delta = entry.index_to_workdir();
+                report.unstaged.push(FileStatus {
+                    path: delta.as_ref().and_then(new_path).unwrap_or(path),
+                    from: delta.as_ref().and_then(renamed_from),
+                    change,
+                });
+            }
+        }
 
-        Ok(())
+        if let Ok(branch) = self.current_branch() {
+            if !self.is_unborn() {
+                report.upstream = self.get_upstream_branch(&branch)?;
+                if let Some((ahead, behind)) = self.upstream_ahead_behind(&branch)? {
+                    report.ahead = ahead;
+                    report.behind = behind;
+                }
+            }
+        }
+
+        Ok(report)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn find_remote(&self, name: &str) -> Result<git2::Remote<'_>> {
+        self.repo.find_remote(name).map_err(|_| {
+            let remotes = self
+                .repo
+                .remotes()
+                .map(|remotes| remotes.iter().flatten().map(String::from).collect())
+                .unwrap_or_default();
+            DevFlowError::RemoteNotFound(name.to_string(), remotes)
+        })
+    }
 
-    #[test]
-    fn test_git_client_in_repo() {
-        let result = GitClient::new();
-        assert!(result.is_ok(), "Should successfully open git repo");
+    /// Push to the branch's configured upstream, or to `origin/<branch>`
+    /// (recording it as the upstream) when it has none. Returns the "create a
+    /// PR/MR" link the server printed, if any.
+    pub fn push(&self, branch_name: &str) -> Result<Option<String>> {
+        self.push_branch(branch_name, false)
     }
 
-    #[test]
-    fn test_current_branch() {
-        if let Ok(git) = GitClient::new() {
-            let result = git.current_branch();
-            match result {
-                Ok(branch) => assert!(!branch.is_empty(), "Branch name should not be empty"),
-                Err(_) => {
-                    // It's okay if we can't get branch (e.g., new repo with no commits)
-                }
+    /// Like `push`, but overwrite the remote branch as long as it is still
+    /// where our remote-tracking ref says, like `git push --force-with-lease`
+    pub fn push_force_with_lease(&self, branch_name: &str) -> Result<Option<String>> {
+        self.push_branch(branch_name, true)
+    }
+
+    fn push_branch(&self, branch_name: &str, force_with_lease: bool) -> Result<Option<String>> {
+        let upstream = self.upstream_config(branch_name);
+        let (remote_name, remote_branch) = upstream
+            .clone()
+            .unwrap_or_else(|| ("origin".to_string(), branch_name.to_string()));
+
+        let mut remote = self.find_remote(&remote_name)?;
+
+        if force_with_lease {
+            let expected = self
+                .repo
+                .refname_to_id(&format!("refs/remotes/{}/{}", remote_name, remote_branch))
+                .ok();
+            let connection = remote
+                .connect_auth(git2::Direction::Push, Some(remote_callbacks()), None)
+                .map_err(|e| classify_push_error(&e, branch_name))?;
+            let remote_ref = format!("refs/heads/{}", remote_branch);
+            let actual = connection
+                .list()
+                .context(format!("Failed to list branches on {}", remote_name))?
+                .iter()
+                .find(|head| head.name() == remote_ref)
+                .map(|head| head.oid());
+            drop(connection);
+
+            if actual != expected {
+                return Err(DevFlowError::Other(format!(
+                    "{}/{} has changed since it was last fetched; not overwriting it.\n  Run `git fetch {}` and review the new commits first",
+                    remote_name, remote_branch, remote_name
+                )));
             }
         }
+
+        let refspec = format!(
+            "{}refs/heads/{}:refs/heads/{}",
+            if force_with_lease { "+" } else { "" },
+            branch_name,
+            remote_branch
+        );
+
+        let messages = push_refspec(&mut remote, &refspec, branch_name)?;
+
+        // The push created the remote-tracking ref, so git2 can record it as is
+        if upstream.is_none() {
+            self.repo
+                .find_branch(branch_name, git2::BranchType::Local)
+                .and_then(|mut branch| branch.set_upstream(Some(&format!("{}/{}", remote_name, remote_branch))))
+                .context(format!("Pushed, but failed to set the upstream of '{}'", branch_name))?;
+        }
+        // Pushed now, so a missing remote-tracking ref means it was deleted
+        let mut config = self.repo.config().context("Failed to open git config")?;
+        let _ = config.remove(&unpushed_key(branch_name));
+
+        Ok(compare_url(&String::from_utf8_lossy(&messages)))
     }
 
-    #[test]
-    fn test_status_summary() {
-        if let Ok(git) = GitClient::new() {
-            let result = git.status_summary();
-            assert!(result.is_ok(), "Should get status summary");
+    /// Create an annotated tag on HEAD. Fails if the tag already exists.
+    pub fn create_tag(&self, name: &str, message: &str) -> Result<()> {
+        let head = self
+            .repo
+            .head()
+            .and_then(|head| head.peel(git2::ObjectType::Commit))
+            .context("Failed to resolve HEAD")?;
+        let tagger = self.repo.signature().context("Failed to get git signature")?;
+
+        self.repo.tag(name, &head, &tagger, message, false).map_err(|e| {
+            if e.code() == git2::ErrorCode::Exists {
+                DevFlowError::Other(format!("Tag '{}' already exists", name))
+            } else {
+                DevFlowError::Other(format!("Failed to create tag '{}': {}", name, e.message()))
+            }
+        })?;
+        Ok(())
+    }
+
+    pub fn tag_exists(&self, name: &str) -> bool {
+        self.repo.find_reference(&format!("refs/tags/{}", name)).is_ok()
+    }
+
+    /// Push a tag to `origin`
+    pub fn push_tag(&self, name: &str) -> Result<()> {
+        let mut remote = self.find_remote("origin")?;
+        push_refspec(&mut remote, &format!("refs/tags/{0}:refs/tags/{0}", name), name)?;
+        Ok(())
+    }
+
+    /// The branch's upstream as `<remote>/<branch>` (e.g. `origin/feat/WAB-1/x`),
+    /// or `None` when it has none or it hasn't been fetched or pushed yet
+    pub fn get_upstream_branch(&self, local_branch: &str) -> Result<Option<String>> {
+        let branch = self
+            .repo
+            .find_branch(local_branch, git2::BranchType::Local)
+            .context(format!("Branch '{}' not found", local_branch))?;
+
+        match branch.upstream() {
+            Ok(upstream) => Ok(upstream.name().ok().flatten().map(String::from)),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(DevFlowError::Other(format!(
+                "Failed to read upstream of '{}': {}",
+                local_branch, e
+            ))),
+        }
+    }
+
+    /// Record `remote/local` as the branch's upstream, so pushes go there and
+    /// `clean` can later tell when the remote branch has been deleted. A
+    /// branch that isn't on the remote yet is marked as unpushed, so its
+    /// missing remote-tracking ref isn't mistaken for a deleted one.
+    pub fn set_upstream(&self, local: &str, remote: &str) -> Result<()> {
+        let mut config = self.repo.config().context("Failed to open git config")?;
+        config
+            .set_str(&format!("branch.{}.remote", local), remote)
+            .and_then(|_| {
+                config.set_str(
+                    &format!("branch.{}.merge", local),
+                    &format!("refs/heads/{}", local),
+                )
+            })
+            .context(format!("Failed to set upstream for '{}'", local))?;
+
+        let tracking = format!("refs/remotes/{}/{}", remote, local);
+        if self.repo.find_reference(&tracking).is_err() {
+            config
+                .set_bool(&unpushed_key(local), true)
+                .context(format!("Failed to set upstream for '{}'", local))?;
         }
+
+        Ok(())
+    }
+
+    /// Remote name and remote branch name from the branch's upstream config
+    fn upstream_config(&self, branch_name: &str) -> Option<(String, String)> {
+        let refname = format!("refs/heads/{}", branch_name);
+        let remote = self.repo.branch_upstream_remote(&refname).ok()?;
+        let merge = self.repo.branch_upstream_merge(&refname).ok()?;
+        let remote_branch = merge.as_str()?.strip_prefix("refs/heads/")?.to_string();
+        Some((remote.as_str()?.to_string(), remote_branch))
+    }
+
+    /// Fetch from a remote with its configured refspecs, deleting
+    /// remote-tracking refs for branches that no longer exist there
+    pub fn prune_remote(&self, remote_name: &str) -> Result<()> {
+        let mut remote = self
+            .repo
+            .find_remote(remote_name)
+            .map_err(|_| DevFlowError::Other(format!("Remote '{}' not found", remote_name)))?;
+
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(remote_callbacks());
+        fetch_options.prune(git2::FetchPrune::On);
+
+        remote
+            .fetch::<&str>(&[], Some(&mut fetch_options), None)
+            .context(format!("Failed to fetch from {}", remote_name))?;
+
+        Ok(())
+    }
+
+    /// Whether the branch tracks an upstream whose remote-tracking ref is
+    /// gone. Branches that were never pushed aren't gone.
+    pub fn upstream_gone(&self, branch_name: &str) -> Result<bool> {
+        let unpushed = self
+            .repo
+            .config()
+            .and_then(|config| config.get_bool(&unpushed_key(branch_name)))
+            .unwrap_or(false);
+        if unpushed {
+            return Ok(false);
+        }
+
+        let refname = format!("refs/heads/{}", branch_name);
+        let upstream = match self.repo.branch_upstream_name(&refname) {
+            Ok(upstream) => upstream,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(false),
+            Err(e) => {
+                return Err(DevFlowError::Other(format!(
+                    "Failed to read upstream of '{}': {}",
+                    branch_name, e
+                )))
+            }
+        };
+
+        let upstream = upstream.as_str().unwrap_or_default();
+        Ok(self.repo.find_reference(upstream).is_err())
+    }
+
+    /// Commits and object bytes reachable only from `branches`: what `git gc`
+    /// can reclaim once they are deleted
+    pub fn unique_history_size(&self, branches: &[String]) -> Result<(usize, u64)> {
+        let mut walk = self.repo.revwalk().context("Failed to walk history")?;
+        let targets: Vec<String> = branches.iter().map(|b| format!("refs/heads/{}", b)).collect();
+
+        for reference in self.repo.references().context("Failed to list references")? {
+            let reference = reference.context("Failed to read reference")?;
+            let Ok(commit) = reference.peel_to_commit() else { continue };
+            let is_target = reference.name().is_some_and(|name| targets.iter().any(|t| t == name));
+            if is_target {
+                walk.push(commit.id()).context("Failed to walk history")?;
+            } else {
+                walk.hide(commit.id()).context("Failed to walk history")?;
+            }
+        }
+        if let Ok(head) = self.repo.head().and_then(|head| head.peel_to_commit()) {
+            walk.hide(head.id()).context("Failed to walk history")?;
+        }
+
+        let odb = self.repo.odb().context("Failed to open object database")?;
+        let object_size = |id| odb.read_header(id).map(|(size, _)| size as u64).unwrap_or(0);
+
+        let mut commits = 0;
+        let mut bytes = 0;
+        let mut blobs = std::collections::HashSet::new();
+        for oid in walk {
+            let commit = self.repo.find_commit(oid.context("Failed to walk history")?).context("Failed to read commit")?;
+            commits += 1;
+            bytes += object_size(commit.id());
+
+            // Count the blobs each commit introduced, once
+            let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+            let diff = self
+                .repo
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree().context("Failed to read tree")?), None)
+                .context("Failed to diff commit")?;
+            for delta in diff.deltas() {
+                let id = delta.new_file().id();
+                if !id.is_zero() && blobs.insert(id) {
+                    bytes += object_size(id);
+                }
+            }
+        }
+
+        Ok((commits, bytes))
+    }
+
+    pub fn delete_branch(&self, branch_name: &str) -> Result<()> {
+        let mut branch = self
+            .repo
+            .find_branch(branch_name, git2::BranchType::Local)
+            .context(format!("Failed to find branch '{}'", branch_name))?;
+
+        branch
+            .delete()
+            .context(format!("Failed to delete branch '{}'", branch_name))?;
+
+        Ok(())
+    }
+
+    pub fn remote_url(&self, remote_name: &str) -> Result<String> {
+        let remote = self
+            .repo
+            .find_remote(remote_name)
+            .map_err(|_| DevFlowError::Other(format!("Remote '{}' not found", remote_name)))?;
+
+        remote
+            .url()
+            .map(String::from)
+            .ok_or_else(|| DevFlowError::Other(format!("Remote '{}' URL contains invalid UTF-8", remote_name)))
+    }
+
+    /// Connect to the remote for pushing without sending anything, to check credentials
+    pub fn check_push_access(&self, remote_name: &str) -> Result<()> {
+        let mut remote = self.find_remote(remote_name)?;
+
+        remote
+            .connect_auth(git2::Direction::Push, Some(remote_callbacks()), None)
+            .map_err(|e| DevFlowError::NoPushAccess(e.message().to_string()))?;
+
+        Ok(())
+    }
+
+    /// Stage everything and commit, returning the new commit's id
+    pub fn commit(&self, message: &str) -> Result<String> {
+        let mut index = self.repo.index().context("Failed to get repository index")?;
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .context("Failed to add files to index")?;
+        index.write().context("Failed to write index")?;
+
+        self.commit_index(&mut index, message)
+    }
+
+    /// Commit only what is already staged, leaving the working tree alone
+    pub fn commit_staged(&self, message: &str) -> Result<String> {
+        let mut index = self.repo.index().context("Failed to get repository index")?;
+
+        // Before the first commit everything in the index is staged
+        let head_tree = match self.head_commit()? {
+            Some(commit) => Some(commit.tree().context("Failed to get HEAD tree")?),
+            None => None,
+        };
+
+        let staged = self
+            .repo
+            .diff_tree_to_index(head_tree.as_ref(), Some(&index), None)
+            .context("Failed to diff index against HEAD")?;
+
+        if staged.deltas().len() == 0 {
+            return Err(DevFlowError::NothingStaged);
+        }
+
+        self.commit_index(&mut index, message)
+    }
+
+    /// Replace the last commit, keeping its parents and author. The new tree
+    /// is the index, after adding everything unless `staged_only`, as with
+    /// `commit` and `commit_staged`. Without `new_message` the old message is
+    /// kept. Signed like `commit`. Returns the new commit's id.
+    pub fn amend_commit(&self, new_message: Option<&str>, staged_only: bool) -> Result<String> {
+        let head_commit = self
+            .repo
+            .head()
+            .context("Failed to get HEAD")?
+            .peel_to_commit()
+            .context("Failed to get HEAD commit")?;
+
+        let mut index = self.repo.index().context("Failed to get repository index")?;
+        if !staged_only {
+            index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+                .context("Failed to add files to index")?;
+            index.write().context("Failed to write index")?;
+        }
+        let tree_id = index.write_tree().context("Failed to write tree")?;
+        let tree = self.repo.find_tree(tree_id).context("Failed to find tree")?;
+
+        let message = match new_message {
+            Some(message) => message.to_string(),
+            None => String::from_utf8_lossy(head_commit.message_bytes()).into_owned(),
+        };
+        let parents: Vec<git2::Commit> = head_commit.parents().collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        let summary = message.lines().next().unwrap_or_default().to_string();
+
+        let id = self.write_commit(&tree, &head_commit.author(), &parents, &message, &format!("commit (amend): {}", summary))?;
+        println!("{}", format!("✓ Amended commit: {}", summary).green());
+
+        Ok(id.to_string())
+    }
+
+    /// Whether HEAD is already on `origin/<branch_name>`
+    pub fn is_head_pushed(&self, branch_name: &str) -> Result<bool> {
+        let head = self
+            .repo
+            .head()
+            .context("Failed to get HEAD")?
+            .peel_to_commit()
+            .context("Failed to get HEAD commit")?
+            .id();
+
+        self.is_pushed(branch_name, head)
+    }
+
+    /// Whether commit `id` is already on `origin/<branch_name>`
+    pub fn is_commit_pushed(&self, branch_name: &str, id: &str) -> Result<bool> {
+        let oid = git2::Oid::from_str(id).context(format!("Invalid commit id '{}'", id))?;
+        self.is_pushed(branch_name, oid)
+    }
+
+    fn is_pushed(&self, branch_name: &str, head: git2::Oid) -> Result<bool> {
+        // The branch's upstream if it has one, otherwise origin/<branch>
+        let remote_ref = match self.upstream_config(branch_name) {
+            Some((remote, remote_branch)) => format!("refs/remotes/{}/{}", remote, remote_branch),
+            None => format!("refs/remotes/origin/{}", branch_name),
+        };
+        let remote = match self.repo.refname_to_id(&remote_ref) {
+            Ok(oid) => oid,
+            Err(_) => return Ok(false),
+        };
+
+        if remote == head {
+            return Ok(true);
+        }
+
+        self.repo
+            .graph_descendant_of(remote, head)
+            .map_err(|e| DevFlowError::Other(format!("Failed to compare with {}: {}", remote_ref, e)))
+    }
+
+    /// Distinct authors of the last `limit` commits reachable from HEAD, as
+    /// (name, email), most recent first. Empty on an unborn branch.
+    pub fn recent_authors(&self, limit: usize) -> Result<Vec<(String, String)>> {
+        if self.head_commit()?.is_none() {
+            return Ok(Vec::new());
+        }
+        let mut walk = self.repo.revwalk().context("Failed to walk history")?;
+        walk.push_head().context("Failed to get HEAD")?;
+
+        let mut authors: Vec<(String, String)> = Vec::new();
+        for oid in walk.take(limit) {
+            let commit = self.repo.find_commit(oid.context("Failed to walk history")?).context("Failed to read commit")?;
+            let author = commit.author();
+            let (Some(name), Some(email)) = (author.name(), author.email()) else { continue };
+            if !authors.iter().any(|(_, known)| known.eq_ignore_ascii_case(email)) {
+                authors.push((name.to_string(), email.to_string()));
+            }
+        }
+        Ok(authors)
+    }
+
+    /// Your commits (by `user.email`) made between `start` and `end` unix
+    /// seconds on `branches`. Commits already on `base` only count when
+    /// their message mentions `ticket_key`, so a branch that was merged or
+    /// started from a busy base doesn't pick up unrelated work.
+    pub fn count_ticket_commits(&self, ticket_key: &str, branches: &[String], base: Option<&str>, (start, end): (i64, i64)) -> Result<usize> {
+        let email = self.repo.config().and_then(|config| config.get_string("user.email")).unwrap_or_default();
+        if email.is_empty() || branches.is_empty() {
+            return Ok(0);
+        }
+        let base_tip = base.and_then(|refname| self.repo.refname_to_id(refname).ok());
+        let key = ticket_key.to_uppercase();
+
+        let mut walk = self.repo.revwalk().context("Failed to walk history")?;
+        walk.set_sorting(git2::Sort::TIME).context("Failed to walk history")?;
+        for branch in branches {
+            walk.push_ref(&format!("refs/heads/{}", branch)).context(format!("Failed to find branch '{}'", branch))?;
+        }
+
+        let mut count = 0;
+        for oid in walk {
+            let commit = self.repo.find_commit(oid.context("Failed to walk history")?).context("Failed to read commit")?;
+            let time = commit.time().seconds();
+            if time < start {
+                break;
+            }
+            if time >= end || !commit.author().email().is_some_and(|author| author.eq_ignore_ascii_case(&email)) {
+                continue;
+            }
+            let on_base = base_tip.is_some_and(|tip| {
+                tip == commit.id() || self.repo.graph_descendant_of(tip, commit.id()).unwrap_or(false)
+            });
+            if !on_base || commit.message().is_some_and(|message| message.to_uppercase().contains(&key)) {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// The last `limit` commits on HEAD's first-parent line, newest first
+    pub fn get_log(&self, limit: usize) -> Result<Vec<CommitInfo>> {
+        let mut walk = self.repo.revwalk().context("Failed to walk history")?;
+        walk.push_head().context("Failed to get HEAD")?;
+        walk.simplify_first_parent().context("Failed to walk history")?;
+
+        let mut commits = Vec::new();
+        for oid in walk.take(limit) {
+            let commit = self.repo.find_commit(oid.context("Failed to walk history")?).context("Failed to read commit")?;
+            let id = commit.id().to_string();
+            commits.push(CommitInfo {
+                short_id: id[..7].to_string(),
+                id,
+                summary: commit.summary().unwrap_or_default().to_string(),
+                message: commit.message().unwrap_or_default().to_string(),
+                is_merge: commit.parent_count() > 1,
+            });
+        }
+
+        Ok(commits)
+    }
+
+    /// Replace the commits from `oldest` to `newest` (both on HEAD's
+    /// first-parent line) with one commit carrying `newest`'s tree, then
+    /// replay any later commits on top. The working tree is untouched since
+    /// HEAD's tree doesn't change. Returns the squashed commit's id.
+    pub fn squash_commits(&self, oldest: &str, newest: &str, message: &str) -> Result<String> {
+        let oldest = git2::Oid::from_str(oldest).context(format!("Invalid commit id '{}'", oldest))?;
+        let newest = git2::Oid::from_str(newest).context(format!("Invalid commit id '{}'", newest))?;
+
+        // HEAD back to `oldest`, newest first
+        let mut chain = Vec::new();
+        let mut commit = self.repo.head().context("Failed to get HEAD")?.peel_to_commit().context("HEAD is not a commit")?;
+        loop {
+            if commit.parent_count() > 1 {
+                return Err(DevFlowError::Other(format!(
+                    "Can't squash across merge commit {}",
+                    &commit.id().to_string()[..7]
+                )));
+            }
+            let id = commit.id();
+            let parent = commit.parent(0).ok();
+            chain.push(commit);
+            if id == oldest {
+                break;
+            }
+            commit = parent.ok_or_else(|| DevFlowError::Other("Commit to squash is not on the current branch".to_string()))?;
+        }
+
+        let newest_index = chain
+            .iter()
+            .position(|c| c.id() == newest)
+            .ok_or_else(|| DevFlowError::Other("Commits to squash are not in order on the current branch".to_string()))?;
+
+        let base = chain.last().and_then(|c| c.parent(0).ok());
+        let signature = self.repo.signature().context("Failed to get git signature (check user.name and user.email)")?;
+        let tree = chain[newest_index].tree().context("Failed to read tree")?;
+        let parents: Vec<&git2::Commit> = base.iter().collect();
+        let squashed = self
+            .repo
+            .commit(None, &signature, &signature, message, &tree, &parents)
+            .context("Failed to create squashed commit")?;
+
+        let mut tip = squashed;
+        for later in chain[..newest_index].iter().rev() {
+            let parent = self.repo.find_commit(tip).context("Failed to read commit")?;
+            tip = self
+                .repo
+                .commit(
+                    None,
+                    &later.author(),
+                    &later.committer(),
+                    later.message().unwrap_or_default(),
+                    &later.tree().context("Failed to read tree")?,
+                    &[&parent],
+                )
+                .context("Failed to replay commit")?;
+        }
+
+        let head = self.repo.head().context("Failed to get HEAD")?;
+        let head_name = head.name().ok_or_else(|| DevFlowError::Other("HEAD is detached".to_string()))?;
+        self.repo
+            .reference(head_name, tip, true, "devflow squash")
+            .context("Failed to update branch")?;
+
+        Ok(squashed.to_string())
+    }
+
+    /// Bring `onto` (any revspec, e.g. `origin/main`) into the current branch.
+    /// Branches without commits of their own are fast-forwarded either way.
+    pub fn sync(&self, onto: &str, mode: SyncMode) -> Result<SyncOutcome> {
+        let branch = self.current_branch()?;
+        let head = self.head_commit()?.ok_or(DevFlowError::EmptyRepository)?;
+        let base = self
+            .repo
+            .revparse_single(onto)
+            .context(format!("Failed to resolve '{}'", onto))?
+            .peel_to_commit()
+            .context(format!("'{}' does not point to a commit", onto))?;
+
+        if head.id() == base.id() || self.repo.graph_descendant_of(head.id(), base.id()).unwrap_or(false) {
+            return Ok(SyncOutcome::UpToDate);
+        }
+        if self.repo.graph_descendant_of(base.id(), head.id()).unwrap_or(false) {
+            self.repo
+                .checkout_tree(base.as_object(), None)
+                .context(format!("Failed to update the working tree to {}", onto))?;
+            self.repo
+                .reference(&format!("refs/heads/{}", branch), base.id(), true, "devflow sync: fast-forward")
+                .context(format!("Failed to update '{}'", branch))?;
+            return Ok(SyncOutcome::FastForwarded);
+        }
+
+        let annotated = self
+            .repo
+            .find_annotated_commit(base.id())
+            .context(format!("Failed to look up '{}'", onto))?;
+        match mode {
+            SyncMode::Rebase => {
+                let mut rebase = self
+                    .repo
+                    .rebase(None, Some(&annotated), None, None)
+                    .context(format!("Failed to start rebasing onto {}", onto))?;
+                self.run_rebase(&mut rebase)
+            }
+            SyncMode::Merge => {
+                self.repo
+                    .merge(&[&annotated], None, None)
+                    .context(format!("Failed to merge {}", onto))?;
+                let message = format!("Merge {} into {}", onto, branch);
+                let mut index = self.repo.index().context("Failed to get repository index")?;
+                if index.has_conflicts() {
+                    let paths = conflicted_paths(&index)?;
+                    // Used by `git commit` once the conflicts are resolved
+                    let conflicts: String = paths.iter().map(|path| format!("#\t{}\n", path)).collect();
+                    std::fs::write(self.repo.path().join("MERGE_MSG"), format!("{}\n\n# Conflicts:\n{}", message, conflicts))
+                        .context("Failed to write MERGE_MSG")?;
+                    return Ok(SyncOutcome::Conflicts(paths));
+                }
+
+                let tree_id = index.write_tree().context("Failed to write tree")?;
+                let tree = self.repo.find_tree(tree_id).context("Failed to find tree")?;
+                let signature = self.repo.signature()
+                    .context("Failed to get git signature. Make sure git user.name and user.email are configured")?;
+                self.repo
+                    .commit(Some("HEAD"), &signature, &signature, &message, &tree, &[&head, &base])
+                    .context("Failed to create merge commit")?;
+                self.repo.cleanup_state().context("Failed to clean up merge state")?;
+                Ok(SyncOutcome::Merged)
+            }
+        }
+    }
+
+    /// Apply the remaining rebase operations, stopping at the first conflict
+    fn run_rebase(&self, rebase: &mut git2::Rebase<'_>) -> Result<SyncOutcome> {
+        let signature = self.repo.signature()
+            .context("Failed to get git signature. Make sure git user.name and user.email are configured")?;
+
+        let mut replayed = 0;
+        while let Some(operation) = rebase.next() {
+            operation.context("Failed to apply commit")?;
+            let index = self.repo.index().context("Failed to get repository index")?;
+            if index.has_conflicts() {
+                self.write_rebase_todo(rebase)?;
+                return Ok(SyncOutcome::Conflicts(conflicted_paths(&index)?));
+            }
+            match rebase.commit(None, &signature, None) {
+                Ok(_) => replayed += 1,
+                // The base already contains this change
+                Err(e) if e.code() == git2::ErrorCode::Applied => {}
+                Err(e) => return Err(anyhow::Error::new(e).context("Failed to commit rebased change").into()),
+            }
+        }
+
+        rebase.finish(Some(&signature)).context("Failed to finish rebase")?;
+        Ok(SyncOutcome::Rebased(replayed))
+    }
+
+    /// libgit2 records only the commits to replay, but `git rebase --continue`
+    /// also reads the todo list and the stopped commit's message and author.
+    /// Write those too so git can finish a rebase that stopped on conflicts.
+    fn write_rebase_todo(&self, rebase: &mut git2::Rebase<'_>) -> Result<()> {
+        let current = rebase.operation_current().unwrap_or(0);
+        let mut done = String::new();
+        let mut todo = String::new();
+        let mut stopped = None;
+        for n in 0..rebase.len() {
+            let Some(operation) = rebase.nth(n) else { continue };
+            let commit = self.repo.find_commit(operation.id()).context("Failed to read commit being rebased")?;
+            let line = format!("pick {} {}\n", commit.id(), commit.summary().unwrap_or_default());
+            if n < current {
+                done.push_str(&line);
+            } else if n == current {
+                done.push_str(&line);
+                stopped = Some(commit);
+            } else {
+                todo.push_str(&line);
+            }
+        }
+        let Some(stopped) = stopped else { return Ok(()) };
+
+        let author = stopped.author();
+        let quote = |value: &str| format!("'{}'", value.replace('\'', "'\\''"));
+        let offset = author.when().offset_minutes();
+        let author_script = format!(
+            "GIT_AUTHOR_NAME={}\nGIT_AUTHOR_EMAIL={}\nGIT_AUTHOR_DATE={}\n",
+            quote(&String::from_utf8_lossy(author.name_bytes())),
+            quote(&String::from_utf8_lossy(author.email_bytes())),
+            quote(&format!(
+                "@{} {}{:02}{:02}",
+                author.when().seconds(),
+                if offset < 0 { '-' } else { '+' },
+                offset.abs() / 60,
+                offset.abs() % 60
+            )),
+        );
+
+        let dir = self.repo.path().join("rebase-merge");
+        let files = [
+            ("done", done),
+            ("git-rebase-todo", todo),
+            ("message", String::from_utf8_lossy(stopped.message_bytes()).into_owned()),
+            ("author-script", author_script),
+        ];
+        for (name, content) in files {
+            std::fs::write(dir.join(name), content).context(format!("Failed to write rebase state '{}'", name))?;
+        }
+        Ok(())
+    }
+
+    /// The rebase or merge left in progress, e.g. by `sync` stopping on conflicts
+    pub fn sync_in_progress(&self) -> Option<SyncMode> {
+        match self.repo.state() {
+            git2::RepositoryState::Rebase
+            | git2::RepositoryState::RebaseInteractive
+            | git2::RepositoryState::RebaseMerge => Some(SyncMode::Rebase),
+            git2::RepositoryState::Merge => Some(SyncMode::Merge),
+            _ => None,
+        }
+    }
+
+    /// Abandon the rebase or merge in progress and restore the branch as it was
+    pub fn sync_abort(&self) -> Result<SyncMode> {
+        let mode = self
+            .sync_in_progress()
+            .ok_or_else(|| DevFlowError::Other("No rebase or merge in progress".to_string()))?;
+
+        match mode {
+            SyncMode::Rebase => {
+                let mut rebase = self.repo.open_rebase(None).context("Failed to open the rebase in progress")?;
+                rebase.abort().context("Failed to abort rebase")?;
+            }
+            SyncMode::Merge => {
+                let head = self.head_commit()?.ok_or(DevFlowError::EmptyRepository)?;
+                self.repo
+                    .reset(head.as_object(), git2::ResetType::Hard, None)
+                    .context("Failed to reset the merge")?;
+                self.repo.cleanup_state().context("Failed to clean up merge state")?;
+            }
+        }
+
+        Ok(mode)
+    }
+
+    /// Names of all local branches
+    pub fn list_local_branches(&self) -> Result<Vec<String>> {
+        let branches = self
+            .repo
+            .branches(Some(git2::BranchType::Local))
+            .context("Failed to list branches")?;
+
+        let mut names = Vec::new();
+        for branch in branches {
+            let (branch, _) = branch.context("Failed to read branch")?;
+            if let Some(name) = branch.name().context("Failed to read branch name")? {
+                names.push(name.to_string());
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Commits only on HEAD and only on `base` (a revspec such as
+    /// `refs/remotes/origin/main`): `(ahead, behind)`
+    pub fn ahead_behind(&self, base: &str) -> Result<(usize, usize)> {
+        let head = self.repo.head().context("Failed to get HEAD")?.peel_to_commit().context("HEAD is not a commit")?;
+        let base = self
+            .repo
+            .revparse_single(base)
+            .context(format!("Failed to resolve '{}'", base))?
+            .peel_to_commit()
+            .context(format!("'{}' does not point to a commit", base))?;
+
+        Ok(self
+            .repo
+            .graph_ahead_behind(head.id(), base.id())
+            .context("Failed to compare the branch with its base")?)
+    }
+
+    /// Commits HEAD is ahead of and behind `local`'s upstream; `None` when it
+    /// has none, or its remote-tracking ref doesn't exist yet
+    pub fn upstream_ahead_behind(&self, local: &str) -> Result<Option<(usize, usize)>> {
+        match self.get_upstream_branch(local)? {
+            Some(upstream) => self.ahead_behind(&upstream).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Files changed on HEAD since it diverged from `base` (a revspec such
+    /// as `refs/remotes/origin/main`)
+    pub fn changed_files_since(&self, base: &str) -> Result<Vec<String>> {
+        let head = self.repo.head().context("Failed to get HEAD")?.peel_to_commit().context("HEAD is not a commit")?;
+        let base = self
+            .repo
+            .revparse_single(base)
+            .context(format!("Failed to resolve '{}'", base))?
+            .peel_to_commit()
+            .context(format!("'{}' does not point to a commit", base))?;
+
+        let merge_base = self
+            .repo
+            .merge_base(base.id(), head.id())
+            .context("Branch has no common ancestor with its base")?;
+        let merge_base_tree = self.repo.find_commit(merge_base).and_then(|c| c.tree()).context("Failed to read merge base")?;
+        let head_tree = head.tree().context("Failed to read HEAD tree")?;
+
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&merge_base_tree), Some(&head_tree), None)
+            .context("Failed to diff branch against its base")?;
+
+        Ok(diff
+            .deltas()
+            .filter_map(|d| d.new_file().path().or_else(|| d.old_file().path()))
+            .map(|p| p.display().to_string())
+            .collect())
+    }
+
+    /// Root of the working tree
+    pub fn workdir(&self) -> Option<&std::path::Path> {
+        self.repo.workdir()
+    }
+
+    /// The repository's `.git` directory, which identifies it across runs
+    pub fn git_dir(&self) -> &std::path::Path {
+        self.repo.path()
+    }
+
+    /// Stash tracked and untracked changes, returning the stash commit ID
+    pub fn stash_save(&mut self, message: &str) -> Result<String> {
+        let signature = self.repo.signature()
+            .context("Failed to get git signature. Make sure git user.name and user.email are configured")?;
+
+        let oid = self
+            .repo
+            .stash_save(&signature, message, Some(git2::StashFlags::INCLUDE_UNTRACKED))
+            .context("Failed to stash changes")?;
+
+        Ok(oid.to_string())
+    }
+
+    /// Files changed in a stash, including untracked files it saved
+    pub fn stash_files(&self, stash_id: &str) -> Result<Vec<String>> {
+        let stash = git2::Oid::from_str(stash_id)
+            .and_then(|oid| self.repo.find_commit(oid))
+            .context("Stash not found")?;
+
+        let tree = stash.tree().context("Failed to read stash tree")?;
+        let base = stash.parent(0).and_then(|p| p.tree()).context("Failed to read stash base")?;
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&base), Some(&tree), None)
+            .context("Failed to diff stash")?;
+
+        let mut files: Vec<String> = diff
+            .deltas()
+            .filter_map(|d| d.new_file().path().map(|p| p.display().to_string()))
+            .collect();
+
+        // Untracked files live in the stash's third parent
+        if let Ok(untracked) = stash.parent(2).and_then(|p| p.tree()) {
+            files.extend(untracked.iter().filter_map(|entry| entry.name().map(str::to_string)));
+        }
+
+        Ok(files)
+    }
+
+    /// Apply and drop the stash with this ID. `Ok(false)` if it is no
+    /// longer in the stash list (e.g. dropped by hand).
+    pub fn stash_pop(&mut self, stash_id: &str) -> Result<bool> {
+        let target = git2::Oid::from_str(stash_id).context("Invalid stash ID")?;
+
+        let mut position = None;
+        self.repo
+            .stash_foreach(|index, _, oid| {
+                if *oid == target {
+                    position = Some(index);
+                }
+                position.is_none()
+            })
+            .context("Failed to read stash list")?;
+
+        match position {
+            Some(index) => {
+                self.repo.stash_pop(index, None).context("Failed to restore stashed changes")?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Directory git runs hooks from, honouring `core.hooksPath`
+    pub fn hooks_dir(&self) -> Result<std::path::PathBuf> {
+        let config = self.repo.config().context("Failed to read git config")?;
+
+        match config.get_path("core.hooksPath") {
+            Ok(path) if path.is_absolute() => Ok(path),
+            // Relative hooksPath is resolved against the working tree root
+            Ok(path) => Ok(self.repo.workdir().unwrap_or_else(|| self.repo.path()).join(path)),
+            Err(_) => Ok(self.repo.path().join("hooks")),
+        }
+    }
+
+    /// How the next commit gets signed, or `None` to leave it unsigned
+    fn signer(&self) -> Result<Option<Signer>> {
+        let config = self.repo.config().context("Failed to open git config")?;
+        let gpg_override = match &self.signing {
+            Signing::Never => return Ok(None),
+            Signing::GitConfig if !config.get_bool("commit.gpgsign").unwrap_or(false) => return Ok(None),
+            Signing::GitConfig => None,
+            Signing::Always(program) => Some(program.clone()),
+        };
+        signer_from_config(&config, gpg_override).map(Some)
+    }
+
+    /// Create a commit, signed if signing is on, and point HEAD's branch (or
+    /// a detached HEAD) at it. The committer is the configured identity.
+    fn write_commit(
+        &self,
+        tree: &git2::Tree,
+        author: &git2::Signature,
+        parents: &[&git2::Commit],
+        message: &str,
+        reflog: &str,
+    ) -> Result<git2::Oid> {
+        let committer = self.repo.signature()
+            .context("Failed to get git signature. Make sure git user.name and user.email are configured")?;
+
+        let id = match self.signer()? {
+            None => self
+                .repo
+                .commit(None, author, &committer, message, tree, parents)
+                .context("Failed to create commit")?,
+            Some(signer) => {
+                let buffer = self
+                    .repo
+                    .commit_create_buffer(author, &committer, message, tree, parents)
+                    .context("Failed to create commit")?;
+                let content = buffer
+                    .as_str()
+                    .ok_or_else(|| DevFlowError::Other("Commit is not valid UTF-8 and can't be signed".to_string()))?;
+
+                let gpg_signature = signer.sign(content)?;
+                self.repo
+                    .commit_signed(content, &gpg_signature, Some("gpgsig"))
+                    .context("Failed to create signed commit")?
+            }
+        };
+
+        // libgit2 only moves HEAD itself for unsigned commits whose first
+        // parent is the old tip, which rules out amends
+        let head = self.repo.find_reference("HEAD").context("Failed to get HEAD")?;
+        match head.symbolic_target() {
+            Some(branch) => {
+                self.repo.reference(branch, id, true, reflog).context("Failed to update branch")?;
+            }
+            None => self.repo.set_head_detached(id).context("Failed to update HEAD")?,
+        }
+
+        Ok(id)
+    }
+
+    /// Commit the index on top of HEAD, returning the new commit's id. On an
+    /// unborn branch this is the root commit, with no parents.
+    fn commit_index(&self, index: &mut git2::Index, message: &str) -> Result<String> {
+        let tree_id = index.write_tree().context("Failed to write tree")?;
+        let tree = self.repo.find_tree(tree_id).context("Failed to find tree")?;
+
+        let parent_commit = self.head_commit()?;
+        let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
+
+        let signature = self.repo.signature()
+            .context("Failed to get git signature. Make sure git user.name and user.email are configured")?;
+
+        let reflog = format!("commit: {}", message.lines().next().unwrap_or_default());
+        let id = self.write_commit(&tree, &signature, &parents, message, &reflog)?;
+
+        say!("{}", format!("✓ Created commit: {}", message).green());
+
+        Ok(id.to_string())
+    }
+}
+
+/// Async versions of the calls that can take a while: network round trips,
+/// signing, and status on a large work tree. Each runs on a blocking thread
+/// so spinners and the requests running alongside keep going. A `Repository`
+/// can't be shared between threads, so the task opens a handle of its own.
+impl GitClient {
+    async fn blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&GitClient) -> Result<T> + Send + 'static,
+    {
+        let git = self.reopen()?;
+        tokio::task::spawn_blocking(move || f(&git))
+            .await
+            .map_err(|e| DevFlowError::Other(format!("Git task failed: {}", e)))?
+    }
+
+    /// `push`, or `push_force_with_lease` when `force_with_lease` is set
+    pub async fn push_async(&self, branch_name: &str, force_with_lease: bool) -> Result<Option<String>> {
+        let branch_name = branch_name.to_string();
+        self.blocking(move |git| {
+            if force_with_lease {
+                git.push_force_with_lease(&branch_name)
+            } else {
+                git.push(&branch_name)
+            }
+        })
+        .await
+    }
+
+    pub async fn push_tag_async(&self, name: &str) -> Result<()> {
+        let name = name.to_string();
+        self.blocking(move |git| git.push_tag(&name)).await
+    }
+
+    pub async fn fetch_remote_async(&self, remote_name: &str, branch_name: &str) -> Result<()> {
+        let (remote_name, branch_name) = (remote_name.to_string(), branch_name.to_string());
+        self.blocking(move |git| git.fetch_remote(&remote_name, &branch_name)).await
+    }
+
+    pub async fn prune_remote_async(&self, remote_name: &str) -> Result<()> {
+        let remote_name = remote_name.to_string();
+        self.blocking(move |git| git.prune_remote(&remote_name)).await
+    }
+
+    pub async fn check_push_access_async(&self, remote_name: &str) -> Result<()> {
+        let remote_name = remote_name.to_string();
+        self.blocking(move |git| git.check_push_access(&remote_name)).await
+    }
+
+    pub async fn status_report_async(&self) -> Result<StatusReport> {
+        self.blocking(|git| git.status_report()).await
+    }
+
+    /// `commit_staged` with `staged`, otherwise `commit`
+    pub async fn commit_async(&self, message: &str, staged: bool) -> Result<String> {
+        let message = message.to_string();
+        self.blocking(move |git| if staged { git.commit_staged(&message) } else { git.commit(&message) }).await
+    }
+
+    pub async fn amend_commit_async(&self, new_message: Option<&str>, staged_only: bool) -> Result<String> {
+        let new_message = new_message.map(str::to_string);
+        self.blocking(move |git| git.amend_commit(new_message.as_deref(), staged_only)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_client_in_repo() {
+        let result = GitClient::new();
+        assert!(result.is_ok(), "Should successfully open git repo");
+    }
+
+    #[test]
+    fn test_current_branch() {
+        if let Ok(git) = GitClient::new() {
+            let result = git.current_branch();
+            match result {
+                Ok(branch) => assert!(!branch.is_empty(), "Branch name should not be empty"),
+                Err(_) => {
+                    // It's okay if we can't get branch (e.g., new repo with no commits)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url_scp_style() {
+        let info = parse_remote_url("git@github.com:Ilia01/devflow.git").unwrap();
+        assert_eq!(info.host, "github.com");
+        assert_eq!(info.path, "Ilia01/devflow");
+    }
+
+    #[test]
+    fn test_parse_remote_url_ssh_with_port() {
+        let info = parse_remote_url("ssh://git@git.example.com:2222/group/sub/repo.git").unwrap();
+        assert_eq!(info.host, "git.example.com");
+        assert_eq!(info.path, "group/sub/repo");
+    }
+
+    #[test]
+    fn test_parse_remote_url_https() {
+        let info = parse_remote_url("https://gitlab.com/group/repo").unwrap();
+        assert_eq!(info.host, "gitlab.com");
+        assert_eq!(info.path, "group/repo");
+    }
+
+    #[test]
+    fn test_parse_remote_url_invalid() {
+        assert!(parse_remote_url("not a remote").is_none());
+        assert!(parse_remote_url("https://host.only").is_none());
+    }
+
+    /// Commit an empty tree to `refname` in a scratch repository
+    fn commit_on(repo: &Repository, refname: &str, message: &str) -> git2::Oid {
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .find_reference(refname)
+            .ok()
+            .and_then(|r| r.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some(refname), &signature, &signature, message, &tree, &parent_refs)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_create_branch_from_fetched_base() {
+        let origin_dir = tempfile::tempdir().unwrap();
+        let origin = Repository::init(origin_dir.path()).unwrap();
+        commit_on(&origin, "refs/heads/main", "initial");
+        let develop_tip = commit_on(&origin, "refs/heads/develop", "develop work");
+
+        let work_dir = tempfile::tempdir().unwrap();
+        let work = Repository::init(work_dir.path()).unwrap();
+        work.remote("origin", origin_dir.path().to_str().unwrap()).unwrap();
+        let main_tip = commit_on(&work, "refs/heads/main", "local main");
+        work.set_head("refs/heads/main").unwrap();
+
+        let git = GitClient::open(work_dir.path()).unwrap();
+        git.fetch_remote("origin", "develop").unwrap();
+        let base = git.resolve_base("develop").unwrap();
+        assert_eq!(base, "refs/remotes/origin/develop");
+
+        git.create_branch_from("feat/WAB-1/thing", &base).unwrap();
+
+        assert_eq!(git.current_branch().unwrap(), "feat/WAB-1/thing");
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap().id();
+        assert_eq!(head, develop_tip);
+        assert_ne!(head, main_tip);
+    }
+
+    #[test]
+    fn test_fast_forward_from_origin() {
+        let origin_dir = tempfile::tempdir().unwrap();
+        let origin = Repository::init(origin_dir.path()).unwrap();
+        commit_on(&origin, "refs/heads/main", "initial");
+
+        let work_dir = tempfile::tempdir().unwrap();
+        let work = Repository::init(work_dir.path()).unwrap();
+        work.remote("origin", origin_dir.path().to_str().unwrap()).unwrap();
+        let git = GitClient::open(work_dir.path()).unwrap();
+
+        // Missing locally: created from origin
+        git.fast_forward_from_origin("main").unwrap();
+        let merged = commit_on(&origin, "refs/heads/main", "merged PR");
+        git.fast_forward_from_origin("main").unwrap();
+        let local = git.repo.revparse_single("refs/heads/main").unwrap().id();
+        assert_eq!(local, merged);
+
+        // Diverged: refuses to move
+        commit_on(&work, "refs/heads/main", "local only");
+        commit_on(&origin, "refs/heads/main", "another PR");
+        assert!(git.fast_forward_from_origin("main").is_err());
+    }
+
+    #[test]
+    fn test_default_branch_falls_back_to_local_main() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_on(&repo, "refs/heads/main", "initial");
+
+        let git = GitClient::open(dir.path()).unwrap();
+        assert_eq!(git.default_branch(), Some("main".to_string()));
+        assert_eq!(git.resolve_base("main").unwrap(), "refs/heads/main");
+        assert!(git.resolve_base("nope").is_err());
+    }
+
+    /// Scratch repository with one commit on main and a configured identity
+    fn scratch_repo() -> (tempfile::TempDir, GitClient) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        // Keep a global commit.gpgsign from reaching the tests
+        config.set_bool("commit.gpgsign", false).unwrap();
+        commit_on(&repo, "refs/heads/main", "initial");
+        repo.set_head("refs/heads/main").unwrap();
+        (dir, GitClient { repo, signing: Signing::GitConfig })
+    }
+
+    /// Freshly initialised repository on an unborn `main`
+    fn unborn_repo() -> (tempfile::TempDir, GitClient) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        config.set_bool("commit.gpgsign", false).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        (dir, GitClient { repo, signing: Signing::GitConfig })
+    }
+
+    #[test]
+    fn test_unborn_branch_name_and_create_branch() {
+        let (_dir, git) = unborn_repo();
+        assert!(git.is_unborn());
+        assert_eq!(git.current_branch().unwrap(), "main");
+
+        let result = git.create_branch_from("feat/WAB-1/x", "HEAD");
+        assert!(matches!(result, Err(DevFlowError::EmptyRepository)));
+    }
+
+    #[test]
+    fn test_initial_commit_has_no_parents() {
+        let (dir, git) = unborn_repo();
+        std::fs::write(dir.path().join("README.md"), "hello").unwrap();
+        git.commit("Initial commit").unwrap();
+
+        let head = git.repo.head().unwrap();
+        assert_eq!(head.name(), Some("refs/heads/main"));
+        assert_eq!(head.peel_to_commit().unwrap().parent_count(), 0);
+        assert!(!git.is_unborn());
+        git.create_branch_from("feat/WAB-1/x", "HEAD").unwrap();
+    }
+
+    #[test]
+    fn test_initial_commit_staged() {
+        let (dir, git) = unborn_repo();
+        assert!(matches!(git.commit_staged("Empty"), Err(DevFlowError::NothingStaged)));
+
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let mut index = git.repo.index().unwrap();
+        index.add_path(std::path::Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+
+        git.commit_staged("Initial commit").unwrap();
+        assert_eq!(git.repo.head().unwrap().peel_to_commit().unwrap().parent_count(), 0);
+    }
+
+    #[test]
+    fn test_current_branch_detached_head() {
+        let (_dir, git) = scratch_repo();
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap().id();
+        git.repo.set_head_detached(head).unwrap();
+
+        match git.current_branch() {
+            Err(DevFlowError::DetachedHead(sha)) => assert_eq!(sha, head.to_string()[..7]),
+            other => panic!("expected DetachedHead, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_commit_staged_only_commits_index() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("staged.txt"), "staged").unwrap();
+        std::fs::write(dir.path().join("unstaged.txt"), "unstaged").unwrap();
+
+        let mut index = git.repo.index().unwrap();
+        index.add_path(std::path::Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+
+        git.commit_staged("Add staged file").unwrap();
+
+        let tree = git.repo.head().unwrap().peel_to_tree().unwrap();
+        assert!(tree.get_name("staged.txt").is_some());
+        assert!(tree.get_name("unstaged.txt").is_none());
+    }
+
+    #[test]
+    fn test_commit_staged_with_empty_index_errors() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("unstaged.txt"), "unstaged").unwrap();
+
+        let result = git.commit_staged("Nothing here");
+        assert!(matches!(result, Err(DevFlowError::NothingStaged)));
+    }
+
+    #[test]
+    fn test_amend_commit_rewords_and_includes_index() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("First").unwrap();
+        let before = git.repo.head().unwrap().peel_to_commit().unwrap();
+
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        let mut index = git.repo.index().unwrap();
+        index.add_path(std::path::Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+
+        std::fs::write(dir.path().join("c.txt"), "c").unwrap();
+
+        git.amend_commit(Some("Reworded"), true).unwrap();
+
+        let after = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_ne!(after.id(), before.id());
+        assert_eq!(after.message(), Some("Reworded"));
+        assert_eq!(after.parent_id(0).unwrap(), before.parent_id(0).unwrap());
+        assert!(after.tree().unwrap().get_name("b.txt").is_some());
+        assert!(after.tree().unwrap().get_name("c.txt").is_none());
+    }
+
+    #[test]
+    fn test_amend_commit_adds_everything_and_keeps_author() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("First\n\nWAB-1: https://jira.example.com/browse/WAB-1").unwrap();
+        let before = git.repo.head().unwrap().peel_to_commit().unwrap();
+
+        // Someone else committing the amend keeps the original author
+        git.repo.config().unwrap().set_str("user.name", "Other").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        git.amend_commit(None, false).unwrap();
+
+        let after = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(after.message(), before.message());
+        assert_eq!(after.author().name(), Some("Test"));
+        assert_eq!(after.committer().name(), Some("Other"));
+        assert_eq!(after.parent_ids().collect::<Vec<_>>(), before.parent_ids().collect::<Vec<_>>());
+        assert!(after.tree().unwrap().get_name("b.txt").is_some());
+        assert_eq!(git.current_branch().unwrap(), "main");
+        assert!(git.is_clean().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_amend_commit_is_signed() {
+        let (dir, git) = scratch_repo();
+        let tools = tempfile::tempdir().unwrap();
+        let program = fake_gpg(tools.path());
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Unsigned").unwrap();
+
+        let git = git.with_signing(&program);
+        git.repo.config().unwrap().set_str("user.signingkey", "ABC123").unwrap();
+        git.amend_commit(Some("Signed"), true).unwrap();
+        assert_eq!(head_signature(&git).as_deref(), Some("-----BEGIN PGP SIGNATURE-----"));
+    }
+
+    #[test]
+    fn test_amend_commit_keeps_message() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Keep me").unwrap();
+
+        git.amend_commit(None, true).unwrap();
+
+        let after = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(after.message(), Some("Keep me"));
+    }
+
+    #[test]
+    fn test_recent_authors() {
+        let (dir, git) = scratch_repo();
+        let mut config = git.repo.config().unwrap();
+        for (name, email) in [("Alice Example", "alice@example.com"), ("Bob", "bob@example.com"), ("Alice E.", "ALICE@example.com")] {
+            config.set_str("user.name", name).unwrap();
+            config.set_str("user.email", email).unwrap();
+            std::fs::write(dir.path().join("a.txt"), name).unwrap();
+            git.commit(name).unwrap();
+        }
+
+        assert_eq!(
+            git.recent_authors(10).unwrap(),
+            vec![
+                ("Alice E.".to_string(), "ALICE@example.com".to_string()),
+                ("Bob".to_string(), "bob@example.com".to_string()),
+                ("Test".to_string(), "test@example.com".to_string()),
+            ]
+        );
+        assert_eq!(git.recent_authors(1).unwrap().len(), 1);
+
+        let (_dir, unborn) = unborn_repo();
+        assert!(unborn.recent_authors(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_count_ticket_commits() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("a.txt"), "base").unwrap();
+        git.commit("WAB-1: Scaffold the endpoint").unwrap();
+
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        git.repo.branch("feat/WAB-1/login", &head, false).unwrap();
+        git.repo.set_head("refs/heads/feat/WAB-1/login").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "mine").unwrap();
+        git.commit("Handle expired sessions").unwrap();
+
+        let mut config = git.repo.config().unwrap();
+        config.set_str("user.email", "bob@example.com").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "bob's").unwrap();
+        git.commit("Review fixes").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
+        let branches = vec!["feat/WAB-1/login".to_string()];
+        let window = (now - 3_600, now + 3_600);
+        // The branch commit, plus the base commit that names the ticket; not
+        // "initial" on main or Bob's commit
+        assert_eq!(git.count_ticket_commits("wab-1", &branches, Some("refs/heads/main"), window).unwrap(), 2);
+        assert_eq!(git.count_ticket_commits("WAB-2", &branches, Some("refs/heads/main"), window).unwrap(), 1);
+        assert_eq!(git.count_ticket_commits("WAB-2", &branches, None, window).unwrap(), 3);
+        assert_eq!(git.count_ticket_commits("WAB-1", &branches, None, (now - 7_200, now - 3_600)).unwrap(), 0);
+        assert_eq!(git.count_ticket_commits("WAB-1", &[], None, window).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_is_head_pushed() {
+        let (_dir, git) = scratch_repo();
+        assert!(!git.is_head_pushed("main").unwrap());
+
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap().id();
+        git.repo
+            .reference("refs/remotes/origin/main", head, true, "test")
+            .unwrap();
+        assert!(git.is_head_pushed("main").unwrap());
+
+        // An upstream under another name or remote is what counts
+        let mut config = git.repo.config().unwrap();
+        config.set_str("branch.main.remote", "fork").unwrap();
+        config.set_str("branch.main.merge", "refs/heads/trunk").unwrap();
+        assert!(!git.is_head_pushed("main").unwrap());
+        git.repo.reference("refs/remotes/fork/trunk", head, true, "test").unwrap();
+        assert!(git.is_head_pushed("main").unwrap());
+    }
+
+    #[test]
+    fn test_status_report_sections() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("tracked.txt"), "v1").unwrap();
+        std::fs::write(dir.path().join("old.txt"), "rename me, I am long enough to be detected").unwrap();
+        git.commit("Base").unwrap();
+
+        // Staged: new file and a rename; unstaged: edit to a staged file
+        std::fs::write(dir.path().join("staged.txt"), "staged").unwrap();
+        std::fs::rename(dir.path().join("old.txt"), dir.path().join("new.txt")).unwrap();
+        std::fs::write(dir.path().join("tracked.txt"), "v2").unwrap();
+        let mut index = git.repo.index().unwrap();
+        index.add_path(std::path::Path::new("staged.txt")).unwrap();
+        index.remove_path(std::path::Path::new("old.txt")).unwrap();
+        index.add_path(std::path::Path::new("new.txt")).unwrap();
+        index.add_path(std::path::Path::new("tracked.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::write(dir.path().join("tracked.txt"), "v3").unwrap();
+        std::fs::write(dir.path().join("untracked.txt"), "new").unwrap();
+
+        let report = git.status_report().unwrap();
+        let staged: Vec<_> = report.staged.iter().map(|f| (f.path.as_str(), f.from.as_deref(), f.change)).collect();
+        assert_eq!(
+            staged,
+            vec![
+                ("new.txt", Some("old.txt"), FileChange::Renamed),
+                ("staged.txt", None, FileChange::Added),
+                ("tracked.txt", None, FileChange::Modified),
+            ]
+        );
+        assert_eq!(report.unstaged, vec![FileStatus { path: "tracked.txt".to_string(), from: None, change: FileChange::Modified }]);
+        assert_eq!(report.untracked, vec!["untracked.txt"]);
+        assert!(report.conflicted.is_empty());
+        assert_eq!(report.upstream, None);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_status_report_upstream_and_clean() {
+        let (dir, git) = scratch_repo();
+        git.repo.remote("origin", "https://example.com/repo.git").unwrap();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap().id();
+        git.repo.reference("refs/remotes/origin/feat/WAB-1/x", head, true, "test").unwrap();
+        git.set_upstream("feat/WAB-1/x", "origin").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Ahead").unwrap();
+
+        let report = git.status_report().unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.upstream.as_deref(), Some("origin/feat/WAB-1/x"));
+        assert_eq!((report.ahead, report.behind), (1, 0));
+    }
+
+    #[test]
+    fn test_hooks_dir_defaults_to_git_dir() {
+        let (_dir, git) = scratch_repo();
+        assert_eq!(git.hooks_dir().unwrap(), git.repo.path().join("hooks"));
+    }
+
+    #[test]
+    fn test_hooks_dir_respects_core_hooks_path() {
+        let (_dir, git) = scratch_repo();
+        git.repo.config().unwrap().set_str("core.hooksPath", ".githooks").unwrap();
+
+        let expected = git.repo.workdir().unwrap().join(".githooks");
+        assert_eq!(git.hooks_dir().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_list_local_branches() {
+        let (_dir, git) = scratch_repo();
+        git.create_branch_from("feat/WAB-1/thing", "HEAD").unwrap();
+
+        let mut branches = git.list_local_branches().unwrap();
+        branches.sort();
+        assert_eq!(branches, vec!["feat/WAB-1/thing", "main"]);
+    }
+
+    #[test]
+    fn test_upstream_gone() {
+        let (_dir, git) = scratch_repo();
+        git.repo.remote("origin", "https://example.com/repo.git").unwrap();
+        git.create_branch_from("feat/WAB-1/pushed", "HEAD").unwrap();
+        git.create_branch_from("feat/WAB-2/local", "HEAD").unwrap();
+
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap().id();
+        let mut tracking = git
+            .repo
+            .reference("refs/remotes/origin/feat/WAB-1/pushed", head, true, "test")
+            .unwrap();
+        git.set_upstream("feat/WAB-1/pushed", "origin").unwrap();
+        assert!(!git.upstream_gone("feat/WAB-1/pushed").unwrap());
+
+        tracking.delete().unwrap();
+        assert!(git.upstream_gone("feat/WAB-1/pushed").unwrap());
+        assert!(!git.upstream_gone("feat/WAB-2/local").unwrap());
+    }
+
+    #[test]
+    fn test_push_uses_upstream_and_clears_unpushed() {
+        let origin_dir = tempfile::tempdir().unwrap();
+        Repository::init_bare(origin_dir.path()).unwrap();
+
+        let (_dir, git) = scratch_repo();
+        git.repo.remote("origin", origin_dir.path().to_str().unwrap()).unwrap();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+
+        // Set at start, before the branch exists on origin
+        git.set_upstream("feat/WAB-1/x", "origin").unwrap();
+        assert_eq!(git.get_upstream_branch("feat/WAB-1/x").unwrap(), None);
+        assert!(!git.upstream_gone("feat/WAB-1/x").unwrap());
+
+        git.push("feat/WAB-1/x").unwrap();
+        assert_eq!(
+            git.get_upstream_branch("feat/WAB-1/x").unwrap().as_deref(),
+            Some("origin/feat/WAB-1/x")
+        );
+        let origin = Repository::open_bare(origin_dir.path()).unwrap();
+        assert!(origin.find_reference("refs/heads/feat/WAB-1/x").is_ok());
+
+        // Deleted on origin and pruned: now it's gone
+        git.repo.find_reference("refs/remotes/origin/feat/WAB-1/x").unwrap().delete().unwrap();
+        assert!(git.upstream_gone("feat/WAB-1/x").unwrap());
+    }
+
+    /// Scratch repo with `main` pushed to a bare `origin`
+    fn pushed_repo() -> (tempfile::TempDir, tempfile::TempDir, GitClient) {
+        let origin_dir = tempfile::tempdir().unwrap();
+        Repository::init_bare(origin_dir.path()).unwrap();
+        let (dir, git) = scratch_repo();
+        let url = format!("file://{}", origin_dir.path().display());
+        git.repo.remote("origin", &url).unwrap();
+        git.push("main").unwrap();
+        (origin_dir, dir, git)
+    }
+
+    /// Replace HEAD with a different commit, as a rebase or amend would
+    fn rewrite_head(dir: &tempfile::TempDir, git: &GitClient) {
+        std::fs::write(dir.path().join("rewritten.txt"), "new").unwrap();
+        git.amend_commit(Some("Rewritten"), false).unwrap();
+    }
+
+    #[test]
+    fn test_push_sets_upstream_config_once() {
+        let (_origin, _dir, git) = pushed_repo();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        assert_eq!(git.push("feat/WAB-1/x").unwrap(), None);
+        git.push("feat/WAB-1/x").unwrap();
+
+        let config = git.repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(config.get_str("branch.feat/WAB-1/x.remote").unwrap(), "origin");
+        assert_eq!(config.get_str("branch.feat/WAB-1/x.merge").unwrap(), "refs/heads/feat/WAB-1/x");
+        let mut merges = 0;
+        config.multivar("branch.feat/WAB-1/x.merge", None).unwrap().for_each(|_| merges += 1).unwrap();
+        assert_eq!(merges, 1);
+        assert_eq!(git.get_upstream_branch("feat/WAB-1/x").unwrap().as_deref(), Some("origin/feat/WAB-1/x"));
+    }
+
+    #[test]
+    fn test_compare_url_from_push_messages() {
+        let github = "remote: \nremote: Create a pull request for 'feat/x' on GitHub by visiting:\n\
+                      remote:      https://github.com/owner/repo/pull/new/feat/x\nremote: \n";
+        assert_eq!(compare_url(github).as_deref(), Some("https://github.com/owner/repo/pull/new/feat/x"));
+
+        let gitlab = "\nTo create a merge request for feat/x, visit:\n  \
+                      https://gitlab.com/group/repo/-/merge_requests/new?merge_request%5Bsource_branch%5D=feat%2Fx\n\n";
+        assert!(compare_url(gitlab).unwrap().starts_with("https://gitlab.com/group/repo/-/merge_requests/new"));
+
+        assert_eq!(compare_url("Resolving deltas: 100% (3/3)\n"), None);
+    }
+
+    #[test]
+    fn test_create_and_push_tag() {
+        let (origin_dir, _dir, git) = pushed_repo();
+        assert!(!git.tag_exists("v1.0.0"));
+
+        git.create_tag("v1.0.0", "Release v1.0.0").unwrap();
+        assert!(git.tag_exists("v1.0.0"));
+        let err = git.create_tag("v1.0.0", "Again").unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        git.push_tag("v1.0.0").unwrap();
+        let origin = Repository::open_bare(origin_dir.path()).unwrap();
+        let tag = origin.find_reference("refs/tags/v1.0.0").unwrap().peel_to_tag().unwrap();
+        assert_eq!(tag.message(), Some("Release v1.0.0"));
+    }
+
+    #[test]
+    fn test_push_missing_remote_lists_remotes() {
+        let (_dir, git) = scratch_repo();
+        match git.push("main").unwrap_err() {
+            DevFlowError::RemoteNotFound(name, remotes) => {
+                assert_eq!(name, "origin");
+                assert!(remotes.is_empty());
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        git.repo.remote("upstream", "file:///nonexistent").unwrap();
+        assert!(matches!(
+            git.push("main").unwrap_err(),
+            DevFlowError::RemoteNotFound(_, remotes) if remotes == vec!["upstream".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_push_non_fast_forward_is_rejected() {
+        let (_origin, dir, git) = pushed_repo();
+        rewrite_head(&dir, &git);
+        assert!(matches!(git.push("main").unwrap_err(), DevFlowError::PushRejected(branch) if branch == "main"));
+    }
+
+    #[test]
+    fn test_force_with_lease_checks_remote_ref() {
+        let (origin_dir, dir, git) = pushed_repo();
+        rewrite_head(&dir, &git);
+        git.push_force_with_lease("main").unwrap();
+        let origin = Repository::open_bare(origin_dir.path()).unwrap();
+        let pushed = origin.refname_to_id("refs/heads/main").unwrap();
+        assert_eq!(origin.find_commit(pushed).unwrap().summary(), Some("Rewritten"));
+
+        // Someone else moves origin/main; our tracking ref is now stale
+        let signature = git2::Signature::now("Other", "other@example.com").unwrap();
+        let tree = origin.find_commit(pushed).unwrap().tree().unwrap();
+        let parent = origin.find_commit(pushed).unwrap();
+        origin.commit(Some("refs/heads/main"), &signature, &signature, "Theirs", &tree, &[&parent]).unwrap();
+
+        rewrite_head(&dir, &git);
+        let err = git.push_force_with_lease("main").unwrap_err().to_string();
+        assert!(err.contains("has changed since it was last fetched"));
+        let head = origin.refname_to_id("refs/heads/main").unwrap();
+        assert_eq!(origin.find_commit(head).unwrap().summary(), Some("Theirs"));
+    }
+
+    #[tokio::test]
+    async fn test_async_calls_match_blocking_ones() {
+        let (origin_dir, dir, git) = pushed_repo();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        std::fs::write(dir.path().join("work.txt"), "work").unwrap();
+        assert_eq!(git.status_report_async().await.unwrap().untracked, vec!["work.txt"]);
+
+        let sha = git.commit_async("Add work", false).await.unwrap();
+        assert_eq!(git.repo.head().unwrap().target().unwrap().to_string(), sha);
+        assert!(git.status_report_async().await.unwrap().is_clean());
+
+        std::fs::write(dir.path().join("work.txt"), "more work").unwrap();
+        assert_eq!(git.status_report_async().await.unwrap().unstaged[0].path, "work.txt");
+        let sha = git.amend_commit_async(Some("Add more work"), false).await.unwrap();
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!((head.id().to_string(), head.summary()), (sha.clone(), Some("Add more work")));
+        assert!(git.status_report_async().await.unwrap().is_clean());
+
+        git.push_async("feat/WAB-1/x", false).await.unwrap();
+        assert_eq!(git.get_upstream_branch("feat/WAB-1/x").unwrap().as_deref(), Some("origin/feat/WAB-1/x"));
+        let origin = Repository::open_bare(origin_dir.path()).unwrap();
+        assert_eq!(origin.refname_to_id("refs/heads/feat/WAB-1/x").unwrap().to_string(), sha);
+
+        // Errors come back as they would from the blocking call
+        rewrite_head(&dir, &git);
+        let err = git.push_async("feat/WAB-1/x", false).await.unwrap_err();
+        assert!(matches!(err, DevFlowError::PushRejected(branch) if branch == "feat/WAB-1/x"));
+        git.push_async("feat/WAB-1/x", true).await.unwrap();
+
+        git.fetch_remote_async("origin", "main").await.unwrap();
+        assert!(matches!(git.fetch_remote_async("upstream", "main").await, Err(DevFlowError::Other(_))));
+    }
+
+    #[test]
+    fn test_classify_push_error() {
+        let error = |code, class| git2::Error::new(code, class, "boom");
+        assert!(matches!(
+            classify_push_error(&error(git2::ErrorCode::Auth, git2::ErrorClass::Http), "x"),
+            DevFlowError::NoPushAccess(_)
+        ));
+        assert!(matches!(
+            classify_push_error(&error(git2::ErrorCode::GenericError, git2::ErrorClass::Ssh), "x"),
+            DevFlowError::NoPushAccess(_)
+        ));
+        assert!(matches!(
+            classify_push_error(&error(git2::ErrorCode::GenericError, git2::ErrorClass::Net), "x"),
+            DevFlowError::NetworkError(msg) if msg == "boom"
+        ));
+        assert!(matches!(
+            classify_push_error(&error(git2::ErrorCode::NotFastForward, git2::ErrorClass::Reference), "x"),
+            DevFlowError::PushRejected(_)
+        ));
+    }
+
+    #[test]
+    fn test_push_unreachable_remote_is_network_error() {
+        let (_dir, git) = scratch_repo();
+        // Nothing listens on port 1
+        git.repo.remote("origin", "http://127.0.0.1:1/repo.git").unwrap();
+        assert!(matches!(git.push("main").unwrap_err(), DevFlowError::NetworkError(_)));
+    }
+
+    #[test]
+    fn test_unique_history_size_and_delete_branch() {
+        let (dir, git) = scratch_repo();
+        git.create_branch_from("feat/WAB-1/thing", "HEAD").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "only on the branch").unwrap();
+        git.commit("Branch work").unwrap();
+        git.checkout_branch("main").unwrap();
+
+        let branches = vec!["feat/WAB-1/thing".to_string()];
+        let (commits, bytes) = git.unique_history_size(&branches).unwrap();
+        assert_eq!(commits, 1);
+        assert!(bytes > "only on the branch".len() as u64);
+
+        // Once the work is reachable from main nothing is reclaimable
+        let tip = git.repo.revparse_single("feat/WAB-1/thing").unwrap().id();
+        git.repo.reference("refs/heads/main", tip, true, "test").unwrap();
+        assert_eq!(git.unique_history_size(&branches).unwrap(), (0, 0));
+
+        git.delete_branch("feat/WAB-1/thing").unwrap();
+        assert!(!git.branch_exists("feat/WAB-1/thing").unwrap());
+    }
+
+    #[test]
+    fn test_squash_commits_replays_later_commits() {
+        let (dir, git) = scratch_repo();
+        for (file, message) in [("a.txt", "A"), ("b.txt", "B"), ("c.txt", "C"), ("d.txt", "D")] {
+            std::fs::write(dir.path().join(file), message).unwrap();
+            git.commit(message).unwrap();
+        }
+
+        let log = git.get_log(10).unwrap();
+        let summaries: Vec<&str> = log.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["D", "C", "B", "A", "initial"]);
+        let head_tree = git.repo.head().unwrap().peel_to_tree().unwrap().id();
+
+        // Squash A to C, keeping D on top
+        let squashed = git.squash_commits(&log[3].id, &log[1].id, "A, B and C").unwrap();
+        let log_after = git.get_log(10).unwrap();
+        let summaries: Vec<&str> = log_after.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["D", "A, B and C", "initial"]);
+        assert_eq!(log_after[1].id, squashed);
+        assert_eq!(git.repo.head().unwrap().peel_to_tree().unwrap().id(), head_tree);
+        assert!(git.is_clean().unwrap());
+    }
+
+    #[test]
+    fn test_stash_round_trip_includes_untracked() {
+        let (dir, mut git) = scratch_repo();
+        std::fs::write(dir.path().join("tracked.txt"), "v1").unwrap();
+        git.commit("Add tracked").unwrap();
+
+        std::fs::write(dir.path().join("tracked.txt"), "v2").unwrap();
+        std::fs::write(dir.path().join("new.txt"), "new").unwrap();
+
+        let stash_id = git.stash_save("devflow workon: main").unwrap();
+        assert!(git.is_clean().unwrap());
+
+        let mut files = git.stash_files(&stash_id).unwrap();
+        files.sort();
+        assert_eq!(files, vec!["new.txt", "tracked.txt"]);
+
+        assert!(git.stash_pop(&stash_id).unwrap());
+        assert_eq!(std::fs::read_to_string(dir.path().join("tracked.txt")).unwrap(), "v2");
+        assert!(dir.path().join("new.txt").exists());
+
+        // Already popped
+        assert!(!git.stash_pop(&stash_id).unwrap());
+    }
+
+    #[test]
+    fn test_changed_files_since_merge_base() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("base.txt"), "base").unwrap();
+        git.commit("Base").unwrap();
+
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        std::fs::write(dir.path().join("feature.rs"), "fn main() {}").unwrap();
+        git.commit("Feature").unwrap();
+
+        // Work landing on main after the branch point is not part of the branch
+        let main_tip = git.repo.find_reference("refs/heads/main").unwrap().peel_to_commit().unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = main_tip.tree().unwrap();
+        git.repo.commit(Some("refs/heads/main"), &signature, &signature, "Main moves", &tree, &[&main_tip]).unwrap();
+
+        assert_eq!(git.changed_files_since("refs/heads/main").unwrap(), vec!["feature.rs"]);
+    }
+
+    #[test]
+    fn test_ahead_behind() {
+        let (dir, git) = scratch_repo();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        std::fs::write(dir.path().join("feature.rs"), "fn main() {}").unwrap();
+        git.commit("Feature").unwrap();
+        assert_eq!(git.ahead_behind("refs/heads/main").unwrap(), (1, 0));
+
+        commit_on(&git.repo, "refs/heads/main", "Main moves");
+        commit_on(&git.repo, "refs/heads/main", "Main moves again");
+        assert_eq!(git.ahead_behind("refs/heads/main").unwrap(), (1, 2));
+    }
+
+    #[test]
+    fn test_upstream_ahead_behind() {
+        let (dir, git) = scratch_repo();
+        git.repo.remote("origin", "https://example.com/repo.git").unwrap();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        assert_eq!(git.upstream_ahead_behind("feat/WAB-1/x").unwrap(), None);
+
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap().id();
+        git.repo.reference("refs/remotes/origin/feat/WAB-1/x", head, true, "test").unwrap();
+        git.set_upstream("feat/WAB-1/x", "origin").unwrap();
+        std::fs::write(dir.path().join("feature.rs"), "fn main() {}").unwrap();
+        git.commit("Feature").unwrap();
+
+        assert_eq!(git.upstream_ahead_behind("feat/WAB-1/x").unwrap(), Some((1, 0)));
+    }
+
+    /// Stand-in for gpg that records its arguments and prints a fixed signature
+    #[cfg(unix)]
+    fn fake_gpg(dir: &std::path::Path) -> String {
+        fake_signer(dir, "fake-gpg", "PGP SIGNATURE")
+    }
+
+    /// Signing program that records its arguments in `<path>.args` and
+    /// prints a fixed `-----BEGIN <armor>-----` block
+    #[cfg(unix)]
+    fn fake_signer(dir: &std::path::Path, name: &str, armor: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\necho \"$@\" > \"$0.args\"\ncat > /dev/null\nprintf -- '-----BEGIN {0}-----\\nsig\\n-----END {0}-----\\n'\n",
+                armor
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.display().to_string()
+    }
+
+    /// First line of HEAD's signature, if it is signed
+    fn head_signature(git: &GitClient) -> Option<String> {
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        let (signature, _) = git.repo.extract_signature(&head.id(), None).ok()?;
+        signature.as_str().and_then(|s| s.lines().next()).map(String::from)
+    }
+
+    #[test]
+    fn test_commit_unsigned_without_signing_config() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Plain").unwrap();
+        assert_eq!(head_signature(&git), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_follows_git_gpgsign() {
+        let (dir, git) = scratch_repo();
+        let tools = tempfile::tempdir().unwrap();
+        let program = fake_gpg(tools.path());
+        let mut config = git.repo.config().unwrap();
+        config.set_bool("commit.gpgsign", true).unwrap();
+        config.set_str("gpg.program", &program).unwrap();
+        config.set_str("user.signingkey", "ABC123").unwrap();
+
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Signed by config").unwrap();
+        assert_eq!(head_signature(&git).as_deref(), Some("-----BEGIN PGP SIGNATURE-----"));
+        let args = std::fs::read_to_string(format!("{}.args", program)).unwrap();
+        assert_eq!(args.trim(), "--armor --detach-sign --local-user ABC123");
+
+        // --no-sign
+        let git = git.without_signing();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        git.commit("Unsigned").unwrap();
+        assert_eq!(head_signature(&git), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_signed_with_ssh_agent_key() {
+        let (dir, git) = scratch_repo();
+        let tools = tempfile::tempdir().unwrap();
+        let program = fake_signer(tools.path(), "fake-ssh-keygen", "SSH SIGNATURE");
+        let mut config = git.repo.config().unwrap();
+        config.set_bool("commit.gpgsign", true).unwrap();
+        config.set_str("gpg.format", "ssh").unwrap();
+        config.set_str("gpg.ssh.program", &program).unwrap();
+        config.set_str("user.signingkey", "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAITest me@host").unwrap();
+
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Signed with ssh").unwrap();
+        assert_eq!(head_signature(&git).as_deref(), Some("-----BEGIN SSH SIGNATURE-----"));
+
+        // The literal key goes through a temp file, removed afterwards
+        let args = std::fs::read_to_string(format!("{}.args", program)).unwrap();
+        let args: Vec<&str> = args.split_whitespace().collect();
+        assert_eq!(args[..5], ["-Y", "sign", "-n", "git", "-f"]);
+        assert_eq!(args[6], "-U");
+        assert!(!std::path::Path::new(args[5]).exists());
+    }
+
+    #[test]
+    fn test_signer_from_config() {
+        let (_dir, git) = scratch_repo();
+        let mut config = git.repo.config().unwrap();
+        config.set_str("user.signingkey", "~/.ssh/id_ed25519.pub").unwrap();
+        config.set_str("gpg.format", "ssh").unwrap();
+        assert_eq!(
+            signer_from_config(&config, None).unwrap(),
+            Signer::Ssh { program: "ssh-keygen".to_string(), key: "~/.ssh/id_ed25519.pub".to_string() }
+        );
+
+        config.set_str("gpg.format", "x509").unwrap();
+        let err = signer_from_config(&config, None).unwrap_err().to_string();
+        assert!(err.contains("gpg.format 'x509' isn't supported"), "{}", err);
+
+        // preferences.gpg_program wins over gpg.program
+        config.set_str("gpg.format", "openpgp").unwrap();
+        config.set_str("gpg.program", "gpg2").unwrap();
+        assert_eq!(
+            signer_from_config(&config, Some("/opt/gpg".to_string())).unwrap(),
+            Signer::OpenPgp { program: "/opt/gpg".to_string(), key: "~/.ssh/id_ed25519.pub".to_string() }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signed_commit() {
+        let (dir, git) = scratch_repo();
+        let tools = tempfile::tempdir().unwrap();
+        let program = fake_gpg(tools.path());
+        git.repo.config().unwrap().set_str("user.signingkey", "ABC123").unwrap();
+        let git = git.with_signing(&program);
+
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Signed work").unwrap();
+
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Signed work"));
+        let (signature, _) = git.repo.extract_signature(&head.id(), None).unwrap();
+        assert!(signature.as_str().unwrap().starts_with("-----BEGIN PGP SIGNATURE-----"));
+
+        let args = std::fs::read_to_string(format!("{}.args", program)).unwrap();
+        assert_eq!(args.trim(), "--armor --detach-sign --local-user ABC123");
+    }
+
+    #[test]
+    fn test_signed_commit_errors() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let git = git.with_signing("devflow-no-such-gpg");
+
+        // No key configured in this repository (global config may have one)
+        if git.repo.config().unwrap().get_string("user.signingkey").is_err() {
+            let err = git.commit("Unsigned").unwrap_err().to_string();
+            assert!(err.contains("git config user.signingkey"));
+        }
+
+        git.repo.config().unwrap().set_str("user.signingkey", "ABC123").unwrap();
+        let err = git.commit("Unsigned").unwrap_err().to_string();
+        assert!(err.contains("preferences.gpg_program"));
+        assert_eq!(git.repo.head().unwrap().peel_to_commit().unwrap().summary(), Some("initial"));
+    }
+
+    /// `main` and `feat/WAB-1/x` with one commit each since `shared.txt` was
+    /// added, changing `main_file` and `feat_file`; `feat/WAB-1/x` is checked out
+    fn diverged_repo(main_file: &str, feat_file: &str) -> (tempfile::TempDir, GitClient) {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("shared.txt"), "base").unwrap();
+        git.commit("Base").unwrap();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        std::fs::write(dir.path().join(feat_file), "feature change").unwrap();
+        git.commit("Feature").unwrap();
+        git.checkout_branch("main").unwrap();
+        std::fs::write(dir.path().join(main_file), "main change").unwrap();
+        git.commit("Upstream").unwrap();
+        git.checkout_branch("feat/WAB-1/x").unwrap();
+        (dir, git)
+    }
+
+    fn head_summary(git: &GitClient) -> String {
+        git.repo.head().unwrap().peel_to_commit().unwrap().summary().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_sync_rebase_and_merge_without_conflicts() {
+        let (dir, git) = diverged_repo("upstream.txt", "feature.txt");
+        assert_eq!(git.sync("main", SyncMode::Rebase).unwrap(), SyncOutcome::Rebased(1));
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Feature"));
+        assert_eq!(head.parent(0).unwrap().summary(), Some("Upstream"));
+        assert_eq!(git.current_branch().unwrap(), "feat/WAB-1/x");
+        assert_eq!(std::fs::read_to_string(dir.path().join("upstream.txt")).unwrap(), "main change");
+        assert_eq!(std::fs::read_to_string(dir.path().join("feature.txt")).unwrap(), "feature change");
+        assert_eq!(git.sync("main", SyncMode::Rebase).unwrap(), SyncOutcome::UpToDate);
+
+        let (dir, git) = diverged_repo("upstream.txt", "feature.txt");
+        assert_eq!(git.sync("main", SyncMode::Merge).unwrap(), SyncOutcome::Merged);
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_count(), 2);
+        assert_eq!(head.summary(), Some("Merge main into feat/WAB-1/x"));
+        assert!(dir.path().join("feature.txt").exists());
+        assert_eq!(git.sync_in_progress(), None);
+    }
+
+    #[test]
+    fn test_sync_fast_forwards_branch_without_commits() {
+        let (dir, git) = scratch_repo();
+        git.create_branch_from("feat/WAB-1/x", "main").unwrap();
+        git.checkout_branch("main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git.commit("Upstream").unwrap();
+        git.checkout_branch("feat/WAB-1/x").unwrap();
+
+        assert_eq!(git.sync("main", SyncMode::Rebase).unwrap(), SyncOutcome::FastForwarded);
+        assert_eq!(head_summary(&git), "Upstream");
+        assert!(git.is_clean().unwrap());
+    }
+
+    #[test]
+    fn test_sync_rebase_conflict_and_abort() {
+        let (dir, git) = diverged_repo("shared.txt", "shared.txt");
+        let before = git.repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        assert_eq!(
+            git.sync("main", SyncMode::Rebase).unwrap(),
+            SyncOutcome::Conflicts(vec!["shared.txt".to_string()])
+        );
+        assert_eq!(git.sync_in_progress(), Some(SyncMode::Rebase));
+        // Enough state for `git rebase --continue` to pick up from here
+        let state = git.repo.path().join("rebase-merge");
+        let done = std::fs::read_to_string(state.join("done")).unwrap();
+        assert_eq!(done, format!("pick {} Feature\n", before));
+        assert_eq!(std::fs::read_to_string(state.join("git-rebase-todo")).unwrap(), "");
+        assert!(std::fs::read_to_string(state.join("author-script")).unwrap().starts_with("GIT_AUTHOR_NAME='Test'\n"));
+
+        assert_eq!(git.sync_abort().unwrap(), SyncMode::Rebase);
+        assert_eq!(git.sync_in_progress(), None);
+        assert_eq!(git.current_branch().unwrap(), "feat/WAB-1/x");
+        assert_eq!(git.repo.head().unwrap().peel_to_commit().unwrap().id(), before);
+        assert_eq!(std::fs::read_to_string(dir.path().join("shared.txt")).unwrap(), "feature change");
+    }
+
+    #[test]
+    fn test_sync_merge_conflict_and_abort() {
+        let (dir, git) = diverged_repo("shared.txt", "shared.txt");
+
+        assert_eq!(
+            git.sync("main", SyncMode::Merge).unwrap(),
+            SyncOutcome::Conflicts(vec!["shared.txt".to_string()])
+        );
+        assert_eq!(git.sync_in_progress(), Some(SyncMode::Merge));
+        assert!(git.repo.path().join("MERGE_HEAD").exists());
+        let merge_msg = std::fs::read_to_string(git.repo.path().join("MERGE_MSG")).unwrap();
+        assert!(merge_msg.starts_with("Merge main into feat/WAB-1/x\n"));
+        assert!(std::fs::read_to_string(dir.path().join("shared.txt")).unwrap().contains("<<<<<<<"));
+
+        assert_eq!(git.sync_abort().unwrap(), SyncMode::Merge);
+        assert_eq!(git.sync_in_progress(), None);
+        assert_eq!(head_summary(&git), "Feature");
+        assert_eq!(std::fs::read_to_string(dir.path().join("shared.txt")).unwrap(), "feature change");
+        assert!(git.sync_abort().is_err());
     }
 }
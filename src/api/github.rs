@@ -1,9 +1,13 @@
 use anyhow::{Context, Result};
+use super::http;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+const GITHUB_API_URL: &str = "https://api.github.com";
+
 pub struct GitHubClient {
     client: Client,
+    api_url: String,
     owner: String,
     repo: String,
     token: String,
@@ -18,12 +22,321 @@ struct CreatePullRequestPayload {
 }
 
 #[derive(Debug, Deserialize)]
-struct PullRequest {
-    html_url: String,
-    #[allow(dead_code)]
-    number: u64,
+pub struct PullRequest {
+    pub html_url: String,
+    pub number: u64,
+    /// GraphQL ID, needed for mutations such as enabling auto-merge
+    pub node_id: String,
+    /// `open` or `closed`; merged pull requests are closed with `merged_at` set
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub merged_at: Option<String>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    user: Option<User>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+impl PullRequest {
+    /// Login of whoever opened the pull request
+    pub fn author(&self) -> Option<&str> {
+        self.user.as_ref().map(|user| user.login.as_str())
+    }
+
+    /// `open`, `draft`, `merged` or `closed`
+    pub fn lifecycle(&self) -> &'static str {
+        match (self.merged_at.is_some(), self.state.as_str(), self.draft) {
+            (true, _, _) => "merged",
+            (false, "closed", _) => "closed",
+            (false, _, true) => "draft",
+            _ => "open",
+        }
+    }
+}
+
+/// A submitted review, from `/pulls/{number}/reviews`
+#[derive(Debug, Deserialize)]
+pub struct Review {
+    user: Option<User>,
+    /// `APPROVED`, `CHANGES_REQUESTED`, `COMMENTED`, `DISMISSED` or `PENDING`
+    pub state: String,
+    #[serde(default)]
+    pub submitted_at: Option<String>,
+}
+
+impl Review {
+    /// Login of the reviewer; `None` for deleted accounts
+    pub fn reviewer(&self) -> Option<&str> {
+        self.user.as_ref().map(|user| user.login.as_str())
+    }
+}
+
+/// Where one reviewer stands on a pull request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    Commented,
+    /// Requested (or re-requested), or their verdict was dismissed
+    Pending,
+}
+
+impl ReviewDecision {
+    pub fn label(self) -> &'static str {
+        match self {
+            ReviewDecision::Approved => "approved",
+            ReviewDecision::ChangesRequested => "changes requested",
+            ReviewDecision::Commented => "commented",
+            ReviewDecision::Pending => "pending",
+        }
+    }
+}
+
+/// Each reviewer's current decision, in the order they first reviewed, then
+/// requested reviewers who haven't yet. Reviews arrive oldest first; a
+/// comment doesn't replace an approval or a change request, and a pending
+/// request (GitHub re-adds reviewers when asked again) overrides both.
+pub fn reviewer_decisions<'a>(reviews: &'a [Review], requested: &[&'a str]) -> Vec<(&'a str, ReviewDecision)> {
+    let mut decisions: Vec<(&str, ReviewDecision)> = Vec::new();
+    for review in reviews {
+        let Some(login) = review.reviewer() else { continue };
+        let decision = match review.state.as_str() {
+            "APPROVED" => ReviewDecision::Approved,
+            "CHANGES_REQUESTED" => ReviewDecision::ChangesRequested,
+            "COMMENTED" => ReviewDecision::Commented,
+            "DISMISSED" => ReviewDecision::Pending,
+            _ => continue,
+        };
+        match decisions.iter_mut().find(|(name, _)| *name == login) {
+            Some(entry) if decision == ReviewDecision::Commented => {
+                if entry.1 == ReviewDecision::Pending {
+                    entry.1 = decision;
+                }
+            }
+            Some(entry) => entry.1 = decision,
+            None => decisions.push((login, decision)),
+        }
+    }
+
+    for login in requested {
+        match decisions.iter_mut().find(|(name, _)| name == login) {
+            Some(entry) => entry.1 = ReviewDecision::Pending,
+            None => decisions.push((login, ReviewDecision::Pending)),
+        }
+    }
+    decisions
+}
+
+/// Where a pull request's reviews stand, from each reviewer's latest verdict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewState {
+    Approved(usize),
+    ChangesRequested,
+    Pending,
+}
+
+impl ReviewState {
+    /// Each reviewer's latest verdict; comments don't count
+    fn from_reviews(reviews: &[Review]) -> Self {
+        let decisions = reviewer_decisions(reviews, &[]);
+        if decisions.iter().any(|(_, decision)| *decision == ReviewDecision::ChangesRequested) {
+            return ReviewState::ChangesRequested;
+        }
+        match decisions.iter().filter(|(_, decision)| *decision == ReviewDecision::Approved).count() {
+            0 => ReviewState::Pending,
+            approvals => ReviewState::Approved(approvals),
+        }
+    }
+}
+
+/// Mergeability of a pull request, from the single-PR endpoint
+#[derive(Debug, Deserialize)]
+pub struct PullRequestStatus {
+    /// `None` while GitHub is still computing it
+    pub mergeable: Option<bool>,
+    /// `clean`, `blocked`, `behind`, `dirty`, `unstable`, `draft` or `unknown`
+    #[serde(default)]
+    pub mergeable_state: String,
+    #[serde(default)]
+    pub draft: bool,
+    pub head: PullRequestHead,
+    #[serde(default)]
+    pub base: Option<PullRequestHead>,
+    #[serde(default)]
+    pub html_url: String,
+    /// `open` or `closed`
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub merged: bool,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    user: Option<User>,
+    /// Users asked to review who haven't yet (or were asked again)
+    #[serde(default)]
+    requested_reviewers: Vec<User>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequestHead {
+    pub sha: String,
+    /// Branch name
+    #[serde(rename = "ref", default)]
+    pub branch: String,
+}
+
+impl PullRequestStatus {
+    pub fn author(&self) -> Option<&str> {
+        self.user.as_ref().map(|user| user.login.as_str())
+    }
+
+    pub fn requested_reviewers(&self) -> Vec<&str> {
+        self.requested_reviewers.iter().map(|user| user.login.as_str()).collect()
+    }
+
+    /// `open`, `draft`, `merged` or `closed`
+    pub fn lifecycle(&self) -> &'static str {
+        match (self.merged, self.state.as_str(), self.draft) {
+            (true, _, _) => "merged",
+            (false, "closed", _) => "closed",
+            (false, _, true) => "draft",
+            _ => "open",
+        }
+    }
+
+    /// Why the pull request can't be merged right now; empty when it can
+    pub fn blockers(&self) -> Vec<String> {
+        if self.draft || self.mergeable_state == "draft" {
+            return vec!["it is a draft".to_string()];
+        }
+
+        let blocker = match (self.mergeable, self.mergeable_state.as_str()) {
+            (Some(false), _) | (_, "dirty") => "it has conflicts with the base branch",
+            (None, _) | (_, "unknown") => "GitHub is still checking mergeability; try again shortly",
+            (_, "blocked") => "required reviews or status checks are not satisfied",
+            (_, "behind") => "the branch is behind the base branch",
+            _ => return Vec::new(),
+        };
+        vec![blocker.to_string()]
+    }
+}
+
+/// A CI check on a commit, e.g. a GitHub Actions job
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckRun {
+    pub name: String,
+    /// `queued`, `in_progress` or `completed`
+    pub status: String,
+    /// Set once completed: `success`, `failure`, `neutral`, `cancelled`,
+    /// `skipped`, `timed_out` or `action_required`
+    pub conclusion: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Passed,
+    Failed,
+    Pending,
+}
+
+impl CheckRun {
+    /// Neutral and skipped runs count as passed, as they do on GitHub
+    pub fn state(&self) -> CheckState {
+        match (self.status.as_str(), self.conclusion.as_deref()) {
+            ("completed", Some("success" | "neutral" | "skipped")) => CheckState::Passed,
+            ("completed", _) => CheckState::Failed,
+            _ => CheckState::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+/// How a pull request is merged, directly or once auto-merge kicks in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+
+    fn as_graphql(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "MERGE",
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Rebase => "REBASE",
+        }
+    }
+}
+
+/// Body of a review request; `team_reviewers` holds team slugs
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReviewRequest {
+    pub reviewers: Vec<String>,
+    pub team_reviewers: Vec<String>,
+}
+
+impl ReviewRequest {
+    pub fn is_empty(&self) -> bool {
+        self.reviewers.is_empty() && self.team_reviewers.is_empty()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+/// A merged pull request as the issue search API returns it
+#[derive(Debug, Deserialize)]
+pub struct MergedPullRequest {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pull_request: IssuePullRequest,
+}
+
+impl MergedPullRequest {
+    pub fn merged_at(&self) -> Option<&str> {
+        self.pull_request.merged_at.as_deref()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuePullRequest {
+    #[serde(default)]
+    merged_at: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct SearchResults<T> {
+    items: Vec<T>,
+}
+
+/// The search API stops returning results past this many
+const SEARCH_RESULT_LIMIT: usize = 1000;
+
+/// `list_pull_requests` stops paging after this many, newest first
+const PULL_REQUEST_LIST_LIMIT: usize = 1000;
+
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 struct Repository {
@@ -33,7 +346,8 @@ struct Repository {
 impl GitHubClient {
     pub fn new(owner: String, repo: String, token: String) -> Self {
         Self {
-            client: Client::new(),
+            client: http::client(),
+            api_url: GITHUB_API_URL.to_string(),
             owner,
             repo,
             token,
@@ -46,7 +360,7 @@ impl GitHubClient {
         target_branch: &str,
         title: &str,
         description: &str,
-    ) -> Result<String> {
+    ) -> Result<PullRequest> {
         let payload = CreatePullRequestPayload {
             title: title.to_string(),
             body: description.to_string(),
@@ -55,8 +369,8 @@ impl GitHubClient {
         };
 
         let url = format!(
-            "https://api.github.com/repos/{}/{}/pulls",
-            self.owner, self.repo
+            "{}/repos/{}/{}/pulls",
+            self.api_url, self.owner, self.repo
         );
 
         let response = self
@@ -64,30 +378,394 @@ impl GitHubClient {
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.token))
             .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "devflow-cli")
             .json(&payload)
             .send()
             .await
             .context("Failed to send pull request creation request")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("GitHub API error ({}): {}", status, text);
-        }
+        let response = http::check_response("GitHub", response).await?;
+
+        response
+            .json::<PullRequest>()
+            .await
+            .context("Failed to parse pull request response")
+    }
+
+    /// Request reviews on pull request `number` from users and teams
+    pub async fn request_reviewers(&self, number: u64, request: &ReviewRequest) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/requested_reviewers",
+            self.api_url, self.owner, self.repo, number
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .json(request)
+            .send()
+            .await
+            .context("Failed to send review request")?;
+
+        http::check_response("GitHub", response).await?;
+
+        Ok(())
+    }
+
+    /// The open pull request whose head is `branch`, if any
+    pub async fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        self.find_pull_request(branch, "open").await
+    }
+
+    /// The most recent pull request from `branch` in any state, so merged and
+    /// closed ones are found too
+    pub async fn get_pull_request_for_branch(&self, branch: &str) -> Result<Option<PullRequest>> {
+        self.find_pull_request(branch, "all").await
+    }
+
+    pub async fn get_pull_request(&self, number: u64) -> Result<PullRequest> {
+        let url = format!("{}/repos/{}/{}/pulls/{}", self.api_url, self.owner, self.repo, number);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to fetch pull request")?;
 
-        let pr = response
+        let response = http::check_response("GitHub", response).await?;
+
+        response
             .json::<PullRequest>()
             .await
-            .context("Failed to parse pull request response")?;
+            .context("Failed to parse pull request response")
+    }
+
+    /// Pull requests in `state` (`open`, `closed` or `all`), newest first.
+    /// Only the first page of 100 is fetched.
+    /// Pages through the results, since merged PRs can be few and far
+    /// between among the closed ones GitHub returns
+    pub async fn list_pull_requests(&self, state: &str) -> Result<Vec<PullRequest>> {
+        let mut pulls = Vec::new();
+
+        for page in 1.. {
+            let url = format!(
+                "{}/repos/{}/{}/pulls?state={}&per_page=100&page={}",
+                self.api_url, self.owner, self.repo, state, page
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", self.token))
+                .header("Accept", "application/vnd.github+json")
+                .send()
+                .await
+                .context("Failed to list pull requests")?;
+
+            let response = http::check_response("GitHub", response).await?;
+
+            let page = response
+                .json::<Vec<PullRequest>>()
+                .await
+                .context("Failed to parse pull requests response")?;
+
+            let last_page = page.len() < 100;
+            pulls.extend(page);
+            if last_page || pulls.len() >= PULL_REQUEST_LIST_LIMIT {
+                break;
+            }
+        }
+
+        Ok(pulls)
+    }
+
+    async fn find_pull_request(&self, branch: &str, state: &str) -> Result<Option<PullRequest>> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls?state={}&head={}",
+            self.api_url,
+            self.owner,
+            self.repo,
+            state,
+            urlencoding::encode(&format!("{}:{}", self.owner, branch))
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to list pull requests")?;
+
+        let response = http::check_response("GitHub", response).await?;
+
+        let pulls = response
+            .json::<Vec<PullRequest>>()
+            .await
+            .context("Failed to parse pull requests response")?;
+
+        Ok(pulls.into_iter().next())
+    }
+
+    /// Mergeability of pull request `number`. GitHub computes it in the
+    /// background, so a pending result is retried a few times.
+    pub async fn get_pull_request_status(&self, number: u64) -> Result<PullRequestStatus> {
+        let url = format!("{}/repos/{}/{}/pulls/{}", self.api_url, self.owner, self.repo, number);
+
+        let mut attempts = 0;
+        loop {
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", self.token))
+                .header("Accept", "application/vnd.github+json")
+                .send()
+                .await
+                .context("Failed to fetch pull request")?;
+
+            let response = http::check_response("GitHub", response).await?;
+
+            let status = response
+                .json::<PullRequestStatus>()
+                .await
+                .context("Failed to parse pull request response")?;
+
+            attempts += 1;
+            if status.mergeable.is_some() || attempts == 3 {
+                return Ok(status);
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    }
+
+    pub async fn get_review_state(&self, number: u64) -> Result<ReviewState> {
+        Ok(ReviewState::from_reviews(&self.get_reviews(number).await?))
+    }
+
+    /// Submitted reviews on pull request `number`, oldest first
+    pub async fn get_reviews(&self, number: u64) -> Result<Vec<Review>> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/reviews?per_page=100",
+            self.api_url, self.owner, self.repo, number
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to fetch reviews")?;
 
-        Ok(pr.html_url)
+        let response = http::check_response("GitHub", response).await?;
+
+        response
+            .json::<Vec<Review>>()
+            .await
+            .context("Failed to parse reviews response")
+    }
+
+    /// Check runs on the head of `git_ref`; empty when GitHub doesn't know
+    /// the ref, e.g. a branch that hasn't been pushed
+    pub async fn list_check_runs(&self, git_ref: &str) -> Result<Vec<CheckRun>> {
+        let url = format!(
+            "{}/repos/{}/{}/commits/{}/check-runs?per_page=100",
+            self.api_url,
+            self.owner,
+            self.repo,
+            urlencoding::encode(git_ref)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to fetch check runs")?;
+
+        if matches!(response.status().as_u16(), 404 | 422) {
+            return Ok(Vec::new());
+        }
+        let response = http::check_response("GitHub", response).await?;
+
+        let runs = response
+            .json::<CheckRuns>()
+            .await
+            .context("Failed to parse check runs response")?;
+
+        Ok(runs.check_runs)
+    }
+
+    /// Merge pull request `number`, provided its head is still `sha`.
+    /// Without `commit_title` GitHub uses its default ("Title (#12)").
+    /// GitHub answers 405 when it isn't mergeable and 409 when the head moved.
+    pub async fn merge_pull_request(
+        &self,
+        number: u64,
+        method: MergeMethod,
+        commit_title: Option<&str>,
+        sha: &str,
+    ) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/pulls/{}/merge", self.api_url, self.owner, self.repo, number);
+
+        let mut body = serde_json::json!({ "merge_method": method.name(), "sha": sha });
+        if let Some(title) = commit_title {
+            body["commit_title"] = title.into();
+        }
+
+        let response = self
+            .client
+            .put(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send merge request")?;
+
+        http::check_response("GitHub", response).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_branch(&self, branch: &str) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/git/refs/heads/{}",
+            self.api_url, self.owner, self.repo, branch
+        );
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to delete remote branch")?;
+
+        http::check_response("GitHub", response).await?;
+
+        Ok(())
+    }
+
+    /// Merge pull request `node_id` automatically once its checks and
+    /// reviews pass. Only available through GraphQL.
+    pub async fn enable_auto_merge(&self, node_id: &str, method: MergeMethod) -> Result<()> {
+        const MUTATION: &str = "mutation($id: ID!, $method: PullRequestMergeMethod!) { \
+            enablePullRequestAutoMerge(input: { pullRequestId: $id, mergeMethod: $method }) { clientMutationId } }";
+
+        self.graphql(
+            MUTATION,
+            serde_json::json!({ "id": node_id, "method": method.as_graphql() }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// POST a GraphQL query. GraphQL reports most failures with a 200 and an
+    /// `errors` array, which becomes an error here.
+    async fn graphql(&self, query: &str, variables: serde_json::Value) -> Result<serde_json::Value> {
+        let response = self
+            .client
+            .post(format!("{}/graphql", self.api_url))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .json(&serde_json::json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .context("Failed to send GitHub GraphQL request")?;
+
+        let response = http::check_response("GitHub", response).await?;
+
+        let body = response
+            .json::<serde_json::Value>()
+            .await
+            .context("Failed to parse GitHub GraphQL response")?;
+
+        if let Some(errors) = body["errors"].as_array().filter(|errors| !errors.is_empty()) {
+            let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+            anyhow::bail!("GitHub GraphQL error: {}", messages.join("; "));
+        }
+
+        Ok(body["data"].clone())
+    }
+
+    /// Login of the token's owner
+    pub async fn current_user(&self) -> Result<String> {
+        let response = self
+            .client
+            .get(format!("{}/user", self.api_url))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to fetch GitHub user")?;
+
+        let response = http::check_response("GitHub", response).await?;
+
+        let user = response
+            .json::<User>()
+            .await
+            .context("Failed to parse GitHub user response")?;
+
+        Ok(user.login)
+    }
+
+    /// The token owner's pull requests merged between `since` and `until`
+    /// (`YYYY-MM-DD`, both inclusive), fetched page by page
+    pub async fn search_merged_pull_requests(&self, since: &str, until: &str) -> Result<Vec<MergedPullRequest>> {
+        let query = format!(
+            "repo:{}/{} is:pr author:@me merged:{}..{}",
+            self.owner, self.repo, since, until
+        );
+        let mut pulls = Vec::new();
+
+        for page in 1.. {
+            let url = format!(
+                "{}/search/issues?q={}&per_page=100&page={}",
+                self.api_url,
+                urlencoding::encode(&query),
+                page
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", self.token))
+                .header("Accept", "application/vnd.github+json")
+                .send()
+                .await
+                .context("Failed to search pull requests")?;
+
+            let response = http::check_response("GitHub", response).await?;
+
+            let results = response
+                .json::<SearchResults<MergedPullRequest>>()
+                .await
+                .context("Failed to parse pull request search response")?;
+
+            let last_page = results.items.len() < 100;
+            pulls.extend(results.items);
+            if last_page || pulls.len() >= SEARCH_RESULT_LIMIT {
+                break;
+            }
+        }
+
+        Ok(pulls)
     }
 
     pub async fn get_repo_info(&self) -> Result<String> {
         let url = format!(
-            "https://api.github.com/repos/{}/{}",
-            self.owner, self.repo
+            "{}/repos/{}/{}",
+            self.api_url, self.owner, self.repo
         );
 
         let response = self
@@ -95,16 +773,11 @@ impl GitHubClient {
             .get(&url)
             .header("Authorization", format!("Bearer {}", self.token))
             .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "devflow-cli")
             .send()
             .await
             .context("Failed to fetch repository information")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("GitHub API error ({}): {}", status, text);
-        }
+        let response = http::check_response("GitHub", response).await?;
 
         let repo = response
             .json::<Repository>()
@@ -130,4 +803,489 @@ mod tests {
         assert_eq!(client.repo, "repo");
         assert_eq!(client.token, "test-token");
     }
+
+    fn mock_client(url: String) -> GitHubClient {
+        let mut client = GitHubClient::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            "test-token".to_string(),
+        );
+        client.api_url = url;
+        client
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request_success() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("POST", "/repos/owner/repo/pulls")
+            .match_header("authorization", "Bearer test-token")
+            .match_header("user-agent", http::USER_AGENT)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "head": "feat/WAB-1/x",
+                "base": "main"
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"html_url":"https://github.com/owner/repo/pull/5","number":5,"node_id":"PR_kwDO5"}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pr = client
+            .create_pull_request("feat/WAB-1/x", "main", "WAB-1: X", "Resolves WAB-1")
+            .await
+            .unwrap();
+
+        assert_eq!(pr.html_url, "https://github.com/owner/repo/pull/5");
+        assert_eq!(pr.number, 5);
+        assert_eq!(pr.node_id, "PR_kwDO5");
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_request_reviewers_sends_users_and_teams() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("POST", "/repos/owner/repo/pulls/5/requested_reviewers")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "reviewers": ["alice"],
+                "team_reviewers": ["api-team"]
+            })))
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let request = ReviewRequest {
+            reviewers: vec!["alice".to_string()],
+            team_reviewers: vec!["api-team".to_string()],
+        };
+        client.request_reviewers(5, &request).await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_enable_auto_merge_sends_mutation() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("POST", "/graphql")
+            .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "variables": { "id": "PR_kwDO5", "method": "SQUASH" }
+            })))
+            .with_status(200)
+            .with_body(r#"{"data":{"enablePullRequestAutoMerge":{"clientMutationId":null}}}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        client.enable_auto_merge("PR_kwDO5", MergeMethod::Squash).await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_enable_auto_merge_surfaces_graphql_errors() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("POST", "/graphql")
+            .with_status(200)
+            .with_body(r#"{"data":{"enablePullRequestAutoMerge":null},"errors":[{"message":"Pull request Auto merge is not allowed for this repository"}]}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let err = client.enable_auto_merge("PR_kwDO5", MergeMethod::Merge).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GitHub GraphQL error: Pull request Auto merge is not allowed for this repository"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_open_pull_request_filters_by_head() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/owner/repo/pulls")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "open".into()),
+                mockito::Matcher::UrlEncoded("head".into(), "owner:feat/WAB-1/x".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"[{"html_url":"https://github.com/owner/repo/pull/5","number":5,"node_id":"PR_kwDO5"}]"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pr = client.find_open_pull_request("feat/WAB-1/x").await.unwrap().unwrap();
+        assert_eq!(pr.number, 5);
+    }
+
+    #[tokio::test]
+    async fn test_get_pull_request_for_branch_includes_merged() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/owner/repo/pulls")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "all".into()),
+                mockito::Matcher::UrlEncoded("head".into(), "owner:feat/WAB-1/x".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"[{"html_url":"https://github.com/owner/repo/pull/5","number":5,"node_id":"PR_kwDO5",
+                     "state":"closed","draft":false,"merged_at":"2024-01-15T10:30:00Z"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pr = client.get_pull_request_for_branch("feat/WAB-1/x").await.unwrap().unwrap();
+        assert_eq!(pr.lifecycle(), "merged");
+    }
+
+    #[tokio::test]
+    async fn test_list_pull_requests() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/owner/repo/pulls")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "all".into()),
+                mockito::Matcher::UrlEncoded("per_page".into(), "100".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"[{"html_url":"https://github.com/owner/repo/pull/7","number":7,"node_id":"PR_kwDO7",
+                     "state":"open","draft":true,"title":"WAB-2: Add export","user":{"login":"alice"},
+                     "created_at":"2024-02-01T09:00:00Z"},
+                    {"html_url":"https://github.com/owner/repo/pull/5","number":5,"node_id":"PR_kwDO5",
+                     "state":"closed","merged_at":"2024-01-15T10:30:00Z","title":"WAB-1: Fix login"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pulls = client.list_pull_requests("all").await.unwrap();
+        assert_eq!(pulls.len(), 2);
+        assert_eq!(pulls[0].title, "WAB-2: Add export");
+        assert_eq!(pulls[0].author(), Some("alice"));
+        assert_eq!(pulls[0].lifecycle(), "draft");
+        assert_eq!(pulls[1].author(), None);
+        assert_eq!(pulls[1].lifecycle(), "merged");
+    }
+
+    #[tokio::test]
+    async fn test_list_pull_requests_pages_through_results() {
+        let mut server = mockito::Server::new_async().await;
+        let pull = |number: u64| {
+            format!(
+                r#"{{"html_url":"https://github.com/owner/repo/pull/{n}","number":{n},"node_id":"PR_{n}",
+                    "state":"closed","title":"WAB-{n}: Change"}}"#,
+                n = number
+            )
+        };
+        let page = |numbers: std::ops::Range<u64>| format!("[{}]", numbers.map(pull).collect::<Vec<_>>().join(","));
+
+        let first = server
+            .mock("GET", "/repos/owner/repo/pulls")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "closed".into()),
+                mockito::Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(page(0..100))
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/repos/owner/repo/pulls")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "closed".into()),
+                mockito::Matcher::UrlEncoded("page".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_body(page(100..103))
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pulls = client.list_pull_requests("closed").await.unwrap();
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(pulls.len(), 103);
+        assert_eq!(pulls[102].number, 102);
+    }
+
+    #[tokio::test]
+    async fn test_search_merged_pull_requests_pages_through_results() {
+        let mut server = mockito::Server::new_async().await;
+        let query = "repo:owner/repo is:pr author:@me merged:2024-01-08..2024-01-15";
+        let item = |number: u64| {
+            format!(
+                r#"{{"number":{n},"title":"WAB-{n}: Change","html_url":"https://github.com/owner/repo/pull/{n}",
+                    "pull_request":{{"merged_at":"2024-01-10T12:00:00Z"}}}}"#,
+                n = number
+            )
+        };
+        let page = |numbers: std::ops::Range<u64>| {
+            format!(r#"{{"items":[{}]}}"#, numbers.map(item).collect::<Vec<_>>().join(","))
+        };
+
+        let first = server
+            .mock("GET", "/search/issues")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("q".into(), query.into()),
+                mockito::Matcher::UrlEncoded("per_page".into(), "100".into()),
+                mockito::Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(page(1..101))
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/search/issues")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("q".into(), query.into()),
+                mockito::Matcher::UrlEncoded("page".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_body(page(101..103))
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let pulls = client.search_merged_pull_requests("2024-01-08", "2024-01-15").await.unwrap();
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(pulls.len(), 102);
+        assert_eq!(pulls[101].number, 102);
+        assert_eq!(pulls[101].title, "WAB-102: Change");
+        assert_eq!(pulls[0].merged_at(), Some("2024-01-10T12:00:00Z"));
+    }
+
+    #[test]
+    fn test_review_state_uses_latest_verdict_per_reviewer() {
+        let reviews: Vec<Review> = serde_json::from_str(
+            r#"[
+                {"user":{"login":"alice"},"state":"CHANGES_REQUESTED"},
+                {"user":{"login":"bob"},"state":"APPROVED"},
+                {"user":{"login":"alice"},"state":"COMMENTED"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(ReviewState::from_reviews(&reviews), ReviewState::ChangesRequested);
+
+        let reviews: Vec<Review> = serde_json::from_str(
+            r#"[
+                {"user":{"login":"alice"},"state":"CHANGES_REQUESTED"},
+                {"user":{"login":"bob"},"state":"APPROVED"},
+                {"user":{"login":"alice"},"state":"APPROVED"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(ReviewState::from_reviews(&reviews), ReviewState::Approved(2));
+
+        let reviews: Vec<Review> =
+            serde_json::from_str(r#"[{"user":{"login":"bob"},"state":"COMMENTED"}]"#).unwrap();
+        assert_eq!(ReviewState::from_reviews(&reviews), ReviewState::Pending);
+    }
+
+    #[test]
+    fn test_reviewer_decisions() {
+        let reviews: Vec<Review> = serde_json::from_str(
+            r#"[
+                {"user":{"login":"alice"},"state":"APPROVED"},
+                {"user":{"login":"bob"},"state":"COMMENTED"},
+                {"user":{"login":"alice"},"state":"COMMENTED"},
+                {"user":{"login":"carol"},"state":"CHANGES_REQUESTED"},
+                {"user":{"login":"dave"},"state":"APPROVED"},
+                {"user":{"login":"dave"},"state":"DISMISSED"},
+                {"user":null,"state":"APPROVED"}
+            ]"#,
+        )
+        .unwrap();
+
+        // Erin hasn't reviewed; carol was asked again after requesting changes
+        let decisions = reviewer_decisions(&reviews, &["erin", "carol"]);
+        assert_eq!(
+            decisions,
+            vec![
+                ("alice", ReviewDecision::Approved),
+                ("bob", ReviewDecision::Commented),
+                ("carol", ReviewDecision::Pending),
+                ("dave", ReviewDecision::Pending),
+                ("erin", ReviewDecision::Pending),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_reviews() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/owner/repo/pulls/5/reviews")
+            .match_query(mockito::Matcher::UrlEncoded("per_page".into(), "100".into()))
+            .with_status(200)
+            .with_body(r#"[{"user":{"login":"alice"},"state":"APPROVED","submitted_at":"2024-03-01T10:00:00Z"}]"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let reviews = client.get_reviews(5).await.unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].reviewer(), Some("alice"));
+        assert_eq!(reviews[0].submitted_at.as_deref(), Some("2024-03-01T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_pull_request_status_metadata() {
+        let pr: PullRequestStatus = serde_json::from_str(
+            r#"{"mergeable":null,"state":"closed","merged":true,"draft":false,"title":"Fix login",
+                "user":{"login":"alice"},"requested_reviewers":[{"login":"bob"}],
+                "head":{"sha":"abc","ref":"feat/WAB-1/login"},"base":{"sha":"def","ref":"main"}}"#,
+        )
+        .unwrap();
+        assert_eq!(pr.lifecycle(), "merged");
+        assert_eq!(pr.author(), Some("alice"));
+        assert_eq!(pr.requested_reviewers(), vec!["bob"]);
+        assert_eq!(pr.base.unwrap().branch, "main");
+    }
+
+    #[tokio::test]
+    async fn test_pull_request_status_blockers() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/owner/repo/pulls/5")
+            .with_status(200)
+            .with_body(
+                r#"{"mergeable":true,"mergeable_state":"blocked","draft":false,"state":"open",
+                    "html_url":"https://github.com/owner/repo/pull/5","head":{"sha":"abc123","ref":"feat/WAB-1/x"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let status = client.get_pull_request_status(5).await.unwrap();
+        assert_eq!(status.head.sha, "abc123");
+        assert_eq!(status.head.branch, "feat/WAB-1/x");
+        assert_eq!(status.blockers(), vec!["required reviews or status checks are not satisfied"]);
+
+        let clean = PullRequestStatus {
+            mergeable: Some(true),
+            mergeable_state: "unstable".to_string(),
+            draft: false,
+            head: PullRequestHead { sha: "abc123".to_string(), branch: "feat/WAB-1/x".to_string() },
+            base: None,
+            html_url: "https://github.com/owner/repo/pull/5".to_string(),
+            state: "open".to_string(),
+            merged: false,
+            title: String::new(),
+            user: None,
+            requested_reviewers: Vec::new(),
+        };
+        assert!(clean.blockers().is_empty());
+
+        let conflicted = PullRequestStatus { mergeable: Some(false), mergeable_state: "dirty".to_string(), ..clean };
+        assert_eq!(conflicted.blockers(), vec!["it has conflicts with the base branch"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_check_runs() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("GET", "/repos/owner/repo/commits/feat%2FWAB-1%2Fx/check-runs?per_page=100")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"total_count":2,"check_runs":[
+                    {"name":"build","status":"completed","conclusion":"success"},
+                    {"name":"test","status":"in_progress","conclusion":null}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/commits/unpushed/check-runs?per_page=100")
+            .with_status(422)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let runs = client.list_check_runs("feat/WAB-1/x").await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].state(), CheckState::Passed);
+        assert_eq!(runs[1].state(), CheckState::Pending);
+
+        assert!(client.list_check_runs("unpushed").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_pull_request_not_mergeable() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("PUT", "/repos/owner/repo/pulls/5/merge")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "merge_method": "squash",
+                "sha": "abc123"
+            })))
+            .with_status(405)
+            .with_body(r#"{"message":"Pull Request is not mergeable"}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let err = client.merge_pull_request(5, MergeMethod::Squash, None, "abc123").await.unwrap_err();
+        assert_eq!(http::status_of(&err), Some(405));
+    }
+
+    #[tokio::test]
+    async fn test_merge_pull_request_with_commit_title() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/repos/owner/repo/pulls/5/merge")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "merge_method": "rebase",
+                "sha": "abc123",
+                "commit_title": "WAB-1: Fix login"
+            })))
+            .with_status(200)
+            .with_body(r#"{"merged":true}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        client
+            .merge_pull_request(5, MergeMethod::Rebase, Some("WAB-1: Fix login"), "abc123")
+            .await
+            .unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_repo_info_error() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/owner/repo")
+            .with_status(401)
+            .with_body(r#"{"message":"Bad credentials"}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let err = client.get_repo_info().await.unwrap_err();
+        assert_eq!(http::status_of(&err), Some(401));
+        assert!(err.to_string().starts_with("GitHub API error (401 Unauthorized)"));
+    }
 }
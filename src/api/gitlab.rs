@@ -1,4 +1,6 @@
 use anyhow::{Context, Result};
+use super::http;
+use crate::models::approvals::ApprovalsInfo;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +10,29 @@ pub struct GitLabClient {
     token: String,
 }
 
+/// Optional merge request settings. Fields left unset are omitted from the
+/// request so older GitLab versions don't see parameters they don't know.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeRequestOptions {
+    /// `None` leaves the project's squash setting in charge
+    pub squash: Option<bool>,
+    pub remove_source_branch: bool,
+    pub labels: Vec<String>,
+    /// Milestone title, resolved to its ID when the MR is created
+    pub milestone: Option<String>,
+}
+
+impl Default for MergeRequestOptions {
+    fn default() -> Self {
+        Self {
+            squash: None,
+            remove_source_branch: true,
+            labels: Vec::new(),
+            milestone: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct CreateMergeRequestPayload {
     source_branch: String,
@@ -15,22 +40,172 @@ struct CreateMergeRequestPayload {
     title: String,
     description: String,
     remove_source_branch: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    squash: Option<bool>,
+    /// Comma-separated label names
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<u64>,
+}
+
+impl CreateMergeRequestPayload {
+    fn new(
+        source_branch: &str,
+        target_branch: &str,
+        title: &str,
+        description: &str,
+        options: &MergeRequestOptions,
+        milestone_id: Option<u64>,
+    ) -> Self {
+        Self {
+            source_branch: source_branch.to_string(),
+            target_branch: target_branch.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+            remove_source_branch: options.remove_source_branch,
+            squash: options.squash,
+            labels: (!options.labels.is_empty()).then(|| options.labels.join(",")),
+            milestone_id,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MergeRequest {
+    pub web_url: String,
+    /// Project-scoped MR number used in API paths
+    pub iid: u64,
+    pub project_id: u64,
+    #[serde(default)]
+    pub source_branch: String,
+    /// Head commit of the source branch
+    #[serde(default)]
+    pub sha: Option<String>,
+    /// Why the MR can or can't be merged (GitLab 15.6+)
+    #[serde(default)]
+    pub detailed_merge_status: Option<String>,
+    #[serde(default)]
+    pub has_conflicts: bool,
+    #[serde(default)]
+    pub draft: bool,
+    /// `opened`, `closed`, `locked` or `merged`
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub target_branch: String,
+    #[serde(default)]
+    pub author: Option<Author>,
+}
+
+/// A merge request as returned by the list endpoint
+#[derive(Debug, Deserialize)]
+pub struct MergeRequestInfo {
+    pub iid: u64,
+    pub title: String,
+    /// `opened`, `closed`, `locked` or `merged`
+    pub state: String,
+    pub web_url: String,
+    pub author: Author,
+    pub created_at: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub merged_at: Option<String>,
+}
+
+impl MergeRequestInfo {
+    /// `open`, `draft`, `merged` or `closed`, as for `MergeRequest`
+    pub fn lifecycle(&self) -> &'static str {
+        match (self.state.as_str(), self.draft) {
+            ("merged", _) => "merged",
+            ("closed" | "locked", _) => "closed",
+            (_, true) => "draft",
+            _ => "open",
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
-struct MergeRequest {
-    web_url: String,
+pub struct Author {
+    pub username: String,
+}
+
+impl MergeRequest {
+    /// `open`, `draft`, `merged` or `closed`
+    pub fn lifecycle(&self) -> &'static str {
+        match (self.state.as_str(), self.draft) {
+            ("merged", _) => "merged",
+            ("closed" | "locked", _) => "closed",
+            (_, true) => "draft",
+            _ => "open",
+        }
+    }
+
+    /// Why the MR can't be merged right now; empty when it can
+    pub fn blockers(&self) -> Vec<String> {
+        let Some(status) = self.detailed_merge_status.as_deref() else {
+            // Older GitLab: only conflicts and draft state are reported
+            let mut blockers = Vec::new();
+            if self.draft {
+                blockers.push("it is a draft".to_string());
+            }
+            if self.has_conflicts {
+                blockers.push("it has conflicts with the target branch".to_string());
+            }
+            return blockers;
+        };
+
+        let blocker = match status {
+            "mergeable" => return Vec::new(),
+            "not_approved" => "required approvals are missing",
+            "ci_must_pass" => "the pipeline must succeed first",
+            "ci_still_running" => "the pipeline is still running",
+            "discussions_not_resolved" => "there are unresolved discussions",
+            "conflict" | "broken_status" => "it has conflicts with the target branch",
+            "draft_status" => "it is a draft",
+            "need_rebase" => "it needs a rebase onto the target branch",
+            "blocked_status" => "it is blocked by another merge request",
+            "not_open" => "it is not open",
+            "checking" | "unchecked" | "preparing" => "GitLab is still checking mergeability; try again shortly",
+            other => return vec![format!("GitLab reports '{}'", other)],
+        };
+        vec![blocker.to_string()]
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct Project {
     id: u64,
+    #[serde(default)]
+    http_url_to_repo: String,
+    #[serde(default)]
+    ssh_url_to_repo: String,
+}
+
+impl Project {
+    fn is_cloned_from(&self, remote_url: &str) -> bool {
+        let normalize = |url: &str| {
+            let url = url.trim().trim_end_matches('/');
+            url.strip_suffix(".git").unwrap_or(url).to_string()
+        };
+        let remote = normalize(remote_url);
+        normalize(&self.http_url_to_repo) == remote || normalize(&self.ssh_url_to_repo) == remote
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Milestone {
+    id: u64,
+    title: String,
 }
 
 impl GitLabClient {
     pub fn new(base_url: String, token: String) -> Self {
         Self {
-            client: Client::new(),
+            client: http::client(),
             base_url,
             token,
         }
@@ -43,17 +218,18 @@ impl GitLabClient {
         target_branch: &str,
         title: &str,
         description: &str,
-    ) -> Result<String> {
+        options: &MergeRequestOptions,
+    ) -> Result<MergeRequest> {
         let project_id = self.get_project_id(project_path).await?;
 
-        let payload = CreateMergeRequestPayload {
-            source_branch: source_branch.to_string(),
-            target_branch: target_branch.to_string(),
-            title: title.to_string(),
-            description: description.to_string(),
-            remove_source_branch: true,
+        let milestone_id = match &options.milestone {
+            Some(milestone) => Some(self.find_milestone_id(project_id, milestone).await?),
+            None => None,
         };
 
+        let payload =
+            CreateMergeRequestPayload::new(source_branch, target_branch, title, description, options, milestone_id);
+
         let url = format!(
             "{}/api/v4/projects/{}/merge_requests",
             self.base_url, project_id
@@ -68,22 +244,326 @@ impl GitLabClient {
             .await
             .context("Failed to send merge request creation request")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("GitLab API error ({}): {}", status, text);
-        }
+        let response = http::check_response("GitLab", response).await?;
 
-        let mr = response
+        response
             .json::<MergeRequest>()
             .await
-            .context("Failed to parse merge request response")?;
+            .context("Failed to parse merge request response")
+    }
+
+    /// The open merge request from `source_branch`, if any
+    pub async fn find_open_merge_request(&self, project_path: &str, source_branch: &str) -> Result<Option<MergeRequest>> {
+        self.find_merge_request(project_path, source_branch, "opened").await
+    }
+
+    /// The most recent merge request from `source_branch` in any state
+    pub async fn find_merge_request_for_branch(&self, project_path: &str, source_branch: &str) -> Result<Option<MergeRequest>> {
+        self.find_merge_request(project_path, source_branch, "all").await
+    }
+
+    /// Merge requests in `state` (`opened`, `closed`, `merged` or `all`),
+    /// newest first. Only the first page of 100 is fetched.
+    pub async fn list_merge_requests(&self, project_id: u64, state: &str) -> Result<Vec<MergeRequestInfo>> {
+        let url = format!(
+            "{}/api/v4/projects/{}/merge_requests?state={}&per_page=100",
+            self.base_url, project_id, state
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to list merge requests")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        response
+            .json::<Vec<MergeRequestInfo>>()
+            .await
+            .context("Failed to parse merge requests response")
+    }
+
+    /// Merged merge requests you opened, updated on or after `since` (`YYYY-MM-DD`)
+    pub async fn list_my_merged_merge_requests(&self, project_id: u64, since: &str) -> Result<Vec<MergeRequestInfo>> {
+        let url = format!(
+            "{}/api/v4/projects/{}/merge_requests?state=merged&scope=created_by_me&updated_after={}T00:00:00Z&per_page=100",
+            self.base_url, project_id, since
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to list merge requests")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        response
+            .json::<Vec<MergeRequestInfo>>()
+            .await
+            .context("Failed to parse merge requests response")
+    }
+
+    async fn find_merge_request(&self, project_path: &str, source_branch: &str, state: &str) -> Result<Option<MergeRequest>> {
+        let project_id = self.get_project_id(project_path).await?;
+        let url = format!(
+            "{}/api/v4/projects/{}/merge_requests?state={}&source_branch={}",
+            self.base_url,
+            project_id,
+            state,
+            urlencoding::encode(source_branch)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to list merge requests")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        let merge_requests = response
+            .json::<Vec<MergeRequest>>()
+            .await
+            .context("Failed to parse merge requests response")?;
+
+        Ok(merge_requests.into_iter().next())
+    }
+
+    pub async fn get_merge_request(&self, project_path: &str, iid: u64) -> Result<MergeRequest> {
+        let project_id = self.get_project_id(project_path).await?;
+        let url = format!("{}/api/v4/projects/{}/merge_requests/{}", self.base_url, project_id, iid);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to fetch merge request")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        response
+            .json::<MergeRequest>()
+            .await
+            .context("Failed to parse merge request response")
+    }
+
+    pub async fn get_approvals(&self, project_id: u64, mr_iid: u64) -> Result<ApprovalsInfo> {
+        let url = format!(
+            "{}/api/v4/projects/{}/merge_requests/{}/approvals",
+            self.base_url, project_id, mr_iid
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to fetch merge request approvals")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        response
+            .json::<ApprovalsInfo>()
+            .await
+            .context("Failed to parse approvals response")
+    }
+
+    /// Merge the MR now. GitLab answers 405 when it isn't mergeable, 406 on
+    /// conflicts and 409 when the head moved since `mr` was fetched.
+    /// `remove_source_branch` of `None` keeps the MR's own setting.
+    pub async fn accept_merge_request(
+        &self,
+        mr: &MergeRequest,
+        squash: bool,
+        remove_source_branch: Option<bool>,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/api/v4/projects/{}/merge_requests/{}/merge",
+            self.base_url, mr.project_id, mr.iid
+        );
+
+        let mut body = serde_json::json!({});
+        if squash {
+            body["squash"] = serde_json::Value::Bool(true);
+        }
+        if let Some(remove) = remove_source_branch {
+            body["should_remove_source_branch"] = serde_json::Value::Bool(remove);
+        }
+        if let Some(sha) = &mr.sha {
+            body["sha"] = serde_json::Value::String(sha.clone());
+        }
+
+        let response = self
+            .client
+            .put(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send merge request")?;
+
+        http::check_response("GitLab", response).await?;
+
+        Ok(())
+    }
+
+    /// Merge the MR once its pipeline succeeds, optionally squashing
+    pub async fn merge_when_pipeline_succeeds(&self, mr: &MergeRequest, squash: bool) -> Result<()> {
+        let url = format!(
+            "{}/api/v4/projects/{}/merge_requests/{}/merge",
+            self.base_url, mr.project_id, mr.iid
+        );
+
+        let mut body = serde_json::json!({ "merge_when_pipeline_succeeds": true });
+        if squash {
+            body["squash"] = serde_json::Value::Bool(true);
+        }
+
+        let response = self
+            .client
+            .put(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send merge request")?;
+
+        http::check_response("GitLab", response).await?;
+
+        Ok(())
+    }
+
+    /// Validate the token by fetching the authenticated user
+    pub async fn test_connection(&self) -> Result<()> {
+        let url = format!("{}/api/v4/user", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to connect to GitLab")?;
 
-        Ok(mr.web_url)
+        http::check_response("GitLab", response).await?;
+
+        Ok(())
     }
 
-    async fn get_project_id(&self, project_path: &str) -> Result<u64> {
-        let encoded_path = urlencoding::encode(project_path);
+    /// Server version from `/version`, requested without the token so it
+    /// checks that the server is reachable on its own
+    pub async fn get_version(&self) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Version {
+            version: String,
+        }
+
+        let url = format!("{}/api/v4/version", self.base_url);
+
+        let response = self.client.get(&url).send().await.context("Failed to connect to GitLab")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        let version = response
+            .json::<Version>()
+            .await
+            .context("Failed to parse version response")?;
+
+        Ok(version.version)
+    }
+
+    /// ID of the project milestone titled `title`
+    async fn find_milestone_id(&self, project_id: u64, title: &str) -> Result<u64> {
+        let url = format!(
+            "{}/api/v4/projects/{}/milestones?title={}",
+            self.base_url,
+            project_id,
+            urlencoding::encode(title)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to fetch milestones")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        let milestones = response
+            .json::<Vec<Milestone>>()
+            .await
+            .context("Failed to parse milestones response")?;
+
+        milestones
+            .into_iter()
+            .find(|milestone| milestone.title == title)
+            .map(|milestone| milestone.id)
+            .ok_or_else(|| anyhow::anyhow!("Milestone '{}' not found in this project", title))
+    }
+
+    /// ID of the project a git remote URL points at. Looks the remote's path
+    /// (`group/subgroup/repo`) up directly, then falls back to the clone URLs
+    /// of the projects the token's user is a member of, for remotes whose
+    /// path isn't the project's (e.g. SSH host aliases)
+    pub async fn get_project_by_remote_url(&self, remote_url: &str) -> Result<u64> {
+        let remote = crate::api::git::parse_remote_url(remote_url)
+            .with_context(|| format!("Could not read a project path from remote URL '{}'", remote_url))?;
+
+        match self.get_project_id(&remote.path).await {
+            Ok(id) => return Ok(id),
+            Err(e) if http::status_of(&e) == Some(404) => {}
+            Err(e) => return Err(e),
+        }
+
+        let name = remote.path.rsplit('/').next().unwrap_or(&remote.path);
+        let url = format!(
+            "{}/api/v4/projects?membership=true&simple=true&per_page=100&search={}",
+            self.base_url,
+            urlencoding::encode(name)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .context("Failed to list projects")?;
+
+        let response = http::check_response("GitLab", response).await?;
+
+        let projects = response
+            .json::<Vec<Project>>()
+            .await
+            .context("Failed to parse projects response")?;
+
+        projects
+            .into_iter()
+            .find(|project| project.is_cloned_from(remote_url))
+            .map(|project| project.id)
+            .ok_or_else(|| anyhow::anyhow!("No GitLab project you are a member of matches remote '{}'", remote_url))
+    }
+
+    /// `project` is a numeric ID, used as is, or a `group/repo` path
+    async fn get_project_id(&self, project: &str) -> Result<u64> {
+        if let Ok(id) = project.parse::<u64>() {
+            return Ok(id);
+        }
+
+        let encoded_path = urlencoding::encode(project);
         let url = format!("{}/api/v4/projects/{}", self.base_url, encoded_path);
 
         let response = self
@@ -94,11 +574,7 @@ impl GitLabClient {
             .await
             .context("Failed to fetch project information")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("GitLab API error ({}): {}", status, text);
-        }
+        let response = http::check_response("GitLab", response).await?;
 
         let project = response
             .json::<Project>()
@@ -122,4 +598,460 @@ mod tests {
         assert_eq!(client.base_url, "https://git.example.com");
         assert_eq!(client.token, "test-token");
     }
+
+    #[tokio::test]
+    async fn test_test_connection_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/api/v4/user")
+            .match_header("PRIVATE-TOKEN", "bad-token")
+            .with_status(401)
+            .with_body("{\"message\":\"401 Unauthorized\"}")
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "bad-token".to_string());
+        let result = client.test_connection().await;
+        assert!(result.unwrap_err().to_string().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_get_version_sends_no_token() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/api/v4/version")
+            .match_header("PRIVATE-TOKEN", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body(r#"{"version":"16.8.1-ee","revision":"abc123"}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        assert_eq!(client.get_version().await.unwrap(), "16.8.1-ee");
+    }
+
+    #[tokio::test]
+    async fn test_list_merge_requests() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/api/v4/projects/42/merge_requests")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "opened".into()),
+                mockito::Matcher::UrlEncoded("per_page".into(), "100".into()),
+            ]))
+            .match_header("PRIVATE-TOKEN", "test-token")
+            .with_status(200)
+            .with_body(
+                r#"[{"iid":12,"title":"WAB-3: Retry uploads","state":"opened","draft":true,
+                     "web_url":"https://git.example.com/group/repo/-/merge_requests/12",
+                     "author":{"id":1,"username":"alice","name":"Alice"},
+                     "created_at":"2024-03-04T08:15:00.000Z"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let mrs = client.list_merge_requests(42, "opened").await.unwrap();
+        assert_eq!(mrs.len(), 1);
+        assert_eq!(mrs[0].iid, 12);
+        assert_eq!(mrs[0].author.username, "alice");
+        assert_eq!(mrs[0].lifecycle(), "draft");
+    }
+
+    #[tokio::test]
+    async fn test_list_my_merged_merge_requests() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/api/v4/projects/42/merge_requests")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "merged".into()),
+                mockito::Matcher::UrlEncoded("scope".into(), "created_by_me".into()),
+                mockito::Matcher::UrlEncoded("updated_after".into(), "2024-03-01T00:00:00Z".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"[{"iid":12,"title":"WAB-3: Retry uploads","state":"merged",
+                     "web_url":"https://git.example.com/group/repo/-/merge_requests/12",
+                     "author":{"id":1,"username":"alice","name":"Alice"},
+                     "created_at":"2024-03-04T08:15:00.000Z","merged_at":"2024-03-05T11:00:00.000Z"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let mrs = client.list_my_merged_merge_requests(42, "2024-03-01").await.unwrap();
+        assert_eq!(mrs[0].merged_at.as_deref(), Some("2024-03-05T11:00:00.000Z"));
+        assert_eq!(mrs[0].lifecycle(), "merged");
+    }
+
+    #[tokio::test]
+    async fn test_get_merge_request_by_iid() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _project = server
+            .mock("GET", "/api/v4/projects/group%2Frepo")
+            .with_status(200)
+            .with_body(r#"{"id":42}"#)
+            .create_async()
+            .await;
+        let _mr = server
+            .mock("GET", "/api/v4/projects/42/merge_requests/12")
+            .match_header("PRIVATE-TOKEN", "test-token")
+            .with_status(200)
+            .with_body(
+                r#"{"iid":12,"project_id":42,"state":"opened","source_branch":"feat/WAB-3/retry",
+                    "web_url":"https://git.example.com/group/repo/-/merge_requests/12","sha":"abc123"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let mr = client.get_merge_request("group/repo", 12).await.unwrap();
+        assert_eq!(mr.source_branch, "feat/WAB-3/retry");
+        assert_eq!(mr.lifecycle(), "open");
+    }
+
+    #[tokio::test]
+    async fn test_create_merge_request_success() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _project = server
+            .mock("GET", "/api/v4/projects/group%2Frepo")
+            .match_header("PRIVATE-TOKEN", "test-token")
+            .with_status(200)
+            .with_body(r#"{"id":42}"#)
+            .create_async()
+            .await;
+
+        let create = server
+            .mock("POST", "/api/v4/projects/42/merge_requests")
+            .match_header("user-agent", http::USER_AGENT)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "source_branch": "feat/WAB-1/x",
+                "target_branch": "main"
+            })))
+            .with_status(201)
+            .with_body(r#"{"web_url":"https://git.example.com/group/repo/-/merge_requests/3","iid":3,"project_id":42}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let mr = client
+            .create_merge_request(
+                "group/repo",
+                "feat/WAB-1/x",
+                "main",
+                "WAB-1: X",
+                "Resolves WAB-1",
+                &MergeRequestOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(mr.web_url, "https://git.example.com/group/repo/-/merge_requests/3");
+        assert_eq!((mr.project_id, mr.iid), (42, 3));
+        create.assert_async().await;
+    }
+
+    fn payload(options: MergeRequestOptions, milestone_id: Option<u64>) -> serde_json::Value {
+        let payload =
+            CreateMergeRequestPayload::new("feat/WAB-1/x", "main", "WAB-1: X", "Resolves WAB-1", &options, milestone_id);
+        serde_json::to_value(payload).unwrap()
+    }
+
+    #[test]
+    fn test_payload_defaults_omit_optional_fields() {
+        assert_eq!(
+            payload(MergeRequestOptions::default(), None),
+            serde_json::json!({
+                "source_branch": "feat/WAB-1/x",
+                "target_branch": "main",
+                "title": "WAB-1: X",
+                "description": "Resolves WAB-1",
+                "remove_source_branch": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_payload_squash_and_keep_source_branch() {
+        let options = MergeRequestOptions {
+            squash: Some(true),
+            remove_source_branch: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            payload(options, None),
+            serde_json::json!({
+                "source_branch": "feat/WAB-1/x",
+                "target_branch": "main",
+                "title": "WAB-1: X",
+                "description": "Resolves WAB-1",
+                "remove_source_branch": false,
+                "squash": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_payload_labels_and_milestone() {
+        let options = MergeRequestOptions {
+            labels: vec!["backend".to_string(), "needs review".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            payload(options, Some(7)),
+            serde_json::json!({
+                "source_branch": "feat/WAB-1/x",
+                "target_branch": "main",
+                "title": "WAB-1: X",
+                "description": "Resolves WAB-1",
+                "remove_source_branch": true,
+                "labels": "backend,needs review",
+                "milestone_id": 7
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_merge_request_resolves_milestone() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _project = server
+            .mock("GET", "/api/v4/projects/group%2Frepo")
+            .with_status(200)
+            .with_body(r#"{"id":42}"#)
+            .create_async()
+            .await;
+
+        let _milestones = server
+            .mock("GET", "/api/v4/projects/42/milestones")
+            .match_query(mockito::Matcher::UrlEncoded("title".into(), "Release 1.2".into()))
+            .with_status(200)
+            .with_body(r#"[{"id":9,"title":"Release 1.2"}]"#)
+            .create_async()
+            .await;
+
+        let create = server
+            .mock("POST", "/api/v4/projects/42/merge_requests")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "source_branch": "feat/WAB-1/x",
+                "target_branch": "main",
+                "title": "WAB-1: X",
+                "description": "Resolves WAB-1",
+                "remove_source_branch": false,
+                "squash": true,
+                "labels": "backend",
+                "milestone_id": 9
+            })))
+            .with_status(201)
+            .with_body(r#"{"web_url":"https://git.example.com/group/repo/-/merge_requests/4","iid":4,"project_id":42}"#)
+            .create_async()
+            .await;
+
+        let options = MergeRequestOptions {
+            squash: Some(true),
+            remove_source_branch: false,
+            labels: vec!["backend".to_string()],
+            milestone: Some("Release 1.2".to_string()),
+        };
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        client
+            .create_merge_request("group/repo", "feat/WAB-1/x", "main", "WAB-1: X", "Resolves WAB-1", &options)
+            .await
+            .unwrap();
+
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_merge_request_unknown_milestone() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _project = server
+            .mock("GET", "/api/v4/projects/group%2Frepo")
+            .with_status(200)
+            .with_body(r#"{"id":42}"#)
+            .create_async()
+            .await;
+
+        let _milestones = server
+            .mock("GET", "/api/v4/projects/42/milestones")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let options = MergeRequestOptions {
+            milestone: Some("Someday".to_string()),
+            ..Default::default()
+        };
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let err = client
+            .create_merge_request("group/repo", "feat/WAB-1/x", "main", "WAB-1: X", "Resolves WAB-1", &options)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Milestone 'Someday' not found"));
+    }
+
+    #[tokio::test]
+    async fn test_merge_when_pipeline_succeeds() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/api/v4/projects/42/merge_requests/3/merge")
+            .match_header("PRIVATE-TOKEN", "test-token")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "merge_when_pipeline_succeeds": true,
+                "squash": true
+            })))
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let mr = MergeRequest {
+            web_url: "https://git.example.com/group/repo/-/merge_requests/3".to_string(),
+            iid: 3,
+            project_id: 42,
+            ..Default::default()
+        };
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        client.merge_when_pipeline_succeeds(&mr, true).await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_open_merge_request_reports_blockers() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _project = server
+            .mock("GET", "/api/v4/projects/group%2Frepo")
+            .with_status(200)
+            .with_body(r#"{"id":42}"#)
+            .create_async()
+            .await;
+
+        let _list = server
+            .mock("GET", "/api/v4/projects/42/merge_requests")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "opened".into()),
+                mockito::Matcher::UrlEncoded("source_branch".into(), "feat/WAB-1/x".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"[{"web_url":"https://git.example.com/group/repo/-/merge_requests/3","iid":3,"project_id":42,"sha":"abc","detailed_merge_status":"not_approved"}]"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let mr = client.find_open_merge_request("group/repo", "feat/WAB-1/x").await.unwrap().unwrap();
+        assert_eq!(mr.iid, 3);
+        assert_eq!(mr.blockers(), vec!["required approvals are missing"]);
+
+        let legacy = MergeRequest { has_conflicts: true, ..Default::default() };
+        assert_eq!(legacy.blockers(), vec!["it has conflicts with the target branch"]);
+        let ready = MergeRequest { detailed_merge_status: Some("mergeable".to_string()), ..Default::default() };
+        assert!(ready.blockers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_approvals() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/api/v4/projects/42/merge_requests/3/approvals")
+            .match_header("private-token", "test-token")
+            .with_status(200)
+            .with_body(r#"{"approvals_required":2,"approvals_left":1,"approved_by":[{"user":{"username":"alice"}}]}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let approvals = client.get_approvals(42, 3).await.unwrap();
+        assert_eq!(approvals.approvals_required, 2);
+        assert_eq!(approvals.approved_by, vec!["alice"]);
+    }
+
+    #[tokio::test]
+    async fn test_accept_merge_request_conflict() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("PUT", "/api/v4/projects/42/merge_requests/3/merge")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "squash": true,
+                "should_remove_source_branch": true,
+                "sha": "abc"
+            })))
+            .with_status(406)
+            .with_body(r#"{"message":"Branch cannot be merged"}"#)
+            .create_async()
+            .await;
+
+        let mr = MergeRequest {
+            iid: 3,
+            project_id: 42,
+            sha: Some("abc".to_string()),
+            ..Default::default()
+        };
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let err = client.accept_merge_request(&mr, true, Some(true)).await.unwrap_err();
+        assert_eq!(http::status_of(&err), Some(406));
+    }
+
+    #[tokio::test]
+    async fn test_get_project_by_remote_url_uses_path() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("GET", "/api/v4/projects/group%2Fsub%2Frepo")
+            .with_status(200)
+            .with_body(r#"{"id":42}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        let id = client.get_project_by_remote_url("git@gitlab.example.com:group/sub/repo.git").await.unwrap();
+        assert_eq!(id, 42);
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_project_by_remote_url_falls_back_to_clone_urls() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _path = server
+            .mock("GET", "/api/v4/projects/alias%2Frepo")
+            .with_status(404)
+            .with_body(r#"{"message":"404 Project Not Found"}"#)
+            .create_async()
+            .await;
+        let _search = server
+            .mock("GET", "/api/v4/projects")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("membership".into(), "true".into()),
+                mockito::Matcher::UrlEncoded("search".into(), "repo".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"id":7,"http_url_to_repo":"https://gitlab.example.com/other/repo.git","ssh_url_to_repo":"git@gitlab.example.com:other/repo.git"},
+                    {"id":9,"http_url_to_repo":"https://gitlab.example.com/team/repo.git","ssh_url_to_repo":"git@work-gitlab:alias/repo.git"}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(server.url(), "test-token".to_string());
+        assert_eq!(client.get_project_by_remote_url("git@work-gitlab:alias/repo.git").await.unwrap(), 9);
+
+        let id = client.get_project_by_remote_url("git@work-gitlab:alias/repo").await.unwrap();
+        assert_eq!(id, 9, "a missing .git suffix still matches");
+    }
 }
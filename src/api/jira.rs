@@ -1,7 +1,20 @@
-use crate::config::settings::AuthMethod;
-use crate::models::ticket::JiraTicket;
+use crate::config::cache::{self, CachedTicket, TicketCache};
+use crate::config::settings::{ApiVersion, AuthMethod, JiraConfig};
+use crate::errors::DevFlowError;
+use crate::models::adf;
+use crate::models::agile::{AgilePage, JiraBoard, Sprint};
+use crate::models::comment::CommentPage;
+use crate::models::remote_link::RemoteLink;
+use crate::models::ticket::{Component, IssueLinkType, JiraProject, JiraRelease, JiraTicket, JiraUser, NewIssue, Version};
+use crate::models::transition::{Transition, TransitionsResponse};
+use crate::utils::jql::{self, JqlBuilder, Sort, SortField, SortOrder};
 use anyhow::{Context, Result};
-use reqwest::{Client, RequestBuilder};
+use super::http;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
 
 enum AuthConfig {
     BearerToken { token: String },
@@ -12,6 +25,65 @@ pub struct JiraClient {
     client: Client,
     base_url: String,
     auth: AuthConfig,
+    api_version: ApiVersion,
+    /// Version picked by auto-detection, probed once per client
+    detected_version: tokio::sync::OnceCell<String>,
+    /// Authenticated user, fetched once per client
+    myself: tokio::sync::OnceCell<JiraUser>,
+    /// Issue link types, fetched once per client
+    link_types: tokio::sync::OnceCell<Vec<IssueLinkType>>,
+    /// Tickets fetched by this client, revalidated with `If-None-Match`
+    etags: EtagCache,
+}
+
+/// In-memory tickets keyed by ID, each with the ETag Jira sent for it. Jira
+/// answers a matching `If-None-Match` with 304 and no body.
+#[derive(Debug, Default)]
+struct EtagCache {
+    entries: Mutex<HashMap<String, (String, JiraTicket)>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl EtagCache {
+    fn etag(&self, ticket_id: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.get(ticket_id).map(|(etag, _)| etag.clone())
+    }
+
+    /// The cached ticket, after Jira said it has not changed
+    fn hit(&self, ticket_id: &str) -> Option<JiraTicket> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let (_, ticket) = entries.get(ticket_id)?;
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.log("hit", ticket_id);
+        Some(ticket.clone())
+    }
+
+    /// Remember a freshly fetched ticket; responses without an ETag can't be
+    /// revalidated, so they are not kept
+    fn miss(&self, ticket_id: &str, etag: Option<String>, ticket: &JiraTicket) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.log("miss", ticket_id);
+
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        match etag {
+            Some(etag) => entries.insert(ticket_id.to_string(), (etag, ticket.clone())),
+            None => entries.remove(ticket_id),
+        };
+    }
+
+    fn log(&self, outcome: &str, ticket_id: &str) {
+        if std::env::var("DEVFLOW_DEBUG").is_ok() {
+            eprintln!(
+                "DEBUG: ETag cache {} for {} ({} hits, {} misses)",
+                outcome,
+                ticket_id,
+                self.hits.load(Ordering::Relaxed),
+                self.misses.load(Ordering::Relaxed)
+            );
+        }
+    }
 }
 
 impl JiraClient {
@@ -25,10 +97,69 @@ impl JiraClient {
         };
 
         Self {
-            client: Client::new(),
+            client: http::client(),
             base_url,
             auth,
+            api_version: ApiVersion::Latest,
+            detected_version: tokio::sync::OnceCell::new(),
+            myself: tokio::sync::OnceCell::new(),
+            link_types: tokio::sync::OnceCell::new(),
+            etags: EtagCache::default(),
+        }
+    }
+
+    pub fn from_config(config: &JiraConfig) -> Self {
+        Self::new(config.url.clone(), config.email.clone(), config.auth_method.clone())
+            .with_api_version(config.api_version)
+    }
+
+    pub fn with_api_version(mut self, api_version: ApiVersion) -> Self {
+        self.api_version = api_version;
+        self
+    }
+
+    /// Path segment for `/rest/api/<version>`. `JIRA_API_VERSION` still
+    /// overrides the configured version.
+    async fn api_version(&self) -> Result<String> {
+        if let Ok(version) = std::env::var("JIRA_API_VERSION") {
+            return Ok(version);
+        }
+
+        match self.api_version {
+            ApiVersion::V2 => Ok("2".to_string()),
+            ApiVersion::V3 => Ok("3".to_string()),
+            ApiVersion::Latest => Ok("latest".to_string()),
+            ApiVersion::Auto => self
+                .detected_version
+                .get_or_try_init(|| self.detect_api_version())
+                .await
+                .cloned(),
+        }
+    }
+
+    /// Server/Data Center has no v3, so a 404 on v3's /myself means v2
+    async fn detect_api_version(&self) -> Result<String> {
+        let url = format!("{}/rest/api/3/myself", self.base_url);
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to connect to Jira")?;
+
+        let status = response.status();
+        let version = if status.is_success() {
+            "3"
+        } else if status == reqwest::StatusCode::NOT_FOUND {
+            "2"
+        } else {
+            return Err(http::ApiError::from_response("Jira", response).await.into());
+        };
+
+        if std::env::var("DEVFLOW_DEBUG").is_ok() {
+            eprintln!("DEBUG: Detected Jira REST API version {}", version);
         }
+
+        Ok(version.to_string())
     }
 
     fn apply_auth(&self, builder: RequestBuilder) -> RequestBuilder {
@@ -42,55 +173,95 @@ impl JiraClient {
         }
     }
 
+    /// Fetch a ticket. Repeat fetches through the same client send the
+    /// ticket's ETag and reuse the earlier response if Jira answers 304.
     pub async fn get_ticket(&self, ticket_id: &str) -> Result<JiraTicket> {
-        let api_version = std::env::var("JIRA_API_VERSION").unwrap_or_else(|_| "latest".to_string());
+        let api_version = self.api_version().await?;
         let url = format!("{}/rest/api/{}/issue/{}", self.base_url, api_version, ticket_id);
 
-        let response = self.apply_auth(self.client.get(&url))
+        let mut request = self.apply_auth(self.client.get(&url));
+        if let Some(etag) = self.etags.etag(ticket_id) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = request
             .send()
             .await
             .context("Failed to send request to Jira")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Jira API error ({}): {}", status, text);
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(ticket) = self.etags.hit(ticket_id) {
+                return Ok(ticket);
+            }
         }
 
+        let response = http::check_response("Jira", response).await?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
         let ticket = response
             .json::<JiraTicket>()
             .await
             .context("Failed to parse Jira response")?;
 
+        self.etags.miss(ticket_id, etag, &ticket);
+        Ok(ticket)
+    }
+
+    /// `get_ticket` through the local cache (see `TicketCache`), unless
+    /// `--no-cache` is set
+    pub async fn get_ticket_cached(&self, ticket_id: &str) -> Result<CachedTicket> {
+        if cache::is_disabled() {
+            return Ok(CachedTicket::from_ticket(&self.get_ticket(ticket_id).await?));
+        }
+
+        let mut cache = TicketCache::load();
+        self.get_ticket_through(&mut cache, ticket_id).await
+    }
+
+    /// `get_ticket`, bypassing the local cache but refreshing it
+    pub async fn refresh_ticket_cached(&self, ticket_id: &str) -> Result<CachedTicket> {
+        let ticket = CachedTicket::from_ticket(&self.get_ticket(ticket_id).await?);
+        if !cache::is_disabled() {
+            let mut cache = TicketCache::load();
+            cache.insert_ticket(ticket_id, ticket.clone());
+            let _ = cache.save();
+        }
+        Ok(ticket)
+    }
+
+    async fn get_ticket_through(&self, cache: &mut TicketCache, ticket_id: &str) -> Result<CachedTicket> {
+        if let Some(ticket) = cache.ticket(ticket_id, cache::TICKET_TTL) {
+            return Ok(ticket.clone());
+        }
+
+        let ticket = CachedTicket::from_ticket(&self.get_ticket(ticket_id).await?);
+        cache.insert_ticket(ticket_id, ticket.clone());
+        // The cache is only an optimisation
+        let _ = cache.save();
+
         Ok(ticket)
     }
 
     pub async fn update_status(&self, ticket_id: &str, transition_name: &str) -> Result<()> {
-        let api_version = std::env::var("JIRA_API_VERSION").unwrap_or_else(|_| "latest".to_string());
+        let transitions = self.get_transitions(ticket_id).await?;
+
+        let Some(transition) = transitions.iter().find(|t| t.name == transition_name) else {
+            let available = transitions.into_iter().map(|t| t.name).collect();
+            return Err(DevFlowError::JiraTransitionNotFound(transition_name.to_string(), available).into());
+        };
+
+        let api_version = self.api_version().await?;
         let transitions_url = format!(
             "{}/rest/api/{}/issue/{}/transitions",
             self.base_url, api_version, ticket_id
         );
-
-        let transitions_response = self.apply_auth(self.client.get(&transitions_url))
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
-
-        let transitions = transitions_response["transitions"]
-            .as_array()
-            .context("No transitions found")?;
-
-        let transition_id = transitions
-            .iter()
-            .find(|t| t["name"].as_str() == Some(transition_name))
-            .and_then(|t| t["id"].as_str())
-            .context(format!("Transition '{}' not found", transition_name))?;
-
         let body = serde_json::json!({
             "transition": {
-                "id": transition_id
+                "id": transition.id
             }
         });
 
@@ -99,26 +270,56 @@ impl JiraClient {
             .send()
             .await?;
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to update status: {}", response.status());
-        }
+        http::check_response("Jira", response).await?;
 
         Ok(())
     }
 
+    #[allow(dead_code)]
     pub async fn search_tickets(&self, project_key: &str) -> Result<Vec<crate::models::ticket::JiraTicket>> {
         let jql = format!("assignee = currentUser() AND project = {}", project_key);
         self.search_with_jql(&jql, 50).await
     }
 
+    /// Sub-tasks of `parent_key`, in key order
+    pub async fn get_subtasks(&self, parent_key: &str) -> Result<Vec<JiraTicket>> {
+        let jql = JqlBuilder::new()
+            .and(jql::parent_clause(parent_key))
+            .sort(Sort { field: SortField::Key, order: SortOrder::Asc })
+            .build();
+        self.search_with_jql(&jql, 100).await
+    }
+
     pub async fn search_with_jql(&self, jql: &str, max_results: u32) -> Result<Vec<crate::models::ticket::JiraTicket>> {
-        // Allow overriding API version for Jira Data Center compatibility
-        let api_version = std::env::var("JIRA_API_VERSION").unwrap_or_else(|_| "latest".to_string());
-        let url = format!("{}/rest/api/{}/search", self.base_url, api_version);
+        self.search_with_fields(jql, max_results, &[]).await
+    }
+
+    /// `search_with_jql`, also requesting `extra_fields` (Jira field names
+    /// such as `reporter` or `duedate`)
+    pub async fn search_with_fields(
+        &self,
+        jql: &str,
+        max_results: u32,
+        extra_fields: &[&str],
+    ) -> Result<Vec<crate::models::ticket::JiraTicket>> {
+        let jql = self.resolve_current_user(jql).await?;
+        let api_version = self.api_version().await?;
+        // Cloud's v3 search moved to /search/jql; the response has no `total`
+        // but `issues` is shaped the same
+        let endpoint = if api_version == "3" { "search/jql" } else { "search" };
+        let url = format!("{}/rest/api/{}/{}", self.base_url, api_version, endpoint);
+
+        let mut fields =
+            vec!["summary", "status", "assignee", "labels", "components", "priority", "issuetype", "updated", "parent"];
+        for field in extra_fields {
+            if !fields.contains(field) {
+                fields.push(field);
+            }
+        }
 
         let body = serde_json::json!({
             "jql": jql,
-            "fields": ["summary", "status", "assignee"],
+            "fields": fields,
             "maxResults": max_results
         });
 
@@ -133,13 +334,10 @@ impl JiraClient {
             .await
             .context("Failed to send search request")?;
 
+        let response = http::check_response("Jira search", response).await?;
         let status = response.status();
         let response_text = response.text().await.unwrap_or_default();
 
-        if !status.is_success() {
-            anyhow::bail!("Jira search API error ({}): {}", status, response_text);
-        }
-
         // Debug: Show raw response text
         if std::env::var("DEVFLOW_DEBUG").is_ok() {
             eprintln!("DEBUG: Response status: {}", status);
@@ -187,300 +385,2660 @@ impl JiraClient {
         Ok(tickets)
     }
 
-    /// Test connection without parsing tickets - just validates auth and API access
-    pub async fn test_connection(&self) -> Result<()> {
-        let api_version = std::env::var("JIRA_API_VERSION").unwrap_or_else(|_| "latest".to_string());
-        let url = format!("{}/rest/api/{}/myself", self.base_url, api_version);
+    /// Add a comment to a ticket (ADF on v3, plain text otherwise)
+    pub async fn add_comment(&self, ticket_id: &str, text: &str) -> Result<()> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/issue/{}/comment", self.base_url, api_version, ticket_id);
 
-        let response = self.apply_auth(self.client.get(&url))
+        let body = if api_version == "3" {
+            adf::from_plain_text(text)
+        } else {
+            serde_json::Value::String(text.to_string())
+        };
+
+        let response = self.apply_auth(self.client.post(&url))
+            .json(&serde_json::json!({ "body": body }))
             .send()
             .await
-            .context("Failed to connect to Jira")?;
+            .context("Failed to send comment to Jira")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Jira API error ({}): {}", status, text);
-        }
+        http::check_response("Jira", response).await?;
 
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_jira_client_creation_with_api_token() {
-        let client = JiraClient::new(
-            "https://jira.example.com".to_string(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "test-token".to_string(),
-            },
+    /// The latest `limit` comments on a ticket, newest first, with the total
+    pub async fn get_comments(&self, ticket_id: &str, limit: u32) -> Result<CommentPage> {
+        let api_version = self.api_version().await?;
+        let url = format!(
+            "{}/rest/api/{}/issue/{}/comment?orderBy=-created&maxResults={}",
+            self.base_url, api_version, ticket_id, limit
         );
-        assert_eq!(client.base_url, "https://jira.example.com");
-        assert!(matches!(client.auth, AuthConfig::BasicAuth { .. }));
-    }
 
-    #[test]
-    fn test_jira_client_creation_with_pat() {
-        let client = JiraClient::new(
-            "https://jira.example.com".to_string(),
-            "test@example.com".to_string(),
-            AuthMethod::PersonalAccessToken {
-                token: "pat-token".to_string(),
-            },
-        );
-        assert_eq!(client.base_url, "https://jira.example.com");
-        assert!(matches!(client.auth, AuthConfig::BearerToken { .. }));
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to send request to Jira")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        response
+            .json::<CommentPage>()
+            .await
+            .context("Failed to parse Jira comments response")
     }
 
-    #[tokio::test]
-    async fn test_search_tickets_success() {
-        let mut server = mockito::Server::new_async().await;
+    /// Add and remove labels with `update` operations, so labels changed
+    /// by someone else in the meantime are left alone
+    pub async fn update_labels(&self, ticket_id: &str, add: &[String], remove: &[String]) -> Result<()> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/issue/{}", self.base_url, api_version, ticket_id);
 
-        let mock_response = serde_json::json!({
-            "issues": [
-                {
-                    "key": "WAB-123",
-                    "fields": {
-                        "summary": "Test ticket 1",
-                        "status": {
-                            "name": "In Progress"
-                        }
-                    }
-                },
-                {
-                    "key": "WAB-124",
-                    "fields": {
-                        "summary": "Test ticket 2",
-                        "status": {
-                            "name": "To Do"
-                        }
-                    }
-                }
-            ]
-        });
+        let operations: Vec<serde_json::Value> = add
+            .iter()
+            .map(|label| serde_json::json!({ "add": label }))
+            .chain(remove.iter().map(|label| serde_json::json!({ "remove": label })))
+            .collect();
 
-        let _m = server
-            .mock("POST", "/rest/api/latest/search")
-            .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(mock_response.to_string())
-            .create_async()
-            .await;
+        let response = self.apply_auth(self.client.put(&url))
+            .json(&serde_json::json!({ "update": { "labels": operations } }))
+            .send()
+            .await
+            .context("Failed to send label update to Jira")?;
 
-        let client = JiraClient::new(
-            server.url(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "test-token".to_string(),
-            },
-        );
+        http::check_response("Jira", response).await?;
 
-        let tickets = client.search_tickets("WAB").await.unwrap();
+        Ok(())
+    }
 
-        assert_eq!(tickets.len(), 2);
-        assert_eq!(tickets[0].key, "WAB-123");
-        assert_eq!(tickets[0].fields.summary, "Test ticket 1");
-        assert_eq!(tickets[0].fields.status.name, "In Progress");
-        assert_eq!(tickets[1].key, "WAB-124");
-        assert_eq!(tickets[1].fields.summary, "Test ticket 2");
+    /// The instance's issue link types. Cached like `get_myself`.
+    pub async fn get_issue_link_types(&self) -> Result<Vec<IssueLinkType>> {
+        self.link_types
+            .get_or_try_init(|| self.fetch_issue_link_types())
+            .await
+            .cloned()
     }
 
-    #[tokio::test]
-    async fn test_search_tickets_empty_results() {
-        let mut server = mockito::Server::new_async().await;
+    async fn fetch_issue_link_types(&self) -> Result<Vec<IssueLinkType>> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/issueLinkType", self.base_url, api_version);
 
-        let mock_response = serde_json::json!({
-            "issues": []
-        });
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to fetch issue link types")?;
 
-        let _m = server
-            .mock("POST", "/rest/api/latest/search")
-            .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(mock_response.to_string())
-            .create_async()
-            .await;
+        let response = http::check_response("Jira", response).await?;
 
-        let client = JiraClient::new(
-            server.url(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "test-token".to_string(),
-            },
-        );
+        let body = response
+            .json::<serde_json::Value>()
+            .await
+            .context("Failed to parse issue link types response")?;
 
-        let tickets = client.search_tickets("WAB").await.unwrap();
-        assert_eq!(tickets.len(), 0);
+        serde_json::from_value(body["issueLinkTypes"].clone()).context("Failed to parse issue link types")
     }
 
-    #[tokio::test]
-    async fn test_search_tickets_api_error() {
-        let mut server = mockito::Server::new_async().await;
+    /// The ticket's links to outside Jira, such as pull requests and repositories
+    pub async fn get_remote_links(&self, ticket_id: &str) -> Result<Vec<RemoteLink>> {
+        let api_version = self.api_version().await?;
+        let url = format!(
+            "{}/rest/api/{}/issue/{}/remotelink",
+            self.base_url,
+            api_version,
+            urlencoding::encode(ticket_id)
+        );
 
-        let _m = server
-            .mock("POST", "/rest/api/latest/search")
-            .with_status(401)
-            .with_body("Unauthorized")
-            .create_async()
-            .await;
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to fetch remote links")?;
 
-        let client = JiraClient::new(
-            server.url(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "invalid-token".to_string(),
-            },
-        );
+        let response = http::check_response("Jira", response).await?;
 
-        let result = client.search_tickets("WAB").await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Jira search API error"));
+        response
+            .json::<Vec<RemoteLink>>()
+            .await
+            .context("Failed to parse remote links response")
     }
 
-    #[tokio::test]
-    async fn test_search_tickets_invalid_json() {
-        let mut server = mockito::Server::new_async().await;
+    /// Link two tickets so that "`ticket_id` <relation> `other_id`" reads
+    /// true, e.g. "WAB-1 blocks WAB-2" or "WAB-1 is-blocked-by WAB-2".
+    /// `relation` is a link type name or either of its descriptions, with
+    /// dashes for spaces allowed. Returns the description that matched.
+    pub async fn create_issue_link(&self, ticket_id: &str, other_id: &str, relation: &str) -> Result<String> {
+        let types = self.get_issue_link_types().await?;
+        let Some(link) = orient_link(&types, relation, ticket_id, other_id) else {
+            let valid: Vec<&str> = types.iter().map(|t| t.name.as_str()).collect();
+            anyhow::bail!(
+                "Unknown link type '{}'. Valid types: {}\n   Relations: {}",
+                relation,
+                valid.join(", "),
+                link_relations(&types).join(", ")
+            );
+        };
 
-        let _m = server
-            .mock("POST", "/rest/api/latest/search")
-            .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body("invalid json")
-            .create_async()
-            .await;
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/issueLink", self.base_url, api_version);
+        let body = serde_json::json!({
+            "type": { "id": link.type_id },
+            "inwardIssue": { "key": link.inward },
+            "outwardIssue": { "key": link.outward },
+        });
 
-        let client = JiraClient::new(
-            server.url(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "test-token".to_string(),
-            },
-        );
+        let response = self.apply_auth(self.client.post(&url))
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send issue link request")?;
 
-        let result = client.search_tickets("WAB").await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Failed to parse search response"));
+        http::check_response("Jira", response).await?;
+
+        Ok(link.description.to_string())
     }
 
-    #[tokio::test]
-    async fn test_search_tickets_missing_issues_field() {
-        let mut server = mockito::Server::new_async().await;
+    /// Create a ticket and return its key
+    pub async fn create_issue(&self, issue: &NewIssue) -> Result<String> {
+        let api_version = self.api_version().await?;
+        let issue_type_id = match &issue.parent {
+            Some(_) => self.resolve_subtask_type(&issue.project_key).await?,
+            None => self.resolve_issue_type(&issue.project_key, &issue.issue_type).await?,
+        };
+
+        let mut fields = serde_json::json!({
+            "project": { "key": issue.project_key },
+            "summary": issue.summary,
+            "issuetype": { "id": issue_type_id },
+        });
+
+        if let Some(description) = &issue.description {
+            fields["description"] = if api_version == "3" {
+                adf::from_plain_text(description)
+            } else {
+                serde_json::Value::String(description.clone())
+            };
+        }
+
+        if !issue.labels.is_empty() {
+            fields["labels"] = serde_json::json!(issue.labels);
+        }
+
+        if !issue.components.is_empty() {
+            let components = self.resolve_components(&issue.project_key, &issue.components).await?;
+            fields["components"] = component_refs(&components);
+        }
+
+        if let Some(priority) = &issue.priority {
+            fields["priority"] = serde_json::json!({ "name": priority });
+        }
+
+        if issue.assign_to_me {
+            fields["assignee"] = self.current_user_ref().await?;
+        }
+
+        if let Some(parent) = &issue.parent {
+            fields["parent"] = serde_json::json!({ "key": parent });
+        }
+
+        let url = format!("{}/rest/api/{}/issue", self.base_url, api_version);
+        let body = serde_json::json!({ "fields": fields });
+
+        if std::env::var("DEVFLOW_DEBUG").is_ok() {
+            eprintln!("DEBUG: Request URL: {}", url);
+            eprintln!("DEBUG: Request body: {}", serde_json::to_string_pretty(&body).unwrap_or_default());
+        }
+
+        let response = self.apply_auth(self.client.post(&url))
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send issue creation request")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        let created = response
+            .json::<serde_json::Value>()
+            .await
+            .context("Failed to parse issue creation response")?;
+
+        created["key"]
+            .as_str()
+            .map(String::from)
+            .context("No 'key' field in issue creation response")
+    }
+
+    /// Issue types a project can create. Uses the per-project createmeta
+    /// endpoint, falling back to the older `createmeta?projectKeys=` form on
+    /// instances that don't have it.
+    async fn get_issue_types(&self, project_key: &str) -> Result<Vec<serde_json::Value>> {
+        let api_version = self.api_version().await?;
+        let url = format!(
+            "{}/rest/api/{}/issue/createmeta/{}/issuetypes",
+            self.base_url, api_version, project_key
+        );
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to fetch issue types")?;
+
+        let types = if response.status() == reqwest::StatusCode::NOT_FOUND {
+            let url = format!(
+                "{}/rest/api/{}/issue/createmeta?projectKeys={}",
+                self.base_url,
+                api_version,
+                urlencoding::encode(project_key)
+            );
+            let response = self.apply_auth(self.client.get(&url))
+                .send()
+                .await
+                .context("Failed to fetch issue types")?;
+
+            let response = http::check_response("Jira", response).await?;
+
+            let body = response.json::<serde_json::Value>().await.context("Failed to parse createmeta response")?;
+            body["projects"][0]["issuetypes"].as_array().cloned().unwrap_or_default()
+        } else if response.status().is_success() {
+            let body = response.json::<serde_json::Value>().await.context("Failed to parse createmeta response")?;
+            // The paginated endpoint has used both names for the list
+            body["issueTypes"]
+                .as_array()
+                .or_else(|| body["values"].as_array())
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            return Err(http::ApiError::from_response("Jira", response).await.into());
+        };
+
+        Ok(types)
+    }
+
+    /// Resolve an issue type name (case-insensitive) to its ID for a project
+    async fn resolve_issue_type(&self, project_key: &str, type_name: &str) -> Result<String> {
+        let types = self.get_issue_types(project_key).await?;
+
+        let found = types.iter().find(|t| {
+            t["name"].as_str().is_some_and(|name| name.eq_ignore_ascii_case(type_name))
+        });
+
+        match found.and_then(|t| t["id"].as_str()) {
+            Some(id) => Ok(id.to_string()),
+            None => {
+                let valid: Vec<&str> = types.iter().filter_map(|t| t["name"].as_str()).collect();
+                anyhow::bail!(
+                    "Unknown issue type '{}' for project {}. Valid types: {}",
+                    type_name,
+                    project_key,
+                    valid.join(", ")
+                )
+            }
+        }
+    }
+
+    /// ID of the project's sub-task issue type ("Sub-task" by default, but
+    /// renameable, so matched on the `subtask` flag)
+    async fn resolve_subtask_type(&self, project_key: &str) -> Result<String> {
+        let types = self.get_issue_types(project_key).await?;
+
+        let found = types.iter().find(|t| t["subtask"].as_bool() == Some(true));
+        match found.and_then(|t| t["id"].as_str()) {
+            Some(id) => Ok(id.to_string()),
+            None => anyhow::bail!(
+                "Project {} has no sub-task issue type; enable sub-tasks in its issue type scheme",
+                project_key
+            ),
+        }
+    }
+
+    pub async fn get_components(&self, project_key: &str) -> Result<Vec<Component>> {
+        let api_version = self.api_version().await?;
+        let url = format!(
+            "{}/rest/api/{}/project/{}/components",
+            self.base_url,
+            api_version,
+            urlencoding::encode(project_key)
+        );
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to fetch project components")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        response
+            .json::<Vec<Component>>()
+            .await
+            .context("Failed to parse project components response")
+    }
+
+    /// Match component names (case-insensitive) to the project's components,
+    /// suggesting close matches for names that don't exist
+    async fn resolve_components(&self, project_key: &str, names: &[String]) -> Result<Vec<String>> {
+        let components = self.get_components(project_key).await?;
+        let valid: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+
+        names
+            .iter()
+            .map(|name| {
+                if let Some(found) = valid.iter().find(|valid| valid.eq_ignore_ascii_case(name.trim())) {
+                    return Ok(found.to_string());
+                }
+                let hint = match close_matches(name, &valid).as_slice() {
+                    [] => format!("Valid components: {}", valid.join(", ")),
+                    close => format!("Did you mean: {}?", close.join(", ")),
+                };
+                anyhow::bail!("Unknown component '{}' for project {}. {}", name, project_key, hint)
+            })
+            .collect()
+    }
+
+    /// Replace a ticket's components; names are checked against its project's
+    pub async fn set_components(&self, ticket_id: &str, names: &[String]) -> Result<Vec<String>> {
+        let components = self.resolve_components(&project_key_of(ticket_id), names).await?;
+        self.update_ticket_fields(ticket_id, serde_json::json!({ "components": component_refs(&components) }))
+            .await?;
+        Ok(components)
+    }
+
+    /// Set fields on a ticket, e.g. `{"components": [...]}`. Jira answers a
+    /// bad value with a 400 naming each rejected field; that comes back as a
+    /// `DevFlowError::JiraApiError`.
+    pub async fn update_ticket_fields(&self, ticket_id: &str, fields: serde_json::Value) -> Result<()> {
+        if fields.as_object().is_none_or(|fields| fields.is_empty()) {
+            anyhow::bail!("No fields given to update on {}", ticket_id);
+        }
+
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/issue/{}", self.base_url, api_version, ticket_id);
+
+        let response = self.apply_auth(self.client.put(&url))
+            .json(&serde_json::json!({ "fields": fields }))
+            .send()
+            .await
+            .context(format!("Failed to send field update for {} to Jira", ticket_id))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(DevFlowError::JiraApiError(status.as_u16(), jira_error_message(&body)).into());
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_versions(&self, project_key: &str) -> Result<Vec<Version>> {
+        let api_version = self.api_version().await?;
+        let url = format!(
+            "{}/rest/api/{}/project/{}/versions",
+            self.base_url,
+            api_version,
+            urlencoding::encode(project_key)
+        );
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to fetch project versions")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        response
+            .json::<Vec<Version>>()
+            .await
+            .context("Failed to parse project versions response")
+    }
+
+    pub async fn create_version(&self, project_key: &str, name: &str) -> Result<Version> {
+        self.create_release_version(project_key, &JiraRelease { name: name.to_string(), released: false }).await
+    }
+
+    /// Add a version to a project, released or not
+    pub async fn create_release_version(&self, project_key: &str, release: &JiraRelease) -> Result<Version> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/version", self.base_url, api_version);
+
+        let response = self.apply_auth(self.client.post(&url))
+            .json(&serde_json::json!({ "name": release.name, "released": release.released, "project": project_key }))
+            .send()
+            .await
+            .context("Failed to send version creation request")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        response
+            .json::<Version>()
+            .await
+            .context("Failed to parse version creation response")
+    }
+
+    /// Match a version name (case-insensitive) to one of the project's
+    /// versions, creating it when `create` is set and it doesn't exist
+    pub async fn resolve_version(&self, project_key: &str, name: &str, create: bool) -> Result<String> {
+        let versions = self.get_versions(project_key).await?;
+        if let Some(found) = versions.iter().find(|v| v.name.eq_ignore_ascii_case(name.trim())) {
+            return Ok(found.name.clone());
+        }
+        if create {
+            return Ok(self.create_version(project_key, name.trim()).await?.name);
+        }
+
+        let names: Vec<&str> = versions.iter().map(|v| v.name.as_str()).collect();
+        let unreleased: Vec<&str> = versions
+            .iter()
+            .filter(|v| !v.released && !v.archived)
+            .map(|v| v.name.as_str())
+            .collect();
+        let hint = match close_matches(name, &names).as_slice() {
+            [] if unreleased.is_empty() => "The project has no unreleased versions".to_string(),
+            [] => format!("Unreleased versions: {}", unreleased.join(", ")),
+            close => format!("Did you mean: {}?", close.join(", ")),
+        };
+        anyhow::bail!(
+            "Unknown version '{}' for project {}. {}\n   Pass --create-version to create it",
+            name,
+            project_key,
+            hint
+        )
+    }
+
+    /// Add a fix version with an `update` operation, keeping any others
+    pub async fn add_fix_version(&self, ticket_id: &str, name: &str) -> Result<()> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/issue/{}", self.base_url, api_version, ticket_id);
+
+        let response = self.apply_auth(self.client.put(&url))
+            .json(&serde_json::json!({ "update": { "fixVersions": [{ "add": { "name": name } }] } }))
+            .send()
+            .await
+            .context("Failed to send fix version update to Jira")?;
+
+        http::check_response("Jira", response).await?;
+
+        Ok(())
+    }
+
+    /// Mark a version released, as the Release button on the project's
+    /// Releases page does
+    pub async fn release_version(&self, version: &Version) -> Result<Version> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/version/{}", self.base_url, api_version, version.id);
+
+        let response = self.apply_auth(self.client.put(&url))
+            .json(&serde_json::json!({ "released": true }))
+            .send()
+            .await
+            .context("Failed to send version release request")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        response
+            .json::<Version>()
+            .await
+            .context("Failed to parse version release response")
+    }
+
+    /// The authenticated user. Cached, so repeated calls don't hit the API.
+    pub async fn get_myself(&self) -> Result<JiraUser> {
+        self.myself
+            .get_or_try_init(|| self.fetch_myself())
+            .await
+            .cloned()
+    }
+
+    async fn fetch_myself(&self) -> Result<JiraUser> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/myself", self.base_url, api_version);
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to connect to Jira")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        let me = response.json::<serde_json::Value>().await.context("Failed to parse user response")?;
+
+        JiraUser::from_value(&me).context("Could not determine current Jira user")
+    }
+
+    /// Users matching a name, username or email. Server/Data Center takes
+    /// the search as `username`, Cloud as `query`.
+    pub async fn search_users(&self, query: &str) -> Result<Vec<JiraUser>> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/user/search", self.base_url, api_version);
+        let param = match self.auth {
+            AuthConfig::BearerToken { .. } => "username",
+            AuthConfig::BasicAuth { .. } => "query",
+        };
+
+        let response = self.apply_auth(self.client.get(&url))
+            .query(&[(param, query)])
+            .send()
+            .await
+            .context("Failed to search Jira users")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        let users = response
+            .json::<Vec<serde_json::Value>>()
+            .await
+            .context("Failed to parse user search response")?;
+
+        Ok(users.iter().filter_map(JiraUser::from_value).collect())
+    }
+
+    /// Reference to a user for assignee fields: `name` on Server/Data
+    /// Center (PAT auth), `accountId` on Cloud. `None` means nobody.
+    fn user_ref(&self, account_id: Option<&str>) -> serde_json::Value {
+        match self.auth {
+            AuthConfig::BearerToken { .. } => serde_json::json!({ "name": account_id }),
+            AuthConfig::BasicAuth { .. } => serde_json::json!({ "accountId": account_id }),
+        }
+    }
+
+    async fn current_user_ref(&self) -> Result<serde_json::Value> {
+        let me = self.get_myself().await?;
+        Ok(self.user_ref(Some(&me.account_id)))
+    }
+
+    /// Assign a ticket to `assignee`, or unassign it when `None`
+    pub async fn assign_issue(&self, ticket_id: &str, assignee: Option<&JiraUser>) -> Result<()> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/issue/{}/assignee", self.base_url, api_version, ticket_id);
+        let body = self.user_ref(assignee.map(|user| user.account_id.as_str()));
+
+        let response = self.apply_auth(self.client.put(&url))
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send assignee update to Jira")?;
+
+        http::check_response("Jira", response).await?;
+
+        Ok(())
+    }
+
+    /// `currentUser()` silently matches nothing on some Data Center setups,
+    /// so PAT searches name the user explicitly instead
+    async fn resolve_current_user(&self, jql: &str) -> Result<String> {
+        if !matches!(self.auth, AuthConfig::BearerToken { .. }) || !jql.contains("currentUser()") {
+            return Ok(jql.to_string());
+        }
+
+        let me = self.get_myself().await?;
+        let user = format!("\"{}\"", me.account_id.replace('"', "\\\""));
+
+        Ok(jql.replace("currentUser()", &user))
+    }
+
+    /// Check that a project key exists and is visible to the current user
+    pub async fn get_project(&self, project_key: &str) -> Result<()> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/project/{}", self.base_url, api_version, project_key);
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to send request to Jira")?;
+
+        http::check_response("Jira", response).await?;
+
+        Ok(())
+    }
+
+    /// Every project the current user can browse
+    pub async fn get_projects(&self) -> Result<Vec<JiraProject>> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/project", self.base_url, api_version);
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to send request to Jira")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        response
+            .json::<Vec<JiraProject>>()
+            .await
+            .context("Failed to parse Jira projects response")
+    }
+
+    /// Who the client authenticates as, for caching per user: the email with
+    /// basic auth, a hash of the token otherwise, so the token isn't stored
+    fn credentials_id(&self) -> String {
+        use std::hash::{Hash, Hasher};
+
+        match &self.auth {
+            AuthConfig::BasicAuth { email, .. } => email.to_lowercase(),
+            AuthConfig::BearerToken { token } => {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                token.hash(&mut hasher);
+                format!("token-{:016x}", hasher.finish())
+            }
+        }
+    }
+
+    /// `get_projects` through the local cache for a day (see
+    /// `cache::PROJECT_LIST_TTL`), unless `--no-cache` is set. Each user has
+    /// their own list, as Jira only lists the projects they can browse.
+    pub async fn get_projects_cached(&self) -> Result<Vec<JiraProject>> {
+        if cache::is_disabled() {
+            return self.get_projects().await;
+        }
+
+        let user = self.credentials_id();
+        let mut cache = TicketCache::load();
+        if let Some(projects) = cache.projects(&self.base_url, &user, cache::PROJECT_LIST_TTL) {
+            return Ok(projects.to_vec());
+        }
+
+        let projects = self.get_projects().await?;
+        cache.insert_projects(&self.base_url, &user, projects.clone());
+        // The cache is only an optimisation
+        let _ = cache.save();
+
+        Ok(projects)
+    }
+
+    /// Boards for a project, from the Agile API (independent of the REST API version)
+    pub async fn get_boards(&self, project_key: &str) -> Result<Vec<JiraBoard>> {
+        let url = format!(
+            "{}/rest/agile/1.0/board?projectKeyOrId={}",
+            self.base_url,
+            urlencoding::encode(project_key)
+        );
+
+        self.get_agile_pages(&url).await
+    }
+
+    /// One board by ID, from the Agile API
+    pub async fn get_board(&self, board_id: u64) -> Result<JiraBoard> {
+        let url = format!("{}/rest/agile/1.0/board/{}", self.base_url, board_id);
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to send request to Jira")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        response
+            .json::<JiraBoard>()
+            .await
+            .context("Failed to parse Jira board response")
+    }
+
+    /// All sprints on a (scrum) board, oldest first
+    pub async fn get_sprints(&self, board_id: u64) -> Result<Vec<Sprint>> {
+        let url = format!("{}/rest/agile/1.0/board/{}/sprint", self.base_url, board_id);
+
+        self.get_agile_pages(&url).await
+    }
+
+    /// Tickets of a sprint whose status is in the Done category
+    pub async fn get_done_sprint_tickets(&self, sprint_id: u64) -> Result<Vec<JiraTicket>> {
+        let jql = JqlBuilder::new()
+            .and(format!("sprint = {}", sprint_id))
+            .and("statusCategory = Done")
+            .sort(Sort { field: SortField::Key, order: SortOrder::Asc })
+            .build();
+        self.search_with_fields(&jql, 500, &["fixVersions"]).await
+    }
+
+    /// Close an active sprint. Jira moves its unfinished tickets to the backlog.
+    pub async fn close_sprint(&self, sprint_id: u64) -> Result<()> {
+        let url = format!("{}/rest/agile/1.0/sprint/{}", self.base_url, sprint_id);
+
+        let response = self.apply_auth(self.client.post(&url))
+            .json(&serde_json::json!({ "state": "closed" }))
+            .send()
+            .await
+            .context("Failed to send sprint close request")?;
+
+        http::check_response("Jira", response).await?;
+
+        Ok(())
+    }
+
+    /// Follow `startAt`/`isLast` paging on an Agile API list endpoint
+    async fn get_agile_pages<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+
+        loop {
+            let response = self.apply_auth(self.client.get(url))
+                .query(&[("startAt", items.len())])
+                .send()
+                .await
+                .context("Failed to send request to Jira")?;
+
+            let response = http::check_response("Jira", response).await?;
+
+            let page = response
+                .json::<AgilePage<T>>()
+                .await
+                .context("Failed to parse Jira Agile response")?;
+
+            let done = page.is_last || page.values.is_empty();
+            items.extend(page.values);
+
+            if done {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// Names of the transitions currently available on a ticket
+    pub async fn get_transition_names(&self, ticket_id: &str) -> Result<Vec<String>> {
+        let transitions = self.get_transitions(ticket_id).await?;
+        Ok(transitions.into_iter().map(|t| t.name).collect())
+    }
+
+    /// Transitions available from the ticket's current status, with the
+    /// fields each one requires
+    pub async fn get_transitions(&self, ticket_id: &str) -> Result<Vec<Transition>> {
+        let api_version = self.api_version().await?;
+        let url = format!(
+            "{}/rest/api/{}/issue/{}/transitions?expand=transitions.fields",
+            self.base_url, api_version, ticket_id
+        );
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to send request to Jira")?;
+
+        let response = http::check_response("Jira", response).await?;
+
+        let body = response
+            .json::<TransitionsResponse>()
+            .await
+            .context("Failed to parse transitions response")?;
+
+        Ok(body.transitions)
+    }
+
+    /// The server's clock, taken from the `Date` header of a cheap request
+    pub async fn server_time(&self) -> Result<std::time::SystemTime> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/serverInfo", self.base_url, api_version);
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to connect to Jira")?;
+
+        let date = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .context("Jira response has no Date header")?;
+
+        httpdate::parse_http_date(date).context("Failed to parse Jira Date header")
+    }
+
+    /// Test connection without parsing tickets - just validates auth and API access
+    pub async fn test_connection(&self) -> Result<()> {
+        let api_version = self.api_version().await?;
+        let url = format!("{}/rest/api/{}/myself", self.base_url, api_version);
+
+        let response = self.apply_auth(self.client.get(&url))
+            .send()
+            .await
+            .context("Failed to connect to Jira")?;
+
+        http::check_response("Jira", response).await?;
+
+        Ok(())
+    }
+}
+
+/// Match a link type by name or outward description, then by inward
+/// description (case-insensitive, `is-blocked-by` matching "is blocked by").
+/// The flag is set for inward matches.
+fn find_link_type<'a>(types: &'a [IssueLinkType], query: &str) -> Option<(&'a IssueLinkType, bool)> {
+    let query = query.trim().replace(['-', '_'], " ");
+    let query = query.as_str();
+    types
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(query) || t.outward.eq_ignore_ascii_case(query))
+        .map(|t| (t, false))
+        .or_else(|| types.iter().find(|t| t.inward.eq_ignore_ascii_case(query)).map(|t| (t, true)))
+}
+
+/// `POST /issueLink` reads "inwardIssue <outward description> outwardIssue"
+#[derive(Debug, PartialEq, Eq)]
+struct OrientedLink<'a> {
+    type_id: &'a str,
+    description: &'a str,
+    inward: &'a str,
+    outward: &'a str,
+}
+
+/// Place the tickets of "`ticket` <relation> `other`" in the link's slots:
+/// as given for an outward relation ("blocks"), swapped for an inward one
+/// ("is blocked by")
+fn orient_link<'a>(types: &'a [IssueLinkType], relation: &str, ticket: &'a str, other: &'a str) -> Option<OrientedLink<'a>> {
+    let (found, reversed) = find_link_type(types, relation)?;
+    Some(if reversed {
+        OrientedLink { type_id: &found.id, description: &found.inward, inward: other, outward: ticket }
+    } else {
+        OrientedLink { type_id: &found.id, description: &found.outward, inward: ticket, outward: other }
+    })
+}
+
+/// Every description as a relation argument, e.g. `blocks`, `is-blocked-by`
+fn link_relations(types: &[IssueLinkType]) -> Vec<String> {
+    let mut relations: Vec<String> = Vec::new();
+    for description in types.iter().flat_map(|t| [&t.outward, &t.inward]) {
+        let relation = description.to_lowercase().replace(' ', "-");
+        if !relations.contains(&relation) {
+            relations.push(relation);
+        }
+    }
+    relations
+}
+
+/// Jira's `{"errorMessages": [...], "errors": {"field": "message"}}` as one
+/// line, e.g. "duedate: invalid date". Anything else is returned as is.
+fn jira_error_message(body: &str) -> String {
+    #[derive(serde::Deserialize)]
+    struct ErrorBody {
+        #[serde(rename = "errorMessages", default)]
+        error_messages: Vec<String>,
+        #[serde(default)]
+        errors: std::collections::BTreeMap<String, String>,
+    }
+
+    let Ok(parsed) = serde_json::from_str::<ErrorBody>(body) else {
+        return body.to_string();
+    };
+    let messages: Vec<String> = parsed
+        .error_messages
+        .into_iter()
+        .chain(parsed.errors.into_iter().map(|(field, message)| format!("{}: {}", field, message)))
+        .collect();
+    if messages.is_empty() {
+        body.to_string()
+    } else {
+        messages.join("; ")
+    }
+}
+
+/// Project key of a ticket, e.g. `WAB` for `wab-123`
+pub fn project_key_of(ticket_id: &str) -> String {
+    ticket_id.split('-').next().unwrap_or(ticket_id).to_uppercase()
+}
+
+fn component_refs(names: &[String]) -> serde_json::Value {
+    names.iter().map(|name| serde_json::json!({ "name": name })).collect()
+}
+
+/// `fixVersions` for `update_ticket_fields`: the ticket's current fix
+/// versions plus `name`, since setting the field replaces the whole list
+pub fn fix_versions_with(current: &[Version], name: &str) -> serde_json::Value {
+    let mut names: Vec<&str> = current.iter().map(|version| version.name.as_str()).collect();
+    if !names.iter().any(|existing| existing.eq_ignore_ascii_case(name)) {
+        names.push(name);
+    }
+    serde_json::json!({ "fixVersions": names.iter().map(|name| serde_json::json!({ "name": name })).collect::<Vec<_>>() })
+}
+
+/// Names within a couple of typos of `query`, or containing it
+fn close_matches<'a>(query: &str, names: &[&'a str]) -> Vec<&'a str> {
+    let query = query.trim().to_lowercase();
+    names
+        .iter()
+        .filter(|name| {
+            let name = name.to_lowercase();
+            name.contains(&query) || query.contains(&name) || edit_distance(&query, &name) <= 2.max(query.len() / 4)
+        })
+        .copied()
+        .collect()
+}
+
+/// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jira_client_creation_with_api_token() {
+        let client = JiraClient::new(
+            "https://jira.example.com".to_string(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+        assert_eq!(client.base_url, "https://jira.example.com");
+        assert!(matches!(client.auth, AuthConfig::BasicAuth { .. }));
+    }
+
+    #[test]
+    fn test_jira_client_creation_with_pat() {
+        let client = JiraClient::new(
+            "https://jira.example.com".to_string(),
+            "test@example.com".to_string(),
+            AuthMethod::PersonalAccessToken {
+                token: "pat-token".to_string(),
+            },
+        );
+        assert_eq!(client.base_url, "https://jira.example.com");
+        assert!(matches!(client.auth, AuthConfig::BearerToken { .. }));
+    }
+
+    fn cloud_client(url: String) -> JiraClient {
+        JiraClient::new(
+            url,
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        )
+        .with_api_version(ApiVersion::V3)
+    }
+
+    #[tokio::test]
+    async fn test_get_ticket_revalidates_with_etag() {
+        let mut server = mockito::Server::new_async().await;
+        let body = serde_json::json!({
+            "key": "WAB-1",
+            "fields": { "summary": "Login fails", "status": { "name": "To Do" } }
+        })
+        .to_string();
+
+        let full = server
+            .mock("GET", "/rest/api/3/issue/WAB-1")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("etag", "\"v1\"")
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/rest/api/3/issue/WAB-1")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        for _ in 0..3 {
+            let ticket = client.get_ticket("WAB-1").await.unwrap();
+            assert_eq!(ticket.fields.summary, "Login fails");
+        }
+
+        full.assert_async().await;
+        not_modified.assert_async().await;
+        assert_eq!(client.etags.hits.load(Ordering::Relaxed), 2);
+        assert_eq!(client.etags.misses.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_resolves_type_and_wraps_adf() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _meta = server
+            .mock("GET", "/rest/api/3/issue/createmeta/WAB/issuetypes")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({
+                "issueTypes": [
+                    { "id": "10001", "name": "Task" },
+                    { "id": "10004", "name": "Bug" }
+                ]
+            }).to_string())
+            .create_async()
+            .await;
+
+        let create = server
+            .mock("POST", "/rest/api/3/issue")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "fields": {
+                    "project": { "key": "WAB" },
+                    "summary": "Login fails",
+                    "issuetype": { "id": "10004" },
+                    "labels": ["auth"],
+                    "priority": { "name": "High" },
+                    "description": {
+                        "type": "doc",
+                        "version": 1,
+                        "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Steps" }] }]
+                    }
+                }
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"1","key":"WAB-42"}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let issue = NewIssue {
+            project_key: "WAB".to_string(),
+            summary: "Login fails".to_string(),
+            issue_type: "bug".to_string(),
+            description: Some("Steps".to_string()),
+            labels: vec!["auth".to_string()],
+            components: Vec::new(),
+            priority: Some("High".to_string()),
+            assign_to_me: false,
+            parent: None,
+        };
+
+        let key = client.create_issue(&issue).await.unwrap();
+        assert_eq!(key, "WAB-42");
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_resolve_issue_type_falls_back_to_legacy_createmeta() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _new = server
+            .mock("GET", "/rest/api/latest/issue/createmeta/WAB/issuetypes")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let _legacy = server
+            .mock("GET", "/rest/api/latest/issue/createmeta")
+            .match_query(mockito::Matcher::UrlEncoded("projectKeys".into(), "WAB".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({
+                "projects": [{ "key": "WAB", "issuetypes": [{ "id": "3", "name": "Story" }] }]
+            }).to_string())
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::PersonalAccessToken {
+                token: "pat-token".to_string(),
+            },
+        );
+
+        let id = client.resolve_issue_type("WAB", "Story").await.unwrap();
+        assert_eq!(id, "3");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_issue_type_unknown_lists_valid_types() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _meta = server
+            .mock("GET", "/rest/api/3/issue/createmeta/WAB/issuetypes")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({
+                "values": [
+                    { "id": "10001", "name": "Task" },
+                    { "id": "10002", "name": "Story" }
+                ]
+            }).to_string())
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let err = client.resolve_issue_type("WAB", "Epic").await.unwrap_err().to_string();
+        assert!(err.contains("Unknown issue type 'Epic'"));
+        assert!(err.contains("Task, Story"));
+    }
+
+    const SUBTASK_TYPES: &str = r#"{"issueTypes":[
+        {"id":"10001","name":"Task","subtask":false},
+        {"id":"10005","name":"Sub-task","subtask":true}
+    ]}"#;
+
+    #[tokio::test]
+    async fn test_create_subtask_sets_parent_and_subtask_type() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _meta = server
+            .mock("GET", "/rest/api/3/issue/createmeta/WAB/issuetypes")
+            .with_status(200)
+            .with_body(SUBTASK_TYPES)
+            .create_async()
+            .await;
+
+        let create = server
+            .mock("POST", "/rest/api/3/issue")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "fields": {
+                    "project": { "key": "WAB" },
+                    "summary": "Write migration",
+                    "issuetype": { "id": "10005" },
+                    "parent": { "key": "WAB-7" }
+                }
+            })))
+            .with_status(201)
+            .with_body(r#"{"id":"2","key":"WAB-43"}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let issue = NewIssue {
+            project_key: "WAB".to_string(),
+            summary: "Write migration".to_string(),
+            parent: Some("WAB-7".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(client.create_issue(&issue).await.unwrap(), "WAB-43");
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_subtask_without_subtask_type() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _meta = server
+            .mock("GET", "/rest/api/3/issue/createmeta/WAB/issuetypes")
+            .with_status(200)
+            .with_body(r#"{"issueTypes":[{"id":"10001","name":"Task","subtask":false}]}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let issue = NewIssue {
+            project_key: "WAB".to_string(),
+            summary: "Write migration".to_string(),
+            parent: Some("WAB-7".to_string()),
+            ..Default::default()
+        };
+
+        let err = client.create_issue(&issue).await.unwrap_err();
+        assert!(err.to_string().contains("Project WAB has no sub-task issue type"));
+    }
+
+    #[tokio::test]
+    async fn test_get_subtasks_queries_by_parent() {
+        let mut server = mockito::Server::new_async().await;
+
+        let search = server
+            .mock("POST", "/rest/api/3/search/jql")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "jql": "parent = \"WAB-7\" ORDER BY key ASC",
+                "fields": [
+                    "summary", "status", "assignee", "labels", "components", "priority", "issuetype", "updated", "parent"
+                ]
+            })))
+            .with_status(200)
+            .with_body(serde_json::json!({
+                "issues": [{
+                    "key": "WAB-43",
+                    "fields": {
+                        "summary": "Write migration",
+                        "status": { "name": "In Progress" },
+                        "parent": { "key": "WAB-7", "fields": { "summary": "Billing" } }
+                    }
+                }]
+            }).to_string())
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let subtasks = client.get_subtasks("WAB-7").await.unwrap();
+        search.assert_async().await;
+        assert_eq!(subtasks.len(), 1);
+        assert_eq!(subtasks[0].fields.status.name, "In Progress");
+        assert_eq!(subtasks[0].fields.parent.as_ref().unwrap().key, "WAB-7");
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_assign_me_uses_account_id() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _meta = server
+            .mock("GET", "/rest/api/3/issue/createmeta/WAB/issuetypes")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"issueTypes":[{"id":"10001","name":"Task"}]}"#)
+            .create_async()
+            .await;
+
+        let _me = server
+            .mock("GET", "/rest/api/3/myself")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"accountId":"abc-123","displayName":"Me"}"#)
+            .create_async()
+            .await;
+
+        let create = server
+            .mock("POST", "/rest/api/3/issue")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "fields": { "assignee": { "accountId": "abc-123" } }
+            })))
+            .with_status(201)
+            .with_body(r#"{"key":"WAB-43"}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let issue = NewIssue {
+            project_key: "WAB".to_string(),
+            summary: "Chore".to_string(),
+            issue_type: "Task".to_string(),
+            assign_to_me: true,
+            ..Default::default()
+        };
+
+        assert_eq!(client.create_issue(&issue).await.unwrap(), "WAB-43");
+        create.assert_async().await;
+    }
+
+    /// Simulated Jira that only speaks one REST API version. v3 answers
+    /// /myself and stores descriptions as ADF; v2 (Server/DC) 404s on v3.
+    async fn versioned_server(version: &str) -> mockito::ServerGuard {
+        let mut server = mockito::Server::new_async().await;
+
+        let description = if version == "3" {
+            serde_json::json!({
+                "type": "doc",
+                "version": 1,
+                "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Broken on Safari" }] }]
+            })
+        } else {
+            serde_json::json!("Broken on Safari")
+        };
+
+        let search_path = if version == "3" { "/rest/api/3/search/jql" } else { "/rest/api/2/search" };
+        let mut search_body = serde_json::json!({
+            "issues": [{ "key": "WAB-7", "fields": { "summary": "Login bug", "status": { "name": "To Do" } } }]
+        });
+        if version == "2" {
+            search_body["total"] = serde_json::json!(1);
+        } else {
+            search_body["isLast"] = serde_json::json!(true);
+        }
+
+        server
+            .mock("GET", "/rest/api/3/myself")
+            .with_status(if version == "3" { 200 } else { 404 })
+            .with_body(r#"{"accountId":"abc"}"#)
+            .create_async()
+            .await;
+
+        server
+            .mock("GET", format!("/rest/api/{}/issue/WAB-7", version).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({
+                "key": "WAB-7",
+                "fields": {
+                    "summary": "Login bug",
+                    "description": description,
+                    "status": { "name": "To Do" }
+                }
+            }).to_string())
+            .create_async()
+            .await;
+
+        server
+            .mock("POST", search_path)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(search_body.to_string())
+            .create_async()
+            .await;
+
+        server
+    }
+
+    fn auto_client(url: String) -> JiraClient {
+        JiraClient::new(
+            url,
+            "test@example.com".to_string(),
+            AuthMethod::PersonalAccessToken {
+                token: "pat-token".to_string(),
+            },
+        )
+        .with_api_version(ApiVersion::Auto)
+    }
+
+    #[tokio::test]
+    async fn test_same_operations_against_v2_and_v3() {
+        for version in ["2", "3"] {
+            let server = versioned_server(version).await;
+            let client = auto_client(server.url());
+
+            let ticket = client.get_ticket("WAB-7").await.unwrap();
+            assert_eq!(ticket.fields.summary, "Login bug", "v{}", version);
+            assert_eq!(
+                ticket.fields.description.unwrap().to_terminal(),
+                "Broken on Safari",
+                "v{}",
+                version
+            );
+
+            let tickets = client.search_with_jql("project = WAB", 10).await.unwrap();
+            assert_eq!(tickets.len(), 1, "v{}", version);
+            assert_eq!(tickets[0].key, "WAB-7", "v{}", version);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_detection_probes_once() {
+        let mut server = mockito::Server::new_async().await;
+
+        let probe = server
+            .mock("GET", "/rest/api/3/myself")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let _myself = server
+            .mock("GET", "/rest/api/2/myself")
+            .with_status(200)
+            .with_body("{}")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = auto_client(server.url());
+        client.test_connection().await.unwrap();
+        client.test_connection().await.unwrap();
+        probe.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_plain_description_on_v2() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _meta = server
+            .mock("GET", "/rest/api/2/issue/createmeta/WAB/issuetypes")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"values":[{"id":"1","name":"Task"}]}"#)
+            .create_async()
+            .await;
+
+        let create = server
+            .mock("POST", "/rest/api/2/issue")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "fields": { "description": "Plain text" }
+            })))
+            .with_status(201)
+            .with_body(r#"{"key":"WAB-8"}"#)
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::PersonalAccessToken {
+                token: "pat-token".to_string(),
+            },
+        )
+        .with_api_version(ApiVersion::V2);
+
+        let issue = NewIssue {
+            project_key: "WAB".to_string(),
+            summary: "Chore".to_string(),
+            issue_type: "Task".to_string(),
+            description: Some("Plain text".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(client.create_issue(&issue).await.unwrap(), "WAB-8");
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_project_not_found() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/latest/project/NOPE")
+            .with_status(404)
+            .with_body("{\"errorMessages\":[\"No project could be found with key 'NOPE'.\"]}")
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let result = client.get_project("NOPE").await;
+        assert!(result.unwrap_err().to_string().contains("404"));
+    }
+
+    #[tokio::test]
+    async fn test_get_transition_names() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_response = serde_json::json!({
+            "transitions": [
+                { "id": "11", "name": "In Progress" },
+                { "id": "21", "name": "In Review" }
+            ]
+        });
+
+        let _m = server
+            .mock("GET", "/rest/api/latest/issue/WAB-1/transitions")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::PersonalAccessToken {
+                token: "pat-token".to_string(),
+            },
+        );
+
+        let names = client.get_transition_names("WAB-1").await.unwrap();
+        assert_eq!(names, vec!["In Progress", "In Review"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_status_unknown_transition_lists_available() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/latest/issue/WAB-1/transitions")
+            .match_query(mockito::Matcher::UrlEncoded("expand".into(), "transitions.fields".into()))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "transitions": [
+                        { "id": "11", "name": "Start Progress", "to": { "name": "In Progress" } },
+                        { "id": "21", "name": "Resolve", "to": { "name": "Resolved" } }
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::PersonalAccessToken {
+                token: "pat-token".to_string(),
+            },
+        );
+
+        let err = client.update_status("WAB-1", "In Progress").await.unwrap_err();
+        let output = err.to_string();
+        assert!(output.contains("'In Progress' not found"));
+        assert!(output.contains("Available transitions: Start Progress, Resolve"));
+    }
+
+    #[tokio::test]
+    async fn test_server_time_reads_date_header() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/latest/serverInfo")
+            .with_status(200)
+            .with_header("date", "Sun, 06 Nov 1994 08:49:37 GMT")
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let time = client.server_time().await.unwrap();
+        let secs = time.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        assert_eq!(secs, 784111777);
+    }
+
+    #[tokio::test]
+    async fn test_search_tickets_success() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_response = serde_json::json!({
+            "issues": [
+                {
+                    "key": "WAB-123",
+                    "fields": {
+                        "summary": "Test ticket 1",
+                        "status": {
+                            "name": "In Progress"
+                        }
+                    }
+                },
+                {
+                    "key": "WAB-124",
+                    "fields": {
+                        "summary": "Test ticket 2",
+                        "status": {
+                            "name": "To Do"
+                        }
+                    }
+                }
+            ]
+        });
+
+        let _m = server
+            .mock("POST", "/rest/api/latest/search")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let tickets = client.search_tickets("WAB").await.unwrap();
+
+        assert_eq!(tickets.len(), 2);
+        assert_eq!(tickets[0].key, "WAB-123");
+        assert_eq!(tickets[0].fields.summary, "Test ticket 1");
+        assert_eq!(tickets[0].fields.status.name, "In Progress");
+        assert_eq!(tickets[1].key, "WAB-124");
+        assert_eq!(tickets[1].fields.summary, "Test ticket 2");
+    }
+
+    #[tokio::test]
+    async fn test_search_tickets_empty_results() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_response = serde_json::json!({
+            "issues": []
+        });
+
+        let _m = server
+            .mock("POST", "/rest/api/latest/search")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let tickets = client.search_tickets("WAB").await.unwrap();
+        assert_eq!(tickets.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_tickets_api_error() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("POST", "/rest/api/latest/search")
+            .with_status(401)
+            .with_body("Unauthorized")
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "invalid-token".to_string(),
+            },
+        );
+
+        let result = client.search_tickets("WAB").await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Jira search API error"));
+    }
+
+    #[tokio::test]
+    async fn test_search_tickets_invalid_json() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("POST", "/rest/api/latest/search")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("invalid json")
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let result = client.search_tickets("WAB").await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Failed to parse search response"));
+    }
+
+    #[tokio::test]
+    async fn test_search_tickets_missing_issues_field() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_response = serde_json::json!({
+            "no_issues_field": []
+        });
+
+        let _m = server
+            .mock("POST", "/rest/api/latest/search")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let result = client.search_tickets("WAB").await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("No 'issues' field in response"));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_jql_success() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_response = serde_json::json!({
+            "issues": [
+                {
+                    "key": "WAB-100",
+                    "fields": {
+                        "summary": "Login bug fix",
+                        "status": {
+                            "name": "To Do"
+                        }
+                    }
+                }
+            ]
+        });
+
+        let _m = server
+            .mock("POST", "/rest/api/latest/search")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let tickets = client.search_with_jql("summary ~ \"login\"", 10).await.unwrap();
+
+        assert_eq!(tickets.len(), 1);
+        assert_eq!(tickets[0].key, "WAB-100");
+        assert_eq!(tickets[0].fields.summary, "Login bug fix");
+    }
+
+    #[tokio::test]
+    async fn test_search_with_jql_respects_limit() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_response = serde_json::json!({
+            "issues": [
+                {
+                    "key": "WAB-1",
+                    "fields": {
+                        "summary": "Test 1",
+                        "status": {
+                            "name": "To Do"
+                        }
+                    }
+                },
+                {
+                    "key": "WAB-2",
+                    "fields": {
+                        "summary": "Test 2",
+                        "status": {
+                            "name": "To Do"
+                        }
+                    }
+                }
+            ]
+        });
+
+        let _m = server
+            .mock("POST", "/rest/api/latest/search")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create_async()
+            .await;
+
+        let client = JiraClient::new(
+            server.url(),
+            "test@example.com".to_string(),
+            AuthMethod::ApiToken {
+                token: "test-token".to_string(),
+            },
+        );
+
+        let tickets = client.search_with_jql("project = WAB", 5).await.unwrap();
+        assert_eq!(tickets.len(), 2);
+    }
+
+    fn data_center_client(url: String) -> JiraClient {
+        JiraClient::new(
+            url,
+            "test@example.com".to_string(),
+            AuthMethod::PersonalAccessToken {
+                token: "pat-token".to_string(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_myself_is_cached() {
+        let mut server = mockito::Server::new_async().await;
+
+        let myself = server
+            .mock("GET", "/rest/api/latest/myself")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"name":"jdoe","key":"JIRAUSER1","displayName":"Jane Doe"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = data_center_client(server.url());
+        let me = client.get_myself().await.unwrap();
+        assert_eq!(me.account_id, "jdoe");
+        assert_eq!(me.display_name, "Jane Doe");
+
+        client.get_myself().await.unwrap();
+        myself.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_data_center_search_replaces_current_user() {
+        let mut server = mockito::Server::new_async().await;
+
+        let myself = server
+            .mock("GET", "/rest/api/latest/myself")
+            .with_status(200)
+            .with_body(r#"{"name":"jdoe","displayName":"Jane Doe"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let search = server
+            .mock("POST", "/rest/api/latest/search")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "jql": "assignee = \"jdoe\" AND project = WAB"
+            })))
+            .with_status(200)
+            .with_body(r#"{"issues":[]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = data_center_client(server.url());
+        client.search_tickets("WAB").await.unwrap();
+        client.search_tickets("WAB").await.unwrap();
+
+        myself.assert_async().await;
+        search.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_cloud_search_keeps_current_user() {
+        let mut server = mockito::Server::new_async().await;
+
+        let search = server
+            .mock("POST", "/rest/api/3/search/jql")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "jql": "assignee = currentUser() AND project = WAB"
+            })))
+            .with_status(200)
+            .with_body(r#"{"issues":[]}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        client.search_tickets("WAB").await.unwrap();
+        search.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_boards() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/agile/1.0/board")
+            .match_query(mockito::Matcher::UrlEncoded("projectKeyOrId".into(), "WAB".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"isLast":true,"values":[{"id":7,"name":"WAB board","type":"scrum"}]}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let boards = client.get_boards("WAB").await.unwrap();
+
+        assert_eq!(boards.len(), 1);
+        assert_eq!(boards[0].id, 7);
+        assert_eq!(boards[0].name, "WAB board");
+        assert_eq!(boards[0].board_type, "scrum");
+    }
+
+    #[tokio::test]
+    async fn test_get_comments_newest_first() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/3/issue/WAB-1/comment")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("orderBy".into(), "-created".into()),
+                mockito::Matcher::UrlEncoded("maxResults".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"startAt":0,"maxResults":2,"total":7,"comments":[
+                    {"id":"7","author":{"displayName":"Ada"},"body":"Shipped","created":"2024-01-16T09:00:00.000+0000"},
+                    {"id":"6","author":{"displayName":"Alan"},"body":"LGTM","created":"2024-01-15T17:00:00.000+0000"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let page = client.get_comments("WAB-1", 2).await.unwrap();
+        assert_eq!(page.total, 7);
+        assert_eq!(page.comments.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), vec!["7", "6"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_projects() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/3/project")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"10000","key":"WAB","name":"Web App","projectTypeKey":"software"},{"id":"10001","key":"OPS","name":"Operations"}]"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let projects = client.get_projects().await.unwrap();
+        assert_eq!(projects.iter().map(|p| (p.key.as_str(), p.name.as_str())).collect::<Vec<_>>(), vec![("WAB", "Web App"), ("OPS", "Operations")]);
+    }
+
+    #[tokio::test]
+    async fn test_get_board() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/agile/1.0/board/12")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":12,"name":"WAB board","type":"simple","location":{"projectKey":"WAB"}}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let board = client.get_board(12).await.unwrap();
+        assert_eq!((board.id, board.board_type.as_str()), (12, "simple"));
+    }
+
+    #[tokio::test]
+    async fn test_get_sprints_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _first = server
+            .mock("GET", "/rest/agile/1.0/board/7/sprint")
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "0".into()))
+            .with_status(200)
+            .with_body(r#"{"isLast":false,"values":[{"id":1,"name":"Sprint 1","state":"closed","startDate":"2025-01-06T09:00:00.000Z","endDate":"2025-01-20T09:00:00.000Z"}]}"#)
+            .create_async()
+            .await;
+
+        let _second = server
+            .mock("GET", "/rest/agile/1.0/board/7/sprint")
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "1".into()))
+            .with_status(200)
+            .with_body(r#"{"isLast":true,"values":[{"id":2,"name":"Sprint 2","state":"future"}]}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let sprints = client.get_sprints(7).await.unwrap();
+
+        assert_eq!(sprints.len(), 2);
+        assert_eq!(sprints[0].start_date.as_deref(), Some("2025-01-06T09:00:00.000Z"));
+        assert_eq!(sprints[1].name, "Sprint 2");
+        assert_eq!(sprints[1].state, "future");
+        assert!(sprints[1].end_date.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_sprints_kanban_board_errors() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/agile/1.0/board/3/sprint")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .with_body(r#"{"errorMessages":["The board does not support sprints"]}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let err = client.get_sprints(3).await.unwrap_err().to_string();
+        assert!(err.contains("400"));
+        assert!(err.contains("does not support sprints"));
+    }
+
+    #[tokio::test]
+    async fn test_search_users_cloud() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/3/user/search")
+            .match_query(mockito::Matcher::UrlEncoded("query".into(), "john".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"accountId":"abc-123","displayName":"John Doe"},{"accountId":"def-456","displayName":"Johnny Smith"}]"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let users = client.search_users("john").await.unwrap();
+
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].account_id, "abc-123");
+        assert_eq!(users[1].display_name, "Johnny Smith");
+    }
+
+    #[tokio::test]
+    async fn test_search_users_data_center_uses_username() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/latest/user/search")
+            .match_query(mockito::Matcher::UrlEncoded("username".into(), "john.doe".into()))
+            .with_status(200)
+            .with_body(r#"[{"name":"john.doe","key":"JIRAUSER10","displayName":"John Doe"}]"#)
+            .create_async()
+            .await;
+
+        let client = data_center_client(server.url());
+        let users = client.search_users("john.doe").await.unwrap();
+
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].account_id, "john.doe");
+    }
+
+    #[tokio::test]
+    async fn test_get_ticket_cached_reads_through() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("GET", "/rest/api/3/issue/WAB-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"key":"WAB-1","fields":{"summary":"Fix login","status":{"name":"To Do"}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tickets.json");
+        let client = cloud_client(server.url());
+
+        let mut cache = TicketCache::load_from(&path);
+        let first = client.get_ticket_through(&mut cache, "WAB-1").await.unwrap();
+
+        let mut cache = TicketCache::load_from(&path);
+        let second = client.get_ticket_through(&mut cache, "WAB-1").await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(second.status, "To Do");
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_add_comment_uses_adf_on_v3() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("POST", "/rest/api/3/issue/WAB-1/comment")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "body": { "type": "doc", "version": 1 }
+            })))
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        client.add_comment("WAB-1", "Looks good").await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_assign_issue_uses_account_id_on_cloud() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/rest/api/3/issue/WAB-1/assignee")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "accountId": "5b10ac8d" })))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let user = JiraUser {
+            account_id: "5b10ac8d".to_string(),
+            display_name: "Jane Doe".to_string(),
+            email: None,
+        };
+        let client = cloud_client(server.url());
+        client.assign_issue("WAB-1", Some(&user)).await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_assign_issue_uses_name_on_data_center() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/rest/api/latest/issue/WAB-1/assignee")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "name": "jdoe" })))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let user = JiraUser {
+            account_id: "jdoe".to_string(),
+            display_name: "Jane Doe".to_string(),
+            email: None,
+        };
+        let client = data_center_client(server.url());
+        client.assign_issue("WAB-1", Some(&user)).await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_assign_issue_none_unassigns() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/rest/api/3/issue/WAB-1/assignee")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "accountId": null })))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        client.assign_issue("WAB-1", None).await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_update_labels_sends_add_and_remove_operations() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/rest/api/3/issue/WAB-1")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "update": { "labels": [{ "add": "needs-qa" }, { "remove": "tech-debt" }] }
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        client
+            .update_labels("WAB-1", &["needs-qa".to_string()], &["tech-debt".to_string()])
+            .await
+            .unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_ticket_parses_labels() {
+        let mut server = mockito::Server::new_async().await;
 
-        let mock_response = serde_json::json!({
-            "no_issues_field": []
-        });
+        let _m = server
+            .mock("GET", "/rest/api/3/issue/WAB-1")
+            .with_status(200)
+            .with_body(r#"{"key":"WAB-1","fields":{"summary":"Fix","status":{"name":"To Do"},"labels":["needs-qa"]}}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let ticket = client.get_ticket("WAB-1").await.unwrap();
+        assert_eq!(ticket.fields.labels, vec!["needs-qa"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_ticket_parses_components() {
+        let mut server = mockito::Server::new_async().await;
 
         let _m = server
-            .mock("POST", "/rest/api/latest/search")
+            .mock("GET", "/rest/api/3/issue/WAB-1")
+            .with_status(200)
+            .with_body(
+                r#"{"key":"WAB-1","fields":{"summary":"Fix","status":{"name":"To Do"},
+                    "components":[{"self":"https://jira/rest/api/3/component/10000","id":"10000","name":"backend"}]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let ticket = client.get_ticket("WAB-1").await.unwrap();
+        let names: Vec<&str> = ticket.fields.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["backend"]);
+    }
+
+    const COMPONENTS: &str = r#"[
+        {"id":"10000","name":"Backend","description":"APIs and jobs"},
+        {"id":"10001","name":"Mobile"},
+        {"id":"10002","name":"Infra"}
+    ]"#;
+
+    #[tokio::test]
+    async fn test_create_issue_uses_canonical_component_names() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _meta = server
+            .mock("GET", "/rest/api/3/issue/createmeta/WAB/issuetypes")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(mock_response.to_string())
+            .with_body(r#"{"issueTypes":[{"id":"10001","name":"Task"}]}"#)
             .create_async()
             .await;
 
-        let client = JiraClient::new(
-            server.url(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "test-token".to_string(),
-            },
-        );
+        let _components = server
+            .mock("GET", "/rest/api/3/project/WAB/components")
+            .with_status(200)
+            .with_body(COMPONENTS)
+            .create_async()
+            .await;
 
-        let result = client.search_tickets("WAB").await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("No 'issues' field in response"));
+        let create = server
+            .mock("POST", "/rest/api/3/issue")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "fields": { "components": [{ "name": "Backend" }, { "name": "Mobile" }] }
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"1","key":"WAB-42"}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let issue = NewIssue {
+            project_key: "WAB".to_string(),
+            summary: "Login fails".to_string(),
+            issue_type: "task".to_string(),
+            components: vec!["backend".to_string(), "MOBILE".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(client.create_issue(&issue).await.unwrap(), "WAB-42");
+        create.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_search_with_jql_success() {
+    async fn test_set_components_uses_canonical_names() {
         let mut server = mockito::Server::new_async().await;
 
-        let mock_response = serde_json::json!({
-            "issues": [
-                {
-                    "key": "WAB-100",
-                    "fields": {
-                        "summary": "Login bug fix",
-                        "status": {
-                            "name": "To Do"
-                        }
-                    }
-                }
-            ]
-        });
+        let _components = server
+            .mock("GET", "/rest/api/3/project/WAB/components")
+            .with_status(200)
+            .with_body(COMPONENTS)
+            .create_async()
+            .await;
+        let m = server
+            .mock("PUT", "/rest/api/3/issue/WAB-1")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "fields": { "components": [{ "name": "Backend" }, { "name": "Infra" }] }
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
 
-        let _m = server
-            .mock("POST", "/rest/api/latest/search")
+        let client = cloud_client(server.url());
+        let set = client
+            .set_components("WAB-1", &["backend".to_string(), "INFRA".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(set, vec!["Backend", "Infra"]);
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_set_components_suggests_close_matches() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _components = server
+            .mock("GET", "/rest/api/3/project/WAB/components")
             .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(mock_response.to_string())
+            .with_body(COMPONENTS)
             .create_async()
             .await;
 
-        let client = JiraClient::new(
-            server.url(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "test-token".to_string(),
-            },
-        );
+        let client = cloud_client(server.url());
+        let err = client.set_components("WAB-1", &["bakend".to_string()]).await.unwrap_err().to_string();
+        assert!(err.contains("Unknown component 'bakend' for project WAB. Did you mean: Backend?"), "{}", err);
 
-        let tickets = client.search_with_jql("summary ~ \"login\"", 10).await.unwrap();
+        let err = client.set_components("WAB-1", &["web".to_string()]).await.unwrap_err().to_string();
+        assert!(err.contains("Valid components: Backend, Mobile, Infra"), "{}", err);
+    }
 
-        assert_eq!(tickets.len(), 1);
-        assert_eq!(tickets[0].key, "WAB-100");
-        assert_eq!(tickets[0].fields.summary, "Login bug fix");
+    #[test]
+    fn test_close_matches() {
+        let names = ["backend", "backend-jobs", "mobile", "infra"];
+        assert_eq!(close_matches("backnd", &names), vec!["backend"]);
+        assert_eq!(close_matches("Back", &names), vec!["backend", "backend-jobs"]);
+        assert_eq!(close_matches("mobil", &names), vec!["mobile"]);
+        assert!(close_matches("design", &names).is_empty());
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
     }
 
+    const VERSIONS: &str = r#"[
+        {"id":"10100","name":"1.4.0","released":true,"archived":false},
+        {"id":"10101","name":"1.5.0","released":false,"archived":false},
+        {"id":"10102","name":"2.0.0","released":false,"archived":false},
+        {"id":"10099","name":"0.9.0","released":false,"archived":true}
+    ]"#;
+
     #[tokio::test]
-    async fn test_search_with_jql_respects_limit() {
+    async fn test_resolve_version_matches_case_insensitively() {
         let mut server = mockito::Server::new_async().await;
 
-        let mock_response = serde_json::json!({
-            "issues": [
-                {
-                    "key": "WAB-1",
-                    "fields": {
-                        "summary": "Test 1",
-                        "status": {
-                            "name": "To Do"
-                        }
-                    }
-                },
-                {
-                    "key": "WAB-2",
-                    "fields": {
-                        "summary": "Test 2",
-                        "status": {
-                            "name": "To Do"
-                        }
-                    }
-                }
-            ]
-        });
+        let _versions = server
+            .mock("GET", "/rest/api/3/project/WAB/versions")
+            .with_status(200)
+            .with_body(r#"[{"id":"10200","name":"Release Q3","released":false}]"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        assert_eq!(client.resolve_version("WAB", "release q3", false).await.unwrap(), "Release Q3");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_version_unknown_lists_unreleased() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _versions = server
+            .mock("GET", "/rest/api/3/project/WAB/versions")
+            .with_status(200)
+            .with_body(VERSIONS)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let err = client.resolve_version("WAB", "3.0-beta", false).await.unwrap_err().to_string();
+        assert!(err.contains("Unknown version '3.0-beta' for project WAB"), "{}", err);
+        assert!(err.contains("Unreleased versions: 1.5.0, 2.0.0\n"), "{}", err);
+        assert!(err.contains("--create-version"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_version_creates_missing_version() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _versions = server
+            .mock("GET", "/rest/api/3/project/WAB/versions")
+            .with_status(200)
+            .with_body(VERSIONS)
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", "/rest/api/3/version")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "name": "2.1.0", "released": false, "project": "WAB" })))
+            .with_status(201)
+            .with_body(r#"{"id":"10103","name":"2.1.0","released":false,"archived":false}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        assert_eq!(client.resolve_version("WAB", "2.1.0", true).await.unwrap(), "2.1.0");
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_add_fix_version_sends_add_operation() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/rest/api/3/issue/WAB-1")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "update": { "fixVersions": [{ "add": { "name": "1.5.0" } }] }
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        client.add_fix_version("WAB-1", "1.5.0").await.unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_update_ticket_fields() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("PUT", "/rest/api/3/issue/WAB-1")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "fields": { "duedate": "2026-11-01", "labels": ["backend"] }
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        client
+            .update_ticket_fields("WAB-1", serde_json::json!({ "duedate": "2026-11-01", "labels": ["backend"] }))
+            .await
+            .unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_update_ticket_fields_reports_field_errors() {
+        let mut server = mockito::Server::new_async().await;
 
         let _m = server
-            .mock("POST", "/rest/api/latest/search")
+            .mock("PUT", "/rest/api/3/issue/WAB-1")
+            .with_status(400)
+            .with_body(r#"{"errorMessages":[],"errors":{"duedate":"Error parsing date string: soon","customfield_10016":"Number value expected"}}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let err = client
+            .update_ticket_fields("WAB-1", serde_json::json!({ "duedate": "soon", "customfield_10016": "many" }))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DevFlowError>(),
+            Some(DevFlowError::JiraApiError(400, message))
+                if message == "customfield_10016: Number value expected; duedate: Error parsing date string: soon"
+        ));
+        assert_eq!(http::status_of(&err), Some(400));
+    }
+
+    #[test]
+    fn test_fix_versions_with_keeps_existing_ones() {
+        let version = |name: &str| Version { id: "1".to_string(), name: name.to_string(), released: false, archived: false };
+        assert_eq!(
+            fix_versions_with(&[version("1.4.0")], "1.5.0"),
+            serde_json::json!({ "fixVersions": [{ "name": "1.4.0" }, { "name": "1.5.0" }] })
+        );
+        assert_eq!(fix_versions_with(&[version("1.5.0")], "1.5.0"), serde_json::json!({ "fixVersions": [{ "name": "1.5.0" }] }));
+    }
+
+    #[tokio::test]
+    async fn test_update_ticket_fields_needs_a_field() {
+        // No mock: an empty update never reaches Jira
+        let server = mockito::Server::new_async().await;
+        let client = cloud_client(server.url());
+
+        for fields in [serde_json::json!({}), serde_json::json!(null), serde_json::json!(["duedate"])] {
+            let err = client.update_ticket_fields("WAB-1", fields).await.unwrap_err();
+            assert_eq!(err.to_string(), "No fields given to update on WAB-1");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_remote_links() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _links = server
+            .mock("GET", "/rest/api/3/issue/WAB-1/remotelink")
             .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(mock_response.to_string())
+            .with_body(
+                r#"[{"id":10000,"application":{"type":"com.github","name":"GitHub"},"relationship":"mentioned in",
+                     "object":{"url":"https://github.com/acme/api/pull/12","title":"Fix login #12"}},
+                    {"id":10001,"object":{"url":"https://confluence.example.com/display/WAB/Login","title":"Design"}}]"#,
+            )
             .create_async()
             .await;
 
-        let client = JiraClient::new(
-            server.url(),
-            "test@example.com".to_string(),
-            AuthMethod::ApiToken {
-                token: "test-token".to_string(),
-            },
+        let client = cloud_client(server.url());
+        let links = client.get_remote_links("WAB-1").await.unwrap();
+        let repos: Vec<_> = links.iter().filter_map(RemoteLink::repository_url).collect();
+        assert_eq!(links.len(), 2);
+        assert_eq!(repos, vec!["https://github.com/acme/api.git"]);
+    }
+
+    #[tokio::test]
+    async fn test_release_version_and_close_sprint() {
+        let mut server = mockito::Server::new_async().await;
+
+        let release = server
+            .mock("PUT", "/rest/api/3/version/10100")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "released": true })))
+            .with_status(200)
+            .with_body(r#"{"id":"10100","name":"1.5.0","released":true}"#)
+            .create_async()
+            .await;
+        let close = server
+            .mock("POST", "/rest/agile/1.0/sprint/42")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "state": "closed" })))
+            .with_status(200)
+            .with_body(r#"{"id":42,"name":"Sprint 7","state":"closed"}"#)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let version = Version { id: "10100".to_string(), name: "1.5.0".to_string(), released: false, archived: false };
+        assert!(client.release_version(&version).await.unwrap().released);
+        client.close_sprint(42).await.unwrap();
+        release.assert_async().await;
+        close.assert_async().await;
+    }
+
+    const LINK_TYPES: &str = r#"{"issueLinkTypes":[
+        {"id":"10000","name":"Blocks","inward":"is blocked by","outward":"blocks"},
+        {"id":"10001","name":"Relates","inward":"relates to","outward":"relates to"}
+    ]}"#;
+
+    #[tokio::test]
+    async fn test_create_issue_link_resolves_type_id() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _types = server
+            .mock("GET", "/rest/api/3/issueLinkType")
+            .with_status(200)
+            .with_body(LINK_TYPES)
+            .create_async()
+            .await;
+
+        let link = server
+            .mock("POST", "/rest/api/3/issueLink")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "type": { "id": "10000" },
+                "inwardIssue": { "key": "WAB-1" },
+                "outwardIssue": { "key": "WAB-2" }
+            })))
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        assert_eq!(client.create_issue_link("WAB-1", "WAB-2", "blocks").await.unwrap(), "blocks");
+        link.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_link_inward_description_swaps_issues() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _types = server
+            .mock("GET", "/rest/api/3/issueLinkType")
+            .with_status(200)
+            .with_body(LINK_TYPES)
+            .create_async()
+            .await;
+
+        let link = server
+            .mock("POST", "/rest/api/3/issueLink")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "type": { "id": "10000" },
+                "inwardIssue": { "key": "WAB-2" },
+                "outwardIssue": { "key": "WAB-1" }
+            })))
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        assert_eq!(client.create_issue_link("WAB-1", "WAB-2", "is-blocked-by").await.unwrap(), "is blocked by");
+        link.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_link_unknown_type_lists_valid() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _types = server
+            .mock("GET", "/rest/api/3/issueLinkType")
+            .with_status(200)
+            .with_body(LINK_TYPES)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        let err = client.create_issue_link("WAB-1", "WAB-2", "clones").await.unwrap_err();
+        assert!(err.to_string().contains("Valid types: Blocks, Relates"));
+        assert!(err.to_string().contains("Relations: blocks, is-blocked-by, relates-to"));
+    }
+
+    #[tokio::test]
+    async fn test_issue_link_types_fetched_once() {
+        let mut server = mockito::Server::new_async().await;
+
+        let types = server
+            .mock("GET", "/rest/api/3/issueLinkType")
+            .with_status(200)
+            .with_body(LINK_TYPES)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let _link = server
+            .mock("POST", "/rest/api/3/issueLink")
+            .with_status(201)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = cloud_client(server.url());
+        client.create_issue_link("WAB-1", "WAB-2", "blocks").await.unwrap();
+        client.create_issue_link("WAB-1", "WAB-3", "relates-to").await.unwrap();
+        types.assert_async().await;
+    }
+
+    fn link_types() -> Vec<IssueLinkType> {
+        let link_type = |id: &str, name: &str, inward: &str, outward: &str| IssueLinkType {
+            id: id.to_string(),
+            name: name.to_string(),
+            inward: inward.to_string(),
+            outward: outward.to_string(),
+        };
+        vec![
+            link_type("10000", "Blocks", "is blocked by", "blocks"),
+            link_type("10001", "Relates", "relates to", "relates to"),
+            link_type("10002", "Duplicate", "is duplicated by", "duplicates"),
+        ]
+    }
+
+    #[test]
+    fn test_orient_link_outward_relations_keep_order() {
+        let types = link_types();
+        // "WAB-1 blocks WAB-2": WAB-1 is the inward issue of the link
+        assert_eq!(
+            orient_link(&types, "blocks", "WAB-1", "WAB-2"),
+            Some(OrientedLink { type_id: "10000", description: "blocks", inward: "WAB-1", outward: "WAB-2" })
         );
+        assert_eq!(
+            orient_link(&types, "Duplicates", "WAB-1", "WAB-2"),
+            Some(OrientedLink { type_id: "10002", description: "duplicates", inward: "WAB-1", outward: "WAB-2" })
+        );
+        // By type name, too
+        assert_eq!(orient_link(&types, "duplicate", "WAB-1", "WAB-2").unwrap().inward, "WAB-1");
+    }
 
-        let tickets = client.search_with_jql("project = WAB", 5).await.unwrap();
-        assert_eq!(tickets.len(), 2);
+    #[test]
+    fn test_orient_link_inward_relations_swap() {
+        let types = link_types();
+        // "WAB-1 is blocked by WAB-2" is stored as "WAB-2 blocks WAB-1"
+        let expected = OrientedLink { type_id: "10000", description: "is blocked by", inward: "WAB-2", outward: "WAB-1" };
+        assert_eq!(orient_link(&types, "is-blocked-by", "WAB-1", "WAB-2"), Some(expected));
+        assert_eq!(orient_link(&types, "Is Blocked By", "WAB-1", "WAB-2").unwrap().inward, "WAB-2");
+        assert_eq!(orient_link(&types, "is_duplicated_by", "WAB-1", "WAB-2").unwrap().outward, "WAB-1");
+    }
+
+    #[test]
+    fn test_orient_link_symmetric_and_unknown() {
+        let types = link_types();
+        // Both descriptions of "Relates" are the same; the outward match wins
+        assert_eq!(
+            orient_link(&types, "relates-to", "WAB-1", "WAB-2"),
+            Some(OrientedLink { type_id: "10001", description: "relates to", inward: "WAB-1", outward: "WAB-2" })
+        );
+        assert_eq!(orient_link(&types, "clones", "WAB-1", "WAB-2"), None);
+        assert_eq!(
+            link_relations(&types),
+            vec!["blocks", "is-blocked-by", "relates-to", "duplicates", "is-duplicated-by"]
+        );
     }
 }
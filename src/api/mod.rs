@@ -1,4 +1,8 @@
+pub mod azure_devops;
+pub mod bitbucket;
 pub mod git;
 pub mod github;
 pub mod gitlab;
+pub mod http;
 pub mod jira;
+pub mod slack;
@@ -0,0 +1,109 @@
+use super::http;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Notifications are best effort; don't hold up `devflow done` for long
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Posts messages to a Slack incoming webhook
+pub struct SlackNotifier {
+    client: Client,
+    webhook_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookPayload {
+    text: String,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            client: http::client(),
+            webhook_url,
+        }
+    }
+
+    /// Announce that `ticket_key`'s pull request is ready for review
+    pub async fn send_pr_ready(
+        &self,
+        ticket_key: &str,
+        summary: &str,
+        pr_url: &str,
+        author: &str,
+    ) -> Result<()> {
+        let payload = WebhookPayload {
+            text: format!(
+                "{} opened a pull request for *{}*: {}\n<{}|Review it here>",
+                author,
+                ticket_key,
+                escape(summary),
+                pr_url
+            ),
+        };
+
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .timeout(SEND_TIMEOUT)
+            .json(&payload)
+            .send()
+            .await
+            .context("Failed to reach Slack webhook")?;
+
+        http::check_response("Slack", response).await?;
+
+        Ok(())
+    }
+}
+
+/// Slack treats `&`, `<` and `>` as control characters in message text
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_send_pr_ready() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m = server
+            .mock("POST", "/services/T000/B000/XXX")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "text": "Jane Doe opened a pull request for *WAB-1*: Fix &lt;login&gt;\n<https://github.com/o/r/pull/1|Review it here>"
+            })))
+            .with_status(200)
+            .with_body("ok")
+            .create_async()
+            .await;
+
+        let notifier = SlackNotifier::new(format!("{}/services/T000/B000/XXX", server.url()));
+        notifier
+            .send_pr_ready("WAB-1", "Fix <login>", "https://github.com/o/r/pull/1", "Jane Doe")
+            .await
+            .unwrap();
+
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_pr_ready_rejected() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("POST", "/hook")
+            .with_status(404)
+            .with_body("no_service")
+            .create_async()
+            .await;
+
+        let notifier = SlackNotifier::new(format!("{}/hook", server.url()));
+        let err = notifier.send_pr_ready("WAB-1", "Fix", "https://pr", "Jane").await.unwrap_err();
+        assert_eq!(http::status_of(&err), Some(404));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::models::ticket::{JiraProject, JiraTicket, JiraUser};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a cached ticket is trusted before Jira is asked again
+pub const TICKET_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Summary of a ticket as last fetched from Jira
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedTicket {
+    pub summary: String,
+    pub status: String,
+    /// Display name; `None` when unassigned
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// Unix timestamp (seconds)
+    pub fetched_at: u64,
+}
+
+impl CachedTicket {
+    pub fn from_ticket(ticket: &JiraTicket) -> Self {
+        Self {
+            summary: ticket.fields.summary.clone(),
+            status: ticket.fields.status.name.clone(),
+            assignee: ticket.fields.assignee.as_ref().map(|a| a.display_name.clone()),
+            priority: ticket.fields.priority.as_ref().map(|p| p.name.clone()),
+            fetched_at: now(),
+        }
+    }
+
+    fn is_fresh(&self, now: u64, ttl: Duration) -> bool {
+        now.saturating_sub(self.fetched_at) < ttl.as_secs()
+    }
+}
+
+/// How long the list of a Jira instance's projects is trusted
+pub const PROJECT_LIST_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The projects `devflow init` offers, as last fetched
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedProjects {
+    pub projects: Vec<JiraProject>,
+    /// Unix timestamp (seconds)
+    pub fetched_at: u64,
+}
+
+/// A branch's PR/MR as last seen by `devflow status`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPullRequest {
+    pub url: String,
+    /// `open`, `draft`, `merged` or `closed`
+    pub state: String,
+    /// Unix timestamp (seconds)
+    pub fetched_at: u64,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// `--no-cache` sets `DEVFLOW_NO_CACHE` so every layer can check it
+pub fn is_disabled() -> bool {
+    std::env::var("DEVFLOW_NO_CACHE").is_ok()
+}
+
+/// Local cache of Jira lookups, kept at `cache/tickets.json` in the config
+/// directory.
+/// It is only an optimisation: a missing or unreadable file is treated as
+/// empty and failures to save are ignored by callers.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TicketCache {
+    /// Ticket key → summary and status
+    #[serde(default)]
+    tickets: HashMap<String, CachedTicket>,
+
+    /// `--assignee` values (lowercased) resolved to Jira users
+    #[serde(default)]
+    users: HashMap<String, JiraUser>,
+
+    /// Branch name → its PR/MR
+    #[serde(default)]
+    pull_requests: HashMap<String, CachedPullRequest>,
+
+    /// Jira URL and user → the projects they can see
+    #[serde(default)]
+    projects: HashMap<String, CachedProjects>,
+
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl TicketCache {
+    pub fn path() -> anyhow::Result<PathBuf> {
+        Ok(super::settings::Settings::config_dir()?.join("cache").join("tickets.json"))
+    }
+
+    /// Load the cache, starting empty if it doesn't exist or can't be parsed
+    pub fn load() -> Self {
+        match Self::path() {
+            Ok(path) => Self::load_from(&path),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let mut cache: Self = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        cache.path = path.to_path_buf();
+        cache
+    }
+
+    /// Write to a temporary file and rename it into place, so concurrent
+    /// devflow processes never see a half-written cache
+    pub fn save(&self) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let dir = self.path.parent().context("Cache path has no parent directory")?;
+        std::fs::create_dir_all(dir).context("Failed to create cache directory")?;
+
+        let tmp = dir.join(format!(".tickets.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_string(self)?).context("Failed to write cache")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to replace cache file")?;
+
+        Ok(())
+    }
+
+    /// Delete the cache file; a missing file is not an error
+    pub fn clear() -> anyhow::Result<()> {
+        match std::fs::remove_file(Self::path()?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// A ticket fetched less than `ttl` ago
+    pub fn ticket(&self, key: &str, ttl: Duration) -> Option<&CachedTicket> {
+        self.tickets.get(key).filter(|t| t.is_fresh(now(), ttl))
+    }
+
+    pub fn insert_ticket(&mut self, key: &str, ticket: CachedTicket) {
+        self.tickets.insert(key.to_string(), ticket);
+    }
+
+    /// Drop a ticket whose status we just changed
+    pub fn forget_ticket(key: &str) {
+        let mut cache = Self::load();
+        if cache.tickets.remove(key).is_some() {
+            let _ = cache.save();
+        }
+    }
+
+    pub fn pull_request(&self, branch: &str) -> Option<&CachedPullRequest> {
+        self.pull_requests.get(branch)
+    }
+
+    pub fn insert_pull_request(&mut self, branch: &str, url: &str, state: &str) {
+        let pull_request = CachedPullRequest { url: url.to_string(), state: state.to_string(), fetched_at: now() };
+        self.pull_requests.insert(branch.to_string(), pull_request);
+    }
+
+    pub fn user(&self, query: &str) -> Option<&JiraUser> {
+        self.users.get(&query.to_lowercase())
+    }
+
+    pub fn insert_user(&mut self, query: &str, user: JiraUser) {
+        self.users.insert(query.to_lowercase(), user);
+    }
+
+    /// The projects `user` can see on the Jira at `jira_url`, fetched less
+    /// than `ttl` ago
+    pub fn projects(&self, jira_url: &str, user: &str, ttl: Duration) -> Option<&[JiraProject]> {
+        self.projects
+            .get(&projects_key(jira_url, user))
+            .filter(|cached| now().saturating_sub(cached.fetched_at) < ttl.as_secs())
+            .map(|cached| cached.projects.as_slice())
+    }
+
+    pub fn insert_projects(&mut self, jira_url: &str, user: &str, projects: Vec<JiraProject>) {
+        self.projects.insert(projects_key(jira_url, user), CachedProjects { projects, fetched_at: now() });
+    }
+}
+
+/// Project lists differ per user, since Jira only lists what they can browse
+fn projects_key(jira_url: &str, user: &str) -> String {
+    format!("{} {}", jira_url, user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str) -> JiraUser {
+        JiraUser {
+            account_id: id.to_string(),
+            display_name: "John Doe".to_string(),
+            email: None,
+        }
+    }
+
+    fn cached(fetched_at: u64) -> CachedTicket {
+        CachedTicket {
+            summary: "Fix login".to_string(),
+            status: "To Do".to_string(),
+            assignee: Some("Ada Lovelace".to_string()),
+            priority: None,
+            fetched_at,
+        }
+    }
+
+    #[test]
+    fn test_ticket_hit_and_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tickets.json");
+
+        let mut cache = TicketCache::load_from(&path);
+        assert!(cache.ticket("WAB-1", TICKET_TTL).is_none());
+
+        cache.insert_ticket("WAB-1", cached(now()));
+        cache.save().unwrap();
+
+        let cache = TicketCache::load_from(&path);
+        assert_eq!(cache.ticket("WAB-1", TICKET_TTL).unwrap().summary, "Fix login");
+        assert!(cache.ticket("WAB-2", TICKET_TTL).is_none());
+    }
+
+    #[test]
+    fn test_ticket_expires_after_ttl() {
+        let mut cache = TicketCache::default();
+        cache.insert_ticket("WAB-1", cached(now() - TICKET_TTL.as_secs() - 1));
+
+        assert!(cache.ticket("WAB-1", TICKET_TTL).is_none());
+        assert!(cache.ticket("WAB-1", Duration::from_secs(3600)).is_some());
+    }
+
+    #[test]
+    fn test_save_leaves_no_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tickets.json");
+
+        let mut cache = TicketCache::load_from(&path);
+        cache.insert_ticket("WAB-1", cached(now()));
+        cache.save().unwrap();
+        cache.save().unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_users_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("tickets.json");
+
+        let mut cache = TicketCache::load_from(&path);
+        assert!(cache.user("john.doe").is_none());
+
+        cache.insert_user("John.Doe", user("abc-123"));
+        cache.save().unwrap();
+
+        let cache = TicketCache::load_from(&path);
+        assert_eq!(cache.user("john.doe").unwrap().account_id, "abc-123");
+    }
+
+    #[test]
+    fn test_projects_per_jira_and_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("tickets.json");
+
+        let mut cache = TicketCache::load_from(&path);
+        let projects = vec![JiraProject { key: "WAB".to_string(), name: "Web App".to_string() }];
+        cache.insert_projects("https://jira.example.com", "alice@example.com", projects.clone());
+        cache.save().unwrap();
+
+        let mut cache = TicketCache::load_from(&path);
+        let cached = cache.projects("https://jira.example.com", "alice@example.com", PROJECT_LIST_TTL);
+        assert_eq!(cached, Some(projects.as_slice()));
+        assert!(cache.projects("https://other.example.com", "alice@example.com", PROJECT_LIST_TTL).is_none());
+        assert!(cache.projects("https://jira.example.com", "bob@example.com", PROJECT_LIST_TTL).is_none());
+
+        let key = projects_key("https://jira.example.com", "alice@example.com");
+        cache.projects.get_mut(&key).unwrap().fetched_at = now() - PROJECT_LIST_TTL.as_secs();
+        assert!(cache.projects("https://jira.example.com", "alice@example.com", PROJECT_LIST_TTL).is_none());
+    }
+
+    #[test]
+    fn test_corrupt_cache_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tickets.json");
+        std::fs::write(&path, "{not json").unwrap();
+
+        let mut cache = TicketCache::load_from(&path);
+        assert!(cache.user("john.doe").is_none());
+        assert!(cache.ticket("WAB-1", TICKET_TTL).is_none());
+
+        cache.insert_user("john.doe", user("abc-123"));
+        cache.save().unwrap();
+        assert!(TicketCache::load_from(&path).user("john.doe").is_some());
+    }
+}
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Entries kept when the file is compacted
+const MAX_ENTRIES: usize = 500;
+/// Compact once the file grows past this; entries are ~100 bytes, so the
+/// file holds a few times `MAX_ENTRIES` at most
+const COMPACT_AT_BYTES: u64 = 128 * 1024;
+
+/// One thing devflow did with a ticket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub ticket: String,
+    /// `start`, `commit`, `done`, `merge`, `show`, ...
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Unix seconds
+    pub at: u64,
+}
+
+/// Tickets devflow has worked with, one JSON entry per line in
+/// `history.jsonl` in the config directory. Each entry is appended with a single write to
+/// a file opened in append mode, so concurrent invocations don't interleave.
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn path() -> anyhow::Result<PathBuf> {
+        Ok(super::settings::Settings::config_dir()?.join("history.jsonl"))
+    }
+
+    pub fn open() -> anyhow::Result<Self> {
+        Ok(Self::at(&Self::path()?))
+    }
+
+    pub fn at(path: &Path) -> Self {
+        Self { path: path.to_path_buf() }
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create config directory")?;
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .context("Failed to open history file")?;
+
+        // Terminate a line left unfinished by an interrupted write
+        let mut line = String::new();
+        let mut last = [0u8; 1];
+        if file.seek(SeekFrom::End(-1)).is_ok() && file.read_exact(&mut last).is_ok() && last[0] != b'\n' {
+            line.push('\n');
+        }
+        line.push_str(&serde_json::to_string(entry)?);
+        line.push('\n');
+
+        file.write_all(line.as_bytes()).context("Failed to write history")?;
+
+        if file.metadata().map(|m| m.len()).unwrap_or_default() > COMPACT_AT_BYTES {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    /// Keep only the newest `MAX_ENTRIES`, replacing the file atomically
+    fn compact(&self) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let entries = self.entries();
+        let keep = &entries[entries.len().saturating_sub(MAX_ENTRIES)..];
+        let mut content = String::new();
+        for entry in keep {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+
+        let dir = self.path.parent().context("History path has no parent directory")?;
+        let tmp = dir.join(format!(".history.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, content).context("Failed to write history")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to replace history")?;
+
+        Ok(())
+    }
+
+    /// All entries, oldest first. Unreadable lines (e.g. from a crash
+    /// mid-write) are skipped.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        std::fs::read_to_string(&self.path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// The latest entry for each of the `limit` most recently touched tickets
+    pub fn recent(&self, limit: usize) -> Vec<HistoryEntry> {
+        let mut recent: Vec<HistoryEntry> = Vec::new();
+        for entry in self.entries().into_iter().rev() {
+            if recent.len() == limit {
+                break;
+            }
+            if !recent.iter().any(|seen| seen.ticket.eq_ignore_ascii_case(&entry.ticket)) {
+                recent.push(entry);
+            }
+        }
+        recent
+    }
+}
+
+/// Record that `action` was done on `ticket`. History is a convenience, so
+/// failures are ignored rather than failing the command.
+pub fn record(ticket: &str, action: &str, branch: Option<&str>) {
+    let entry = HistoryEntry {
+        ticket: ticket.to_uppercase(),
+        action: action.to_string(),
+        branch: branch.map(str::to_string),
+        at: now(),
+    };
+    if let Ok(history) = History::open() {
+        let _ = history.append(&entry);
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// "just now", "5m ago", "3h ago", "2d ago"
+pub fn describe_age(at: u64, now: u64) -> String {
+    let secs = now.saturating_sub(at);
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(ticket: &str, action: &str, at: u64) -> HistoryEntry {
+        HistoryEntry { ticket: ticket.to_string(), action: action.to_string(), branch: None, at }
+    }
+
+    #[test]
+    fn test_recent_returns_latest_action_per_ticket() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::at(&dir.path().join("history.jsonl"));
+        history.append(&entry("WAB-1", "start", 1)).unwrap();
+        history.append(&entry("WAB-2", "start", 2)).unwrap();
+        history.append(&entry("WAB-1", "done", 3)).unwrap();
+        history.append(&entry("WAB-3", "commit", 4)).unwrap();
+
+        let recent = history.recent(2);
+        assert_eq!(recent, vec![entry("WAB-3", "commit", 4), entry("WAB-1", "done", 3)]);
+        assert_eq!(history.recent(10).len(), 3);
+    }
+
+    #[test]
+    fn test_corrupt_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        std::fs::write(&path, "{\"ticket\":\"WAB-1\",\"action\":\"start\",\"at\":1}\n{\"tick").unwrap();
+
+        let history = History::at(&path);
+        history.append(&entry("WAB-2", "done", 2)).unwrap();
+        // The truncated line is dropped without taking the next entry with it
+        let tickets: Vec<String> = history.entries().into_iter().map(|e| e.ticket).collect();
+        assert_eq!(tickets, vec!["WAB-1", "WAB-2"]);
+    }
+
+    #[test]
+    fn test_compact_keeps_newest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::at(&dir.path().join("history.jsonl"));
+        for i in 0..(MAX_ENTRIES as u64 + 10) {
+            history.append(&entry(&format!("WAB-{}", i), "commit", i)).unwrap();
+        }
+
+        history.compact().unwrap();
+        let entries = history.entries();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].at, 10);
+    }
+
+    #[test]
+    fn test_describe_age() {
+        assert_eq!(describe_age(100, 130), "just now");
+        assert_eq!(describe_age(0, 300), "5m ago");
+        assert_eq!(describe_age(0, 3 * 3600), "3h ago");
+        assert_eq!(describe_age(0, 2 * 86_400 + 5), "2d ago");
+    }
+}
@@ -1 +1,4 @@
+pub mod cache;
+pub mod history;
 pub mod settings;
+pub mod stash;
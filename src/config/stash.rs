@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Stashes `devflow workon` and `devflow pause` made when switching away
+/// from a branch, and the tickets `pause` parked, kept at
+/// `stash_map.json` in the config directory so the work comes back when the
+/// branch does
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StashMap {
+    /// Repository `.git` directory → branch → stash commit ID
+    #[serde(default)]
+    repos: HashMap<String, HashMap<String, String>>,
+
+    /// Repository `.git` directory → paused tickets, oldest first
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    paused: HashMap<String, Vec<Pause>>,
+
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// A ticket parked with `devflow pause`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pause {
+    pub ticket: String,
+    pub branch: String,
+    /// Unix seconds
+    pub paused_at: u64,
+    /// Set when pause moved the Jira ticket to another status, so resume
+    /// moves it back
+    #[serde(default)]
+    pub transitioned: bool,
+}
+
+impl StashMap {
+    pub fn path() -> anyhow::Result<PathBuf> {
+        Ok(super::settings::Settings::config_dir()?.join("stash_map.json"))
+    }
+
+    /// Load the map, starting empty if it doesn't exist or can't be parsed
+    pub fn load() -> anyhow::Result<Self> {
+        Ok(Self::load_from(&Self::path()?))
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let mut map: Self = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        map.path = path.to_path_buf();
+        map
+    }
+
+    /// Write to a temporary file and rename it into place
+    pub fn save(&self) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let dir = self.path.parent().context("Stash map path has no parent directory")?;
+        std::fs::create_dir_all(dir).context("Failed to create config directory")?;
+
+        let tmp = dir.join(format!(".stash_map.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?).context("Failed to write stash map")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to replace stash map")?;
+
+        Ok(())
+    }
+
+    pub fn get(&self, repo: &Path, branch: &str) -> Option<&str> {
+        self.repos
+            .get(&repo_key(repo))
+            .and_then(|branches| branches.get(branch))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, repo: &Path, branch: &str, stash_id: String) {
+        self.repos
+            .entry(repo_key(repo))
+            .or_default()
+            .insert(branch.to_string(), stash_id);
+    }
+
+    pub fn remove(&mut self, repo: &Path, branch: &str) -> Option<String> {
+        let key = repo_key(repo);
+        let branches = self.repos.get_mut(&key)?;
+        let removed = branches.remove(branch);
+        if branches.is_empty() {
+            self.repos.remove(&key);
+        }
+        removed
+    }
+
+    /// Record a pause, replacing an earlier one for the same branch
+    pub fn add_pause(&mut self, repo: &Path, pause: Pause) {
+        let pauses = self.paused.entry(repo_key(repo)).or_default();
+        pauses.retain(|earlier| earlier.branch != pause.branch);
+        pauses.push(pause);
+    }
+
+    pub fn pauses(&self, repo: &Path) -> &[Pause] {
+        self.paused.get(&repo_key(repo)).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The pause for `ticket`, or the most recent one
+    pub fn find_pause(&self, repo: &Path, ticket: Option<&str>) -> Option<&Pause> {
+        let pauses = self.pauses(repo);
+        match ticket {
+            Some(ticket) => pauses.iter().rev().find(|pause| pause.ticket.eq_ignore_ascii_case(ticket)),
+            None => pauses.last(),
+        }
+    }
+
+    /// Forget the pause for `branch`; `true` if there was one
+    pub fn remove_pause(&mut self, repo: &Path, branch: &str) -> bool {
+        let key = repo_key(repo);
+        let Some(pauses) = self.paused.get_mut(&key) else {
+            return false;
+        };
+
+        let before = pauses.len();
+        pauses.retain(|pause| pause.branch != branch);
+        let removed = pauses.len() != before;
+        if pauses.is_empty() {
+            self.paused.remove(&key);
+        }
+        removed
+    }
+}
+
+fn repo_key(repo: &Path) -> String {
+    repo.canonicalize()
+        .unwrap_or_else(|_| repo.to_path_buf())
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stash_map_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stash_map.json");
+        let repo = dir.path().join("repo/.git");
+
+        let mut map = StashMap::load_from(&path);
+        map.insert(&repo, "feat/WAB-1/x", "abc123".to_string());
+        map.save().unwrap();
+
+        let mut map = StashMap::load_from(&path);
+        assert_eq!(map.get(&repo, "feat/WAB-1/x"), Some("abc123"));
+        assert_eq!(map.get(&dir.path().join("other/.git"), "feat/WAB-1/x"), None);
+
+        assert_eq!(map.remove(&repo, "feat/WAB-1/x"), Some("abc123".to_string()));
+        assert!(map.repos.is_empty());
+        assert_eq!(map.remove(&repo, "feat/WAB-1/x"), None);
+    }
+
+    #[test]
+    fn test_pauses_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stash_map.json");
+        let repo = dir.path().join("repo/.git");
+        let pause = |ticket: &str, paused_at| Pause {
+            ticket: ticket.to_string(),
+            branch: format!("feat/{}/x", ticket),
+            paused_at,
+            transitioned: false,
+        };
+
+        let mut map = StashMap::load_from(&path);
+        map.add_pause(&repo, pause("WAB-1", 1));
+        map.add_pause(&repo, pause("WAB-2", 2));
+        map.add_pause(&repo, pause("WAB-1", 3));
+        map.save().unwrap();
+
+        let mut map = StashMap::load_from(&path);
+        assert_eq!(map.pauses(&repo).len(), 2);
+        assert_eq!(map.find_pause(&repo, None), Some(&pause("WAB-1", 3)));
+        assert_eq!(map.find_pause(&repo, Some("wab-2")), Some(&pause("WAB-2", 2)));
+        assert_eq!(map.find_pause(&repo, Some("WAB-3")), None);
+
+        assert!(map.remove_pause(&repo, "feat/WAB-1/x"));
+        assert!(map.remove_pause(&repo, "feat/WAB-2/x"));
+        assert!(!map.remove_pause(&repo, "feat/WAB-2/x"));
+        assert!(map.paused.is_empty());
+    }
+
+    #[test]
+    fn test_stash_map_corrupt_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stash_map.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let map = StashMap::load_from(&path);
+        assert!(map.repos.is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use colored::*;
 use std::fmt;
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub enum DevFlowError {
     // Configuration errors
     ConfigNotFound,
@@ -12,159 +13,306 @@ pub enum DevFlowError {
     JiraAuthFailed(u16),
     JiraTicketNotFound(String),
     JiraApiError(u16, String),
-    JiraTransitionNotFound(String),
+    /// Transition name, and the ones the ticket does have
+    JiraTransitionNotFound(String, Vec<String>),
 
     // Git errors
     NotInGitRepo,
+    EmptyRepository,
+    /// Short id of the checked-out commit
+    DetachedHead(String),
     GitRepoNotClean,
+    NothingStaged,
+    CommitAlreadyPushed(String),
+    InvalidCommitMessage(String),
     BranchAlreadyExists(String),
     BranchHasNoTicketId(String),
+    /// The default branch or one matching `preferences.protected_branches`
+    ProtectedBranch(String),
+    InvalidBranchName(String, String),
+    InvalidLabel(String),
     NoPushAccess(String),
+    /// Branch whose push was refused because the remote has moved on
+    PushRejected(String),
+    /// Missing remote, and the names of the remotes that do exist
+    RemoteNotFound(String, Vec<String>),
+    HookAlreadyExists(String),
+    HookFailed(String, String),
 
     // GitHub/GitLab errors
     PrCreationFailed(String),
+    MergeBlocked(String),
     GitHubAuthFailed,
     GitLabAuthFailed,
 
     // Network errors
     NetworkError(String),
+    /// The proxy answered 407 to our credentials
+    ProxyAuthFailed(String),
+    /// The server's certificate chain didn't verify
+    TlsCertificate(String),
 
     // Generic error
     Other(String),
 }
 
+/// The config file in use, for "edit it" hints
+fn config_file() -> String {
+    crate::config::settings::Settings::config_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| "~/.config/devflow/config.toml".to_string())
+}
+
 impl fmt::Display for DevFlowError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             // Configuration errors
             DevFlowError::ConfigNotFound => {
-                write!(f, "{}\n", "Configuration not found".red().bold())?;
+                writeln!(f, "{}", "Configuration not found".red().bold())?;
                 write!(f, "   {}\n\n", "Run 'devflow init' to set up your configuration".dimmed())?;
                 write!(f, "   {}", "devflow init".green())
             }
             DevFlowError::ConfigInvalid(msg) => {
-                write!(f, "{}\n", "Invalid configuration".red().bold())?;
+                writeln!(f, "{}", "Invalid configuration".red().bold())?;
                 write!(f, "   {}\n\n", msg.dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Check your config file: ~/.devflow/config.toml\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check your config file: {}", config_file())?;
                 write!(f, "   2. Or reinitialize: {}", "devflow init".green())
             }
             DevFlowError::ConfigValidationFailed(msg) => {
-                write!(f, "{}\n", "Configuration validation failed".red().bold())?;
+                writeln!(f, "{}", "Configuration validation failed".red().bold())?;
                 write!(f, "   {}\n\n", msg.dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Check your API tokens are valid\n")?;
-                write!(f, "   2. Verify network connectivity\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check your API tokens are valid")?;
+                writeln!(f, "   2. Verify network connectivity")?;
                 write!(f, "   3. Reinitialize if needed: {}", "devflow init".green())
             }
 
             // Jira errors
             DevFlowError::JiraAuthFailed(status) => {
-                write!(f, "{}\n", format!("Jira authentication failed ({})", status).red().bold())?;
+                writeln!(f, "{}", format!("Jira authentication failed ({})", status).red().bold())?;
                 write!(f, "   {}\n\n", "Your API token may have expired or is invalid".dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Generate new token: {}\n", "https://id.atlassian.com/manage-profile/security/api-tokens".cyan())?;
-                write!(f, "   2. Update config: {}\n", "devflow init".green())?;
-                write!(f, "   3. Or edit manually: ~/.devflow/config.toml")
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Generate new token: {}", "https://id.atlassian.com/manage-profile/security/api-tokens".cyan())?;
+                writeln!(f, "   2. Update config: {}", "devflow init".green())?;
+                write!(f, "   3. Or edit manually: {}", config_file())
             }
             DevFlowError::JiraTicketNotFound(ticket_id) => {
-                write!(f, "{}\n", format!("Ticket '{}' not found", ticket_id).red().bold())?;
+                writeln!(f, "{}", format!("Ticket '{}' not found", ticket_id).red().bold())?;
                 write!(f, "   {}\n\n", "The ticket doesn't exist or you don't have access to it".dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Check the ticket ID is correct\n")?;
-                write!(f, "   2. Verify you have access to this project\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check the ticket ID is correct")?;
+                writeln!(f, "   2. Verify you have access to this project")?;
                 write!(f, "   3. Search for tickets: {}", format!("devflow search \"{}\"", ticket_id).green())
             }
             DevFlowError::JiraApiError(status, msg) => {
-                write!(f, "{}\n", format!("Jira API error ({})", status).red().bold())?;
+                writeln!(f, "{}", format!("Jira API error ({})", status).red().bold())?;
                 write!(f, "   {}\n\n", msg.dimmed())?;
-                write!(f, "   Try again or check your network connection")
+                if *status == 400 {
+                    write!(f, "   Jira rejected the values above; correct the fields it names and try again")
+                } else {
+                    write!(f, "   Try again or check your network connection")
+                }
             }
-            DevFlowError::JiraTransitionNotFound(transition) => {
-                write!(f, "{}\n", format!("Status transition '{}' not found", transition).red().bold())?;
-                write!(f, "   {}\n\n", "This status is not available for this ticket".dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Check available statuses in Jira\n")?;
+            DevFlowError::JiraTransitionNotFound(transition, available) => {
+                writeln!(f, "{}", format!("Status transition '{}' not found", transition).red().bold())?;
+                writeln!(f, "   {}", "This status is not available for this ticket".dimmed())?;
+                if available.is_empty() {
+                    write!(f, "   {}\n\n", "No transitions are available from its current status".dimmed())?;
+                } else {
+                    write!(f, "   Available transitions: {}\n\n", available.join(", "))?;
+                }
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check available statuses in Jira")?;
                 write!(f, "   2. Update your config with a valid transition")
             }
 
             // Git errors
             DevFlowError::NotInGitRepo => {
-                write!(f, "{}\n", "Not in a git repository".red().bold())?;
+                writeln!(f, "{}", "Not in a git repository".red().bold())?;
                 write!(f, "   {}\n\n", "DevFlow must be run inside a git repository".dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Navigate to a git repository\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Navigate to a git repository")?;
                 write!(f, "   2. Or initialize one: {}", "git init".green())
             }
+            DevFlowError::EmptyRepository => {
+                writeln!(f, "{}", "Repository has no commits yet".red().bold())?;
+                write!(f, "   {}\n\n", "A branch needs a commit to start from".dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Make the first commit with git: {}", "git add -A && git commit -m \"Initial commit\"".green())?;
+                write!(f, "   2. Or an empty one: {}", "git commit --allow-empty -m \"Initial commit\"".green())
+            }
+            DevFlowError::DetachedHead(sha) => {
+                writeln!(f, "{}", format!("Not on a branch (detached at {})", sha).red().bold())?;
+                write!(f, "   {}\n\n", "This command needs a checked-out branch".dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Switch to a branch: {}", "git switch <branch>".green())?;
+                write!(f, "   2. Or keep this commit on a new one: {}", "git switch -c <branch>".green())
+            }
             DevFlowError::GitRepoNotClean => {
-                write!(f, "{}\n", "Uncommitted changes detected".red().bold())?;
+                writeln!(f, "{}", "Uncommitted changes detected".red().bold())?;
                 write!(f, "   {}\n\n", "Commit or stash your changes before running 'devflow done'".dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Commit changes: {}\n", "devflow commit \"your message\"".green())?;
-                write!(f, "   2. Or stash: {}\n", "git stash".green())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Commit changes: {}", "devflow commit \"your message\"".green())?;
+                writeln!(f, "   2. Or stash: {}", "git stash".green())?;
                 write!(f, "   3. Check status: {}", "git status".green())
             }
+            DevFlowError::NothingStaged => {
+                writeln!(f, "{}", "Nothing staged to commit".red().bold())?;
+                write!(f, "   {}\n\n", "--staged only commits changes already added to the index".dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Stage changes: {}", "git add <files>".green())?;
+                write!(f, "   2. Or commit everything: {}", "devflow commit \"your message\"".green())
+            }
+            DevFlowError::CommitAlreadyPushed(branch) => {
+                writeln!(f, "{}", "Last commit has already been pushed".red().bold())?;
+                write!(f, "   {}\n\n", format!("It is already on origin/{}; amending rewrites shared history", branch).dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Make a new commit instead: {}", "devflow commit \"your message\"".green())?;
+                write!(f, "   2. Or amend anyway (requires a force-push): {}", "devflow commit --amend --force".green())
+            }
+            DevFlowError::InvalidCommitMessage(reason) => {
+                writeln!(f, "{}", "Commit message is not a conventional commit".red().bold())?;
+                write!(f, "   {}\n\n", reason.dimmed())?;
+                writeln!(f, "   Expected format: {}", "type(scope): description".bright_white())?;
+                writeln!(f, "   Types: {}\n", crate::utils::commit::CONVENTIONAL_TYPES.join(", "))?;
+                writeln!(f, "   Examples:")?;
+                writeln!(f, "   {}", "devflow commit \"feat(auth): add token refresh\"".green())?;
+                writeln!(f, "   {}\n", "devflow commit \"fix: handle empty search results\"".green())?;
+                write!(f, "   To skip the check once: {}", "--bypass-convention".green())
+            }
+            DevFlowError::HookAlreadyExists(path) => {
+                writeln!(f, "{}", "A prepare-commit-msg hook already exists".red().bold())?;
+                write!(f, "   {}\n\n", format!("{} was not installed by devflow", path).dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Keep it and run it before devflow's: {}", "devflow hook install --chain".green())?;
+                write!(f, "   2. Or replace it: {}", "devflow hook install --force".green())
+            }
+            DevFlowError::HookFailed(command, reason) => {
+                writeln!(f, "{}", format!("Hook {}", reason).red().bold())?;
+                write!(f, "   {}\n\n", command.dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Run the command yourself to see why it failed")?;
+                write!(f, "   2. Or remove it from [hooks] in {}", config_file())
+            }
             DevFlowError::BranchAlreadyExists(branch) => {
-                write!(f, "{}\n", format!("Branch '{}' already exists", branch).red().bold())?;
+                writeln!(f, "{}", format!("Branch '{}' already exists", branch).red().bold())?;
                 write!(f, "   {}\n\n", "You're already on this branch or it exists locally".dimmed())?;
                 write!(f, "   To check status: {}", "devflow status".green())
             }
             DevFlowError::BranchHasNoTicketId(branch) => {
-                write!(f, "{}\n", "Branch doesn't contain a ticket ID".red().bold())?;
+                writeln!(f, "{}", "Branch doesn't contain a ticket ID".red().bold())?;
                 write!(f, "   {}\n\n", format!("Current branch: {}", branch).dimmed())?;
                 write!(f, "   DevFlow expects branches in format: feat/TICKET-123/description\n\n")?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Start work on a ticket: {}\n", "devflow start TICKET-123".green())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Start work on a ticket: {}", "devflow start TICKET-123".green())?;
                 write!(f, "   2. Or switch to a DevFlow branch")
             }
+            DevFlowError::ProtectedBranch(branch) => {
+                writeln!(f, "{}", format!("'{}' is a protected branch", branch).red().bold())?;
+                write!(f, "   {}\n\n", "It is the default branch or matches preferences.protected_branches".dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Start work on a ticket branch: {}", "devflow start TICKET-123".green())?;
+                write!(f, "   2. Or run it on this branch anyway: {}", "--allow-protected".green())
+            }
+            DevFlowError::InvalidBranchName(branch, pattern) => {
+                writeln!(f, "{}", format!("Invalid branch name '{}'", branch).red().bold())?;
+                write!(f, "   {}\n\n", format!("Branch names must match: {}", pattern).dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Pick a name that matches the pattern")?;
+                write!(f, "   2. Or adjust it: {}", "devflow config set preferences.branch_name_regex <pattern>".green())
+            }
+            DevFlowError::InvalidLabel(label) => {
+                writeln!(f, "{}", format!("Invalid label '{}'", label).red().bold())?;
+                write!(f, "   {}\n\n", "Jira labels can't be empty or contain spaces".dimmed())?;
+                write!(f, "   Use dashes or underscores instead, e.g. {}", "needs-qa".green())
+            }
             DevFlowError::NoPushAccess(msg) => {
-                write!(f, "{}\n", "Failed to push to remote".red().bold())?;
+                writeln!(f, "{}", "Failed to push to remote".red().bold())?;
                 write!(f, "   {}\n\n", msg.dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Check your SSH keys are configured\n")?;
-                write!(f, "   2. Verify you have push access to the repository\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check your SSH keys are configured")?;
+                writeln!(f, "   2. Verify you have push access to the repository")?;
                 write!(f, "   3. Test SSH: {}", "ssh -T git@github.com".green())
             }
+            DevFlowError::PushRejected(branch) => {
+                writeln!(f, "{}", "Push rejected".red().bold())?;
+                write!(f, "   {}\n\n", format!("The remote has commits on {} that your branch doesn't", branch).dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Bring them in, then push again: {}", "devflow sync".green())?;
+                write!(f, "   2. Or, if you rewrote history on purpose: {}", "devflow done --force-with-lease".green())
+            }
+            DevFlowError::RemoteNotFound(name, remotes) => {
+                writeln!(f, "{}", format!("Remote '{}' not found", name).red().bold())?;
+                if remotes.is_empty() {
+                    write!(f, "   {}\n\n", "This repository has no remotes".dimmed())?;
+                } else {
+                    write!(f, "   {}\n\n", format!("Remotes in this repository: {}", remotes.join(", ")).dimmed())?;
+                }
+                write!(f, "   To add it: {}", format!("git remote add {} <url>", name).green())
+            }
 
             // GitHub/GitLab errors
             DevFlowError::PrCreationFailed(msg) => {
-                write!(f, "{}\n", "Failed to create pull/merge request".red().bold())?;
+                writeln!(f, "{}", "Failed to create pull/merge request".red().bold())?;
                 write!(f, "   {}\n\n", msg.dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Check your API token is valid\n")?;
-                write!(f, "   2. Verify you have permissions to create PRs\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check your API token is valid")?;
+                writeln!(f, "   2. Verify you have permissions to create PRs")?;
                 write!(f, "   3. Try creating the PR manually")
             }
+            DevFlowError::MergeBlocked(reason) => {
+                writeln!(f, "{}", "Pull/merge request can't be merged".red().bold())?;
+                write!(f, "   {}\n\n", reason.dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Review it in the browser: {}", "devflow open --pr".green())?;
+                write!(f, "   2. Check again with: {}", "devflow merge --dry-run".green())
+            }
             DevFlowError::GitHubAuthFailed => {
-                write!(f, "{}\n", "GitHub authentication failed".red().bold())?;
+                writeln!(f, "{}", "GitHub authentication failed".red().bold())?;
                 write!(f, "   {}\n\n", "Your GitHub token is invalid or expired".dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Generate new token: Settings > Developer settings > Personal access tokens\n")?;
-                write!(f, "   2. Required scope: repo (full control)\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Generate new token: Settings > Developer settings > Personal access tokens")?;
+                writeln!(f, "   2. Required scope: repo (full control)")?;
                 write!(f, "   3. Update config: {}", "devflow init".green())
             }
             DevFlowError::GitLabAuthFailed => {
-                write!(f, "{}\n", "GitLab authentication failed".red().bold())?;
+                writeln!(f, "{}", "GitLab authentication failed".red().bold())?;
                 write!(f, "   {}\n\n", "Your GitLab token is invalid or expired".dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Generate new token: Settings > Access Tokens\n")?;
-                write!(f, "   2. Required scope: api\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Generate new token: Settings > Access Tokens")?;
+                writeln!(f, "   2. Required scope: api")?;
                 write!(f, "   3. Update config: {}", "devflow init".green())
             }
 
             // Network errors
             DevFlowError::NetworkError(msg) => {
-                write!(f, "{}\n", "Network error".red().bold())?;
+                writeln!(f, "{}", "Network error".red().bold())?;
                 write!(f, "   {}\n\n", msg.dimmed())?;
-                write!(f, "   To fix:\n")?;
-                write!(f, "   1. Check your internet connection\n")?;
-                write!(f, "   2. Verify you can reach the API endpoints\n")?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check your internet connection")?;
+                writeln!(f, "   2. Verify you can reach the API endpoints")?;
                 write!(f, "   3. Try again in a moment")
             }
+            DevFlowError::ProxyAuthFailed(msg) => {
+                writeln!(f, "{}", "Proxy authentication failed".red().bold())?;
+                write!(f, "   {}\n\n", msg.dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Check the proxy credentials: {}", "devflow config set network.proxy_auth user:password".green())?;
+                writeln!(f, "   2. Or fix the user:password in network.proxy or HTTPS_PROXY")?;
+                write!(f, "   3. See which hosts use the proxy: {}", "devflow --verbose <command>".green())
+            }
+            DevFlowError::TlsCertificate(msg) => {
+                writeln!(f, "{}", "TLS certificate not trusted".red().bold())?;
+                write!(f, "   {}\n\n", msg.dimmed())?;
+                writeln!(f, "   To fix:")?;
+                writeln!(f, "   1. Get your internal CA certificate (PEM) from your IT team")?;
+                write!(f, "   2. Trust it: {}", "devflow config set network.ca_cert /path/to/ca.pem".green())
+            }
 
             // Generic
             DevFlowError::Other(msg) => {
-                write!(f, "{}\n", "Error".red().bold())?;
+                writeln!(f, "{}", "Error".red().bold())?;
                 write!(f, "   {}", msg.dimmed())
             }
         }
@@ -226,6 +374,20 @@ mod tests {
         assert!(output.contains("config.toml"));
     }
 
+    #[test]
+    fn test_jira_transition_not_found_lists_available() {
+        let err = DevFlowError::JiraTransitionNotFound(
+            "Done".to_string(),
+            vec!["Start Progress".to_string(), "Resolve".to_string()],
+        );
+        let output = format!("{}", err);
+        assert!(output.contains("'Done' not found"));
+        assert!(output.contains("Available transitions: Start Progress, Resolve"));
+
+        let output = format!("{}", DevFlowError::JiraTransitionNotFound("Done".to_string(), Vec::new()));
+        assert!(output.contains("No transitions are available"));
+    }
+
     #[test]
     fn test_config_validation_failed_display() {
         let err = DevFlowError::ConfigValidationFailed("Jira connection failed".to_string());
@@ -260,6 +422,18 @@ mod tests {
         assert!(output.contains("git init"));
     }
 
+    #[test]
+    fn test_empty_repository_and_detached_head_display() {
+        let output = format!("{}", DevFlowError::EmptyRepository);
+        assert!(output.contains("no commits yet"));
+        assert!(output.contains("git add -A && git commit -m \"Initial commit\""));
+        assert!(!output.contains("devflow commit"));
+
+        let output = format!("{}", DevFlowError::DetachedHead("abc1234".to_string()));
+        assert!(output.contains("detached at abc1234"));
+        assert!(output.contains("git switch"));
+    }
+
     #[test]
     fn test_git_repo_not_clean_error_display() {
         let err = DevFlowError::GitRepoNotClean;
@@ -269,6 +443,80 @@ mod tests {
         assert!(output.contains("git stash"));
     }
 
+    #[test]
+    fn test_nothing_staged_display() {
+        let err = DevFlowError::NothingStaged;
+        let output = format!("{}", err);
+        assert!(output.contains("Nothing staged"));
+        assert!(output.contains("git add"));
+    }
+
+    #[test]
+    fn test_protected_branch_display() {
+        let err = DevFlowError::ProtectedBranch("main".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("'main' is a protected branch"));
+        assert!(output.contains("devflow start"));
+        assert!(output.contains("--allow-protected"));
+    }
+
+    #[test]
+    fn test_commit_already_pushed_display() {
+        let err = DevFlowError::CommitAlreadyPushed("feat/WAB-1".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("already been pushed"));
+        assert!(output.contains("origin/feat/WAB-1"));
+        assert!(output.contains("--force"));
+    }
+
+    #[test]
+    fn test_push_rejected_and_remote_not_found_display() {
+        let output = format!("{}", DevFlowError::PushRejected("feat/WAB-1".to_string()));
+        assert!(output.contains("Push rejected"));
+        assert!(output.contains("devflow sync"));
+        assert!(output.contains("--force-with-lease"));
+
+        let output = format!("{}", DevFlowError::RemoteNotFound("origin".to_string(), vec!["upstream".to_string(), "fork".to_string()]));
+        assert!(output.contains("Remote 'origin' not found"));
+        assert!(output.contains("upstream, fork"));
+        assert!(format!("{}", DevFlowError::RemoteNotFound("origin".to_string(), Vec::new())).contains("has no remotes"));
+    }
+
+    #[test]
+    fn test_hook_already_exists_display() {
+        let err = DevFlowError::HookAlreadyExists(".git/hooks/prepare-commit-msg".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains(".git/hooks/prepare-commit-msg"));
+        assert!(output.contains("--chain"));
+        assert!(output.contains("--force"));
+    }
+
+    #[test]
+    fn test_invalid_label_display() {
+        let output = format!("{}", DevFlowError::InvalidLabel("needs qa".to_string()));
+        assert!(output.contains("Invalid label 'needs qa'"));
+        assert!(output.contains("spaces"));
+    }
+
+    #[test]
+    fn test_hook_failed_display() {
+        let err = DevFlowError::HookFailed("cargo clippy".to_string(), "exited with status 101".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("Hook exited with status 101"));
+        assert!(output.contains("cargo clippy"));
+        assert!(output.contains("[hooks]"));
+    }
+
+    #[test]
+    fn test_invalid_commit_message_display() {
+        let err = DevFlowError::InvalidCommitMessage("Unknown commit type 'feature'".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("Unknown commit type 'feature'"));
+        assert!(output.contains("type(scope): description"));
+        assert!(output.contains("feat(auth): add token refresh"));
+        assert!(output.contains("--bypass-convention"));
+    }
+
     #[test]
     fn test_branch_has_no_ticket_id_display() {
         let err = DevFlowError::BranchHasNoTicketId("main".to_string());
@@ -286,6 +534,26 @@ mod tests {
         assert!(output.contains("already exists"));
     }
 
+    #[test]
+    fn test_invalid_branch_name_display() {
+        let err = DevFlowError::InvalidBranchName("bad name".to_string(), "^[a-z]+$".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("bad name"));
+        assert!(output.contains("^[a-z]+$"));
+        assert!(output.contains("preferences.branch_name_regex"));
+    }
+
+    #[test]
+    fn test_jira_api_error_display() {
+        let output = format!("{}", DevFlowError::JiraApiError(400, "duedate: invalid date".to_string()));
+        assert!(output.contains("duedate: invalid date"));
+        assert!(output.contains("correct the fields it names"));
+        assert!(!output.contains("network connection"));
+
+        let output = format!("{}", DevFlowError::JiraApiError(502, "Bad Gateway".to_string()));
+        assert!(output.contains("check your network connection"));
+    }
+
     #[test]
     fn test_network_error_display() {
         let err = DevFlowError::NetworkError("Connection timeout".to_string());
@@ -295,6 +563,31 @@ mod tests {
         assert!(output.contains("internet connection"));
     }
 
+    #[test]
+    fn test_proxy_auth_failed_display() {
+        let err = DevFlowError::ProxyAuthFailed("proxy authorization required".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("Proxy authentication failed"));
+        assert!(output.contains("network.proxy_auth"));
+    }
+
+    #[test]
+    fn test_tls_certificate_display() {
+        let err = DevFlowError::TlsCertificate("self-signed certificate in certificate chain".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("TLS certificate not trusted"));
+        assert!(output.contains("network.ca_cert"));
+    }
+
+    #[test]
+    fn test_merge_blocked_display() {
+        let err = DevFlowError::MergeBlocked("required reviews are missing".to_string());
+        let output = format!("{}", err);
+        assert!(output.contains("can't be merged"));
+        assert!(output.contains("required reviews are missing"));
+        assert!(output.contains("devflow merge --dry-run"));
+    }
+
     #[test]
     fn test_github_auth_failed_display() {
         let err = DevFlowError::GitHubAuthFailed;
@@ -0,0 +1,92 @@
+//! User-configured shell commands run around devflow operations (`[hooks]`
+//! in config.toml). Unrelated to the git hooks in `utils::hooks`.
+
+use crate::errors::{DevFlowError, Result};
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Run `command` through the platform shell with `env_vars` added to its
+/// environment. Output goes straight to the terminal; a non-zero exit
+/// fails the calling devflow command.
+pub fn run_hook(command: &str, env_vars: &HashMap<&str, &str>) -> Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    shell.arg(command).envs(env_vars);
+    // Keep stdout clean for `--json` results
+    if crate::utils::output::is_json() {
+        shell.stdout(std::io::stderr());
+    }
+
+    let status = shell
+        .status()
+        .map_err(|e| DevFlowError::HookFailed(command.to_string(), e.to_string()))?;
+
+    if !status.success() {
+        let reason = match status.code() {
+            Some(code) => format!("exited with status {}", code),
+            None => "was terminated by a signal".to_string(),
+        };
+        return Err(DevFlowError::HookFailed(command.to_string(), reason));
+    }
+
+    Ok(())
+}
+
+/// Run the hook configured for `name` (e.g. `pre_start`), if any.
+/// `DEVFLOW_HOOK` is set to `name` alongside `env_vars`.
+pub fn run_configured(name: &str, command: Option<&str>, env_vars: &HashMap<&str, &str>) -> Result<()> {
+    let Some(command) = command.filter(|c| !c.trim().is_empty()) else {
+        return Ok(());
+    };
+
+    if !crate::utils::progress::is_quiet() {
+        eprintln!("  → {} hook: {}", name, command);
+    }
+
+    let mut env_vars = env_vars.clone();
+    env_vars.insert("DEVFLOW_HOOK", name);
+    run_hook(command, &env_vars)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_hook_passes_env_vars() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let out_path = out.to_str().unwrap();
+
+        let env = HashMap::from([("DEVFLOW_TICKET", "WAB-1"), ("OUT", out_path)]);
+        run_configured("post_start", Some("echo \"$DEVFLOW_HOOK $DEVFLOW_TICKET\" > \"$OUT\""), &env).unwrap();
+
+        assert_eq!(std::fs::read_to_string(out).unwrap().trim(), "post_start WAB-1");
+    }
+
+    #[test]
+    fn test_run_hook_fails_on_non_zero_exit() {
+        let err = run_hook("exit 3", &HashMap::new()).unwrap_err();
+        match err {
+            DevFlowError::HookFailed(command, reason) => {
+                assert_eq!(command, "exit 3");
+                assert_eq!(reason, "exited with status 3");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unset_hook_is_skipped() {
+        assert!(run_configured("pre_done", None, &HashMap::new()).is_ok());
+        assert!(run_configured("pre_done", Some("  "), &HashMap::new()).is_ok());
+    }
+}
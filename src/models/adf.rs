@@ -0,0 +1,269 @@
+//! Minimal Atlassian Document Format (ADF) support.
+//!
+//! Jira Cloud's v3 API takes rich-text fields (descriptions, comments) as ADF
+//! documents rather than plain strings.
+
+use colored::*;
+use serde_json::{json, Value};
+
+/// Wrap plain text in an ADF document: blank lines separate paragraphs and
+/// single newlines become hard breaks.
+pub fn from_plain_text(text: &str) -> Value {
+    let paragraphs: Vec<Value> = text
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|paragraph| {
+            let mut content = Vec::new();
+            for (i, line) in paragraph.lines().enumerate() {
+                if i > 0 {
+                    content.push(json!({ "type": "hardBreak" }));
+                }
+                if !line.is_empty() {
+                    content.push(json!({ "type": "text", "text": line }));
+                }
+            }
+            json!({ "type": "paragraph", "content": content })
+        })
+        .collect();
+
+    json!({
+        "type": "doc",
+        "version": 1,
+        "content": paragraphs
+    })
+}
+
+/// Flatten an ADF document to plain text: block nodes are separated by blank
+/// lines, hard breaks become newlines, list items get `•` or their number and
+/// code blocks are indented.
+pub fn to_plain_text(doc: &Value) -> String {
+    blocks(doc, Style::Plain)
+}
+
+/// [`to_plain_text`] for printing to a terminal: headings are bold and code
+/// blocks have a background. Styles never span lines, so callers can indent
+/// line by line. Without colors this is just [`to_plain_text`].
+pub fn to_terminal(doc: &Value) -> String {
+    if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        return to_plain_text(doc);
+    }
+    blocks(doc, Style::Terminal)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Plain,
+    Terminal,
+}
+
+/// The node's children rendered as blocks, separated by blank lines
+fn blocks(node: &Value, style: Style) -> String {
+    children(node).map(|child| block(child, style)).filter(|b| !b.is_empty()).collect::<Vec<_>>().join("\n\n")
+}
+
+fn children(node: &Value) -> impl Iterator<Item = &Value> {
+    node["content"].as_array().into_iter().flatten()
+}
+
+fn block(node: &Value, style: Style) -> String {
+    match node["type"].as_str() {
+        Some("paragraph") => inline(node),
+        Some("heading") => {
+            let text = inline(node);
+            match style {
+                Style::Plain => text,
+                Style::Terminal => text.lines().map(|line| line.bold().to_string()).collect::<Vec<_>>().join("\n"),
+            }
+        }
+        Some("bulletList") => list(node, style, |_| "•".to_string()),
+        Some("orderedList") => {
+            let start = node["attrs"]["order"].as_u64().unwrap_or(1);
+            list(node, style, |i| format!("{}.", start + i as u64))
+        }
+        Some("codeBlock") => code_block(&inline(node), style),
+        Some("text" | "hardBreak" | "mention" | "inlineCard") => inline_node(node),
+        // Panels, quotes, expands and the like: whatever they contain
+        _ => blocks(node, style),
+    }
+}
+
+/// One line per item, each starting with its marker; an item's further lines
+/// (and nested lists) are indented to line up with its text
+fn list(node: &Value, style: Style, marker: impl Fn(usize) -> String) -> String {
+    children(node)
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = marker(i);
+            let indent = " ".repeat(marker.chars().count() + 1);
+            let content = children(item).map(|child| block(child, style)).collect::<Vec<_>>().join("\n");
+            let mut lines = content.lines();
+            let mut out = format!("{} {}", marker, lines.next().unwrap_or_default());
+            for line in lines {
+                out.push('\n');
+                if !line.is_empty() {
+                    out.push_str(&indent);
+                    out.push_str(line);
+                }
+            }
+            out
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn code_block(code: &str, style: Style) -> String {
+    let code = code.trim_end_matches('\n');
+    match style {
+        Style::Plain => code.lines().map(|line| format!("    {}", line).trim_end().to_string()).collect::<Vec<_>>().join("\n"),
+        Style::Terminal => {
+            let width = code.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+            code.lines()
+                .map(|line| format!(" {:<width$} ", line, width = width).on_bright_black().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+/// The text of a node's inline children
+fn inline(node: &Value) -> String {
+    children(node).map(inline_node).collect()
+}
+
+fn inline_node(node: &Value) -> String {
+    let attr = |name: &str| node["attrs"][name].as_str().unwrap_or_default().to_string();
+    match node["type"].as_str() {
+        Some("text") => node["text"].as_str().unwrap_or_default().to_string(),
+        Some("hardBreak") => "\n".to_string(),
+        // `text` is the display name with its `@`, when Jira filled it in
+        Some("mention") => match attr("text") {
+            text if text.is_empty() => "@unknown".to_string(),
+            text if text.starts_with('@') => text,
+            text => format!("@{}", text),
+        },
+        Some("inlineCard") => attr("url"),
+        _ => inline(node),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_plain_text_single_paragraph() {
+        let doc = from_plain_text("Fix the login page");
+        assert_eq!(doc["type"], "doc");
+        assert_eq!(doc["version"], 1);
+        assert_eq!(doc["content"][0]["type"], "paragraph");
+        assert_eq!(doc["content"][0]["content"][0]["text"], "Fix the login page");
+    }
+
+    #[test]
+    fn test_from_plain_text_paragraphs_and_breaks() {
+        let doc = from_plain_text("Line one\nLine two\n\nSecond paragraph");
+        let content = doc["content"].as_array().unwrap();
+        assert_eq!(content.len(), 2);
+
+        let first = content[0]["content"].as_array().unwrap();
+        assert_eq!(first[0]["text"], "Line one");
+        assert_eq!(first[1]["type"], "hardBreak");
+        assert_eq!(first[2]["text"], "Line two");
+
+        assert_eq!(content[1]["content"][0]["text"], "Second paragraph");
+    }
+
+    #[test]
+    fn test_to_plain_text_round_trip() {
+        let text = "Line one\nLine two\n\nSecond paragraph";
+        assert_eq!(to_plain_text(&from_plain_text(text)), text);
+    }
+
+    #[test]
+    fn test_to_plain_text_nodes() {
+        let text = |text: &str| json!({ "type": "text", "text": text });
+        let paragraph = |content: Value| json!({ "type": "paragraph", "content": content });
+        let item = |content: Value| json!({ "type": "listItem", "content": content });
+        let doc = json!({
+            "type": "doc",
+            "version": 1,
+            "content": [
+                { "type": "heading", "attrs": { "level": 2 }, "content": [text("Steps")] },
+                {
+                    "type": "orderedList",
+                    "attrs": { "order": 3 },
+                    "content": [
+                        item(json!([paragraph(json!([text("Log in as "), { "type": "mention", "attrs": { "id": "5b10", "text": "@Ada" } }]))])),
+                        item(json!([
+                            paragraph(json!([text("Open")])),
+                            { "type": "bulletList", "content": [item(json!([paragraph(json!([text("Settings")]))]))] }
+                        ])),
+                    ]
+                },
+                paragraph(json!([text("See "), { "type": "inlineCard", "attrs": { "url": "https://example.com/x" } }])),
+                { "type": "codeBlock", "attrs": { "language": "sh" }, "content": [text("cargo test\n  --all")] },
+                { "type": "panel", "content": [paragraph(json!([text("Careful")]))] }
+            ]
+        });
+
+        assert_eq!(
+            to_plain_text(&doc),
+            "Steps\n\n\
+             3. Log in as @Ada\n\
+             4. Open\n   • Settings\n\n\
+             See https://example.com/x\n\n    \
+             cargo test\n      --all\n\n\
+             Careful"
+        );
+    }
+
+    #[test]
+    fn test_to_terminal_styles_headings_and_code_blocks() {
+        let _colors = crate::utils::output::override_colors(true);
+        let text = |text: &str| json!({ "type": "text", "text": text });
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                { "type": "heading", "attrs": { "level": 2 }, "content": [text("Steps")] },
+                { "type": "paragraph", "content": [text("Run")] },
+                { "type": "codeBlock", "content": [text("cargo test\n  --all")] }
+            ]
+        });
+
+        let bold = "\x1b[1m";
+        let background = "\x1b[100m";
+        let rendered = to_terminal(&doc);
+        let blocks: Vec<&str> = rendered.split("\n\n").collect();
+        assert_eq!(blocks.len(), 3, "{:?}", rendered);
+        assert!(blocks[0].starts_with(bold) && blocks[0].contains("Steps"), "{:?}", blocks[0]);
+        assert_eq!(blocks[1], "Run");
+        // Each line is padded to the widest and styled on its own
+        let code: Vec<&str> = blocks[2].lines().collect();
+        let reset = "\x1b[0m";
+        assert_eq!(
+            code,
+            [format!("{} cargo test {}", background, reset), format!("{}   --all    {}", background, reset)]
+        );
+        assert!(!rendered.contains(&format!("{}Run", bold)));
+    }
+
+    #[test]
+    fn test_mention_without_text() {
+        let doc = json!({
+            "type": "doc",
+            "content": [{ "type": "paragraph", "content": [
+                { "type": "mention", "attrs": { "id": "5b10", "text": "Ada" } },
+                { "type": "text", "text": " and " },
+                { "type": "mention", "attrs": { "id": "5b11" } }
+            ] }]
+        });
+        assert_eq!(to_plain_text(&doc), "@Ada and @unknown");
+    }
+
+    #[test]
+    fn test_from_plain_text_empty() {
+        let doc = from_plain_text("  \n\n ");
+        assert_eq!(doc["content"].as_array().unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// A board from the Jira Agile API (`/rest/agile/1.0/board`)
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JiraBoard {
+    pub id: u64,
+    pub name: String,
+    /// `scrum`, `kanban` or `simple`; only scrum boards have sprints, and
+    /// `simple` boards belong to team-managed projects
+    #[serde(rename = "type")]
+    pub board_type: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Sprint {
+    pub id: u64,
+    pub name: String,
+    /// `future`, `active` or `closed`
+    pub state: String,
+    #[serde(rename = "startDate", default)]
+    pub start_date: Option<String>,
+    #[serde(rename = "endDate", default)]
+    pub end_date: Option<String>,
+}
+
+/// One page of an Agile API list response
+#[derive(Debug, Deserialize)]
+pub struct AgilePage<T> {
+    pub values: Vec<T>,
+    #[serde(rename = "isLast", default = "default_is_last")]
+    pub is_last: bool,
+}
+
+fn default_is_last() -> bool {
+    true
+}
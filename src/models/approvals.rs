@@ -0,0 +1,64 @@
+use serde::{Deserialize, Deserializer};
+
+/// Approval state of a GitLab merge request
+/// (`GET /projects/{id}/merge_requests/{iid}/approvals`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApprovalsInfo {
+    #[serde(default)]
+    pub approvals_required: u32,
+    #[serde(default)]
+    pub approvals_left: u32,
+    /// Usernames of the approvers, in the order they approved
+    #[serde(default, deserialize_with = "approver_usernames")]
+    pub approved_by: Vec<String>,
+}
+
+impl ApprovalsInfo {
+    /// Enough approvals to merge. With no required count, one approval is
+    /// enough.
+    pub fn is_approved(&self) -> bool {
+        !self.approved_by.is_empty() && self.approvals_left == 0
+    }
+}
+
+#[derive(Deserialize)]
+struct Approver {
+    user: ApproverUser,
+}
+
+#[derive(Deserialize)]
+struct ApproverUser {
+    username: String,
+}
+
+/// `approved_by` is a list of `{"user": {...}}`; keep the usernames
+fn approver_usernames<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let approvers = Vec::<Approver>::deserialize(deserializer)?;
+    Ok(approvers.into_iter().map(|approver| approver.user.username).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_approvals() {
+        let approvals: ApprovalsInfo = serde_json::from_str(
+            r#"{"approvals_required":2,"approvals_left":0,"approved_by":[
+                {"user":{"id":1,"username":"alice","name":"Alice"}},
+                {"user":{"id":2,"username":"bob","name":"Bob"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(approvals.approvals_required, 2);
+        assert_eq!(approvals.approved_by, vec!["alice", "bob"]);
+        assert!(approvals.is_approved());
+    }
+
+    #[test]
+    fn test_no_approvals_is_not_approved() {
+        // GitLab CE without approval rules sends only `approved_by`
+        let approvals: ApprovalsInfo = serde_json::from_str(r#"{"approved_by":[]}"#).unwrap();
+        assert_eq!((approvals.approvals_required, approvals.approvals_left), (0, 0));
+        assert!(!approvals.is_approved());
+    }
+}
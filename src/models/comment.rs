@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use super::ticket::User;
+
+/// A comment on an issue (`/issue/{key}/comment`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Comment {
+    pub id: String,
+    pub author: User,
+    /// ADF document on v3, plain text on v2
+    pub body: serde_json::Value,
+    /// Jira timestamp, e.g. `2024-01-15T10:30:00.000+0000`
+    pub created: String,
+}
+
+impl Comment {
+    /// The body styled for printing to a terminal
+    pub fn body_terminal(&self) -> String {
+        match &self.body {
+            serde_json::Value::String(text) => text.clone(),
+            doc => super::adf::to_terminal(doc),
+        }
+    }
+
+    /// `2024-01-15 10:30`, in the timezone Jira reported
+    pub fn created_display(&self) -> String {
+        self.created.get(..16).unwrap_or(&self.created).replace('T', " ")
+    }
+}
+
+/// Body of `GET /issue/{key}/comment`
+#[derive(Debug, Deserialize)]
+pub struct CommentPage {
+    pub comments: Vec<Comment>,
+    /// Comments on the issue, including those not on this page
+    #[serde(default)]
+    pub total: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_body_from_v2_and_v3() {
+        let page: CommentPage = serde_json::from_value(serde_json::json!({
+            "total": 5,
+            "comments": [
+                {
+                    "id": "10001",
+                    "author": { "displayName": "Ada Lovelace" },
+                    "body": "Reproduced on staging",
+                    "created": "2024-01-15T10:30:00.000+0000"
+                },
+                {
+                    "id": "10002",
+                    "author": { "displayName": "Alan Turing" },
+                    "body": {
+                        "type": "doc",
+                        "version": 1,
+                        "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Fixed in #42" }] }]
+                    },
+                    "created": "2024-01-16T08:05:00.000+0100"
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(page.total, 5);
+        assert_eq!(page.comments[0].body_terminal(), "Reproduced on staging");
+        assert_eq!(page.comments[0].created_display(), "2024-01-15 10:30");
+        assert_eq!(page.comments[1].body_terminal().trim(), "Fixed in #42");
+        assert_eq!(page.comments[1].author.display_name, "Alan Turing");
+    }
+}
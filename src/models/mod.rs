@@ -1 +1,7 @@
+pub mod adf;
+pub mod agile;
+pub mod approvals;
+pub mod comment;
+pub mod remote_link;
 pub mod ticket;
+pub mod transition;
@@ -0,0 +1,106 @@
+use serde::Deserialize;
+
+/// A link from a ticket to something outside Jira
+/// (`GET /issue/{key}/remotelink`), e.g. a pull request added by the
+/// GitHub or GitLab integration
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteLink {
+    pub object: RemoteLinkObject,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteLinkObject {
+    pub url: String,
+}
+
+impl RemoteLink {
+    /// Clone URL of the repository the link points into, if it points into one
+    pub fn repository_url(&self) -> Option<String> {
+        repository_url(&self.object.url)
+    }
+}
+
+/// Path segments that start a page inside a repository, e.g. `pull` in
+/// `github.com/org/repo/pull/12` or `-` in `gitlab.com/group/repo/-/merge_requests/3`
+const REPOSITORY_PAGES: &[&str] = &[
+    "-", "pull", "pulls", "commit", "commits", "tree", "blob", "merge_requests", "pull-requests", "src", "branch",
+];
+
+/// Hosts where a bare `https://host/owner/repo` link is a repository
+const REPOSITORY_HOSTS: &[&str] = &["github.com", "gitlab.com", "bitbucket.org"];
+
+fn repository_url(url: &str) -> Option<String> {
+    if url.starts_with("git@") || url.starts_with("ssh://") || url.starts_with("git://") {
+        return Some(url.to_string());
+    }
+
+    let parsed = reqwest::Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = parsed.host_str()?;
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    if segments.last()?.ends_with(".git") {
+        return Some(url.to_string());
+    }
+
+    let origin = match parsed.port() {
+        Some(port) => format!("{}://{}:{}", parsed.scheme(), host, port),
+        None => format!("{}://{}", parsed.scheme(), host),
+    };
+
+    // Bitbucket Server: /projects/KEY/repos/name/... clones from /scm/key/name.git
+    if let ["projects", project, "repos", repo, ..] = segments.as_slice() {
+        return Some(format!("{}/scm/{}/{}.git", origin, project.to_lowercase(), repo));
+    }
+
+    let repo = match segments.iter().position(|s| REPOSITORY_PAGES.contains(s)) {
+        Some(end) if end >= 2 => &segments[..end],
+        None if segments.len() == 2 && REPOSITORY_HOSTS.contains(&host) => &segments[..],
+        _ => return None,
+    };
+    Some(format!("{}/{}.git", origin, repo.join("/")))
+}
+
+/// The directory `git clone <url>` creates: the last path component, without `.git`
+pub fn clone_dir_name(url: &str) -> String {
+    let name = url.trim_end_matches('/').rsplit(['/', ':']).next().unwrap_or(url);
+    name.strip_suffix(".git").unwrap_or(name).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_url() {
+        let cases = [
+            ("https://github.com/acme/api/pull/12", Some("https://github.com/acme/api.git")),
+            ("https://github.com/acme/api/commit/0a1b2c", Some("https://github.com/acme/api.git")),
+            ("https://github.com/acme/api", Some("https://github.com/acme/api.git")),
+            (
+                "https://gitlab.example.com:8443/group/sub/api/-/merge_requests/3",
+                Some("https://gitlab.example.com:8443/group/sub/api.git"),
+            ),
+            ("https://bitbucket.org/acme/api/pull-requests/7", Some("https://bitbucket.org/acme/api.git")),
+            (
+                "https://git.example.com/projects/WAB/repos/api/pull-requests/7/overview",
+                Some("https://git.example.com/scm/wab/api.git"),
+            ),
+            ("https://git.example.com/acme/api.git", Some("https://git.example.com/acme/api.git")),
+            ("git@github.com:acme/api.git", Some("git@github.com:acme/api.git")),
+            ("https://confluence.example.com/display/WAB/Design", None),
+            ("https://example.com/acme/api", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(repository_url(url).as_deref(), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_clone_dir_name() {
+        assert_eq!(clone_dir_name("https://github.com/acme/api.git"), "api");
+        assert_eq!(clone_dir_name("git@github.com:acme/web-app.git"), "web-app");
+        assert_eq!(clone_dir_name("git@host:api"), "api");
+    }
+}
@@ -1,28 +1,241 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct JiraTicket {
     pub key: String,
     pub fields: TicketFields,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TicketFields {
     pub summary: String,
     #[serde(default)]
-    pub description: Option<String>,
+    pub description: Option<Description>,
     pub status: Status,
     #[serde(default)]
     pub assignee: Option<User>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub components: Vec<Component>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub issuetype: Option<IssueType>,
+    /// Jira timestamp, e.g. `2024-01-15T10:30:00.000+0000`
+    #[serde(default)]
+    pub updated: Option<String>,
+    /// Only fetched by `search --fields reporter`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reporter: Option<User>,
+    /// `YYYY-MM-DD`; only fetched by `search --fields duedate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duedate: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issuelinks: Vec<IssueLink>,
+    /// Set on sub-tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<LinkedIssue>,
+    /// Only fetched for `devflow release`
+    #[serde(default, rename = "fixVersions", skip_serializing_if = "Vec::is_empty")]
+    pub fix_versions: Vec<Version>,
+}
+
+/// Jira v2 returns descriptions as plain strings, v3 as ADF documents
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Description {
+    Text(String),
+    Adf(serde_json::Value),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl Description {
+    /// The description styled for printing to a terminal
+    pub fn to_terminal(&self) -> String {
+        match self {
+            Description::Text(text) => text.clone(),
+            Description::Adf(doc) => super::adf::to_terminal(doc),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Status {
     pub name: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// A project component (e.g. "backend"), from tickets and `/project/{key}/components`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Component {
+    pub name: String,
+}
+
+/// A project visible to the current user, from `/project`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct JiraProject {
+    pub key: String,
+    pub name: String,
+}
+
+/// A project version from `/project/{key}/versions`, used as a fix version
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Version {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub released: bool,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// A version for `create_release_version` to add to a project
+#[derive(Debug, Clone, Serialize)]
+pub struct JiraRelease {
+    pub name: String,
+    pub released: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Priority {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IssueType {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct User {
     #[serde(rename = "displayName")]
     pub display_name: String,
 }
+
+/// A Jira user, as returned by `/myself` and `/user/search`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JiraUser {
+    /// `accountId` on Cloud. Server/Data Center has no account IDs, so this
+    /// holds the username, which JQL accepts in the same places.
+    pub account_id: String,
+    pub display_name: String,
+    /// Hidden on Cloud unless the user's profile makes it visible
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+impl JiraUser {
+    pub fn from_value(user: &serde_json::Value) -> Option<Self> {
+        let account_id = user["accountId"].as_str().or_else(|| user["name"].as_str())?;
+
+        Some(Self {
+            account_id: account_id.to_string(),
+            display_name: user["displayName"].as_str().unwrap_or(account_id).to_string(),
+            email: user["emailAddress"].as_str().map(str::to_string),
+        })
+    }
+}
+
+/// Fields for a ticket created with `devflow create`
+#[derive(Debug, Default)]
+pub struct NewIssue {
+    pub project_key: String,
+    pub summary: String,
+    /// Issue type name (e.g. "Task"), resolved to an ID via createmeta
+    pub issue_type: String,
+    pub description: Option<String>,
+    pub labels: Vec<String>,
+    /// Component names, checked against the project's components
+    pub components: Vec<String>,
+    pub priority: Option<String>,
+    pub assign_to_me: bool,
+    /// Parent ticket key; makes this a sub-task of the project's sub-task
+    /// type, and `issue_type` is ignored
+    pub parent: Option<String>,
+}
+
+/// A kind of link between two issues (`/issueLinkType`), e.g. "Blocks"
+/// with outward "blocks" and inward "is blocked by"
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IssueLinkType {
+    pub id: String,
+    pub name: String,
+    pub inward: String,
+    pub outward: String,
+}
+
+/// A link from a ticket to another one (`fields.issuelinks`). Only the other
+/// ticket's side is set: `outward_issue` when this ticket "blocks" it,
+/// `inward_issue` when this ticket "is blocked by" it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IssueLink {
+    #[serde(rename = "type")]
+    pub link_type: IssueLinkType,
+    #[serde(rename = "inwardIssue", default, skip_serializing_if = "Option::is_none")]
+    pub inward_issue: Option<LinkedIssue>,
+    #[serde(rename = "outwardIssue", default, skip_serializing_if = "Option::is_none")]
+    pub outward_issue: Option<LinkedIssue>,
+}
+
+impl IssueLink {
+    /// How this ticket relates to the other one, e.g. ("is blocked by", WAB-2)
+    pub fn describe(&self) -> Option<(&str, &LinkedIssue)> {
+        match (&self.outward_issue, &self.inward_issue) {
+            (Some(issue), _) => Some((&self.link_type.outward, issue)),
+            (None, Some(issue)) => Some((&self.link_type.inward, issue)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The other end of an `IssueLink`, or a sub-task's parent, with just
+/// enough fields to list it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LinkedIssue {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<LinkedIssueFields>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LinkedIssueFields {
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub status: Option<Status>,
+}
+
+/// A ticket as a search lists it, for tests: `key` in `status`, summary
+/// "Fix login"
+#[cfg(test)]
+pub(crate) fn listed(key: &str, status: &str) -> JiraTicket {
+    serde_json::from_value(serde_json::json!({
+        "key": key,
+        "fields": { "summary": "Fix login", "status": { "name": status } }
+    }))
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_and_type_optional() {
+        let absent: JiraTicket = serde_json::from_value(serde_json::json!({
+            "key": "WAB-1",
+            "fields": { "summary": "Login fails", "status": { "name": "To Do" } }
+        }))
+        .unwrap();
+        assert!(absent.fields.priority.is_none());
+        assert!(absent.fields.issuetype.is_none());
+
+        // Projects without priorities send null
+        let null: JiraTicket = serde_json::from_value(serde_json::json!({
+            "key": "WAB-2",
+            "fields": { "summary": "Add SSO", "status": { "name": "To Do" }, "priority": null, "issuetype": { "name": "Story" } }
+        }))
+        .unwrap();
+        assert!(null.fields.priority.is_none());
+        assert_eq!(null.fields.issuetype.map(|t| t.name).as_deref(), Some("Story"));
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+use super::ticket::Status;
+
+/// A workflow transition available on an issue (`/issue/{key}/transitions`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Transition {
+    pub id: String,
+    pub name: String,
+    /// Status the issue moves to
+    #[serde(rename = "to", default)]
+    pub to_status: Status,
+    /// IDs of the fields the transition screen requires (e.g. `resolution`),
+    /// sorted. Only filled in when the transitions were requested with
+    /// `expand=transitions.fields`
+    #[serde(rename = "fields", default, deserialize_with = "required_fields")]
+    pub fields_required: Vec<String>,
+}
+
+/// Body of `GET /issue/{key}/transitions`
+#[derive(Debug, Deserialize)]
+pub struct TransitionsResponse {
+    pub transitions: Vec<Transition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FieldMeta {
+    #[serde(default)]
+    required: bool,
+}
+
+/// `fields` maps field IDs to their metadata; keep the required ones
+fn required_fields<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let fields = HashMap::<String, FieldMeta>::deserialize(deserializer)?;
+    let mut required: Vec<String> = fields
+        .into_iter()
+        .filter(|(_, meta)| meta.required)
+        .map(|(id, _)| id)
+        .collect();
+    required.sort();
+    Ok(required)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_expanded_transitions() {
+        let body = serde_json::json!({
+            "expand": "transitions",
+            "transitions": [
+                {
+                    "id": "21",
+                    "name": "In Progress",
+                    "hasScreen": false,
+                    "isGlobal": true,
+                    "to": {
+                        "self": "https://jira.example.com/rest/api/2/status/3",
+                        "name": "In Progress",
+                        "id": "3",
+                        "statusCategory": { "id": 4, "key": "indeterminate", "name": "In Progress" }
+                    },
+                    "fields": {}
+                },
+                {
+                    "id": "31",
+                    "name": "Done",
+                    "hasScreen": true,
+                    "to": { "name": "Closed", "id": "6" },
+                    "fields": {
+                        "resolution": {
+                            "required": true,
+                            "schema": { "type": "resolution", "system": "resolution" },
+                            "name": "Resolution",
+                            "operations": ["set"],
+                            "allowedValues": [{ "id": "1", "name": "Fixed" }]
+                        },
+                        "comment": { "required": false, "name": "Comment", "operations": ["add"] },
+                        "fixVersions": { "required": true, "name": "Fix Version/s", "operations": ["set"] }
+                    }
+                }
+            ]
+        });
+
+        let response: TransitionsResponse = serde_json::from_value(body).unwrap();
+        let [start, done] = &response.transitions[..] else {
+            panic!("expected two transitions");
+        };
+
+        assert_eq!((start.id.as_str(), start.name.as_str()), ("21", "In Progress"));
+        assert_eq!(start.to_status.name, "In Progress");
+        assert!(start.fields_required.is_empty());
+
+        assert_eq!(done.to_status.name, "Closed");
+        assert_eq!(done.fields_required, vec!["fixVersions", "resolution"]);
+    }
+
+    #[test]
+    fn test_deserialize_without_fields_expansion() {
+        let body = serde_json::json!({ "transitions": [{ "id": "11", "name": "To Do", "to": { "name": "Open" } }] });
+        let response: TransitionsResponse = serde_json::from_value(body).unwrap();
+        assert!(response.transitions[0].fields_required.is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+//! Bulk actions on the tickets picked in `search --multi` and `list --multi`
+
+use crate::api::jira::JiraClient;
+use crate::errors::DevFlowError;
+use crate::models::ticket::JiraUser;
+use crate::utils::output::say;
+use colored::*;
+
+#[derive(Debug, Clone)]
+pub enum BulkAction {
+    /// Apply the Jira transition with this name
+    Transition(String),
+    AddLabel(String),
+    RemoveLabel(String),
+    /// `None` unassigns
+    Assign(Option<JiraUser>),
+}
+
+impl BulkAction {
+    pub fn describe(&self) -> String {
+        match self {
+            BulkAction::Transition(status) => format!("transition to {}", status),
+            BulkAction::AddLabel(label) => format!("add label {}", label),
+            BulkAction::RemoveLabel(label) => format!("remove label {}", label),
+            BulkAction::Assign(Some(user)) => format!("assign to {}", user.display_name),
+            BulkAction::Assign(None) => "unassign".to_string(),
+        }
+    }
+}
+
+/// The Jira calls bulk actions make, so the executor can run against a fake
+pub trait BulkTarget {
+    async fn transition(&self, key: &str, status: &str) -> anyhow::Result<()>;
+    async fn update_labels(&self, key: &str, add: &[String], remove: &[String]) -> anyhow::Result<()>;
+    async fn assign(&self, key: &str, user: Option<&JiraUser>) -> anyhow::Result<()>;
+}
+
+impl BulkTarget for JiraClient {
+    async fn transition(&self, key: &str, status: &str) -> anyhow::Result<()> {
+        self.update_status(key, status).await
+    }
+
+    async fn update_labels(&self, key: &str, add: &[String], remove: &[String]) -> anyhow::Result<()> {
+        JiraClient::update_labels(self, key, add, remove).await
+    }
+
+    async fn assign(&self, key: &str, user: Option<&JiraUser>) -> anyhow::Result<()> {
+        self.assign_issue(key, user).await
+    }
+}
+
+/// How one ticket fared; `error` is `None` on success
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkOutcome {
+    pub key: String,
+    pub error: Option<String>,
+}
+
+/// Run `action` on each ticket in turn, printing a line per ticket and
+/// carrying on past failures. With `dry_run` nothing is sent to Jira and
+/// every ticket counts as a success.
+pub async fn run(target: &impl BulkTarget, keys: &[String], action: &BulkAction, dry_run: bool) -> Vec<BulkOutcome> {
+    let description = action.describe();
+    let mut outcomes = Vec::with_capacity(keys.len());
+
+    for key in keys {
+        if dry_run {
+            say!("  {} {} would {}", "•".dimmed(), key.bright_white(), description);
+            outcomes.push(BulkOutcome { key: key.clone(), error: None });
+            continue;
+        }
+
+        let result = match action {
+            BulkAction::Transition(status) => target.transition(key, status).await,
+            BulkAction::AddLabel(label) => target.update_labels(key, std::slice::from_ref(label), &[]).await,
+            BulkAction::RemoveLabel(label) => target.update_labels(key, &[], std::slice::from_ref(label)).await,
+            BulkAction::Assign(user) => target.assign(key, user.as_ref()).await,
+        };
+
+        let error = result.err().map(|e| short_reason(&e));
+        match &error {
+            None => say!("  {} {} {}", "✓".green(), key.bright_white(), description),
+            Some(reason) => say!("  {} {} {}", "✗".red(), key.bright_white(), reason.red()),
+        }
+        outcomes.push(BulkOutcome { key: key.clone(), error });
+    }
+
+    outcomes
+}
+
+/// One line for the summary: our multi-line errors are cut to their gist
+fn short_reason(error: &anyhow::Error) -> String {
+    if let Some(DevFlowError::JiraTransitionNotFound(status, _)) = error.downcast_ref::<DevFlowError>() {
+        return format!("transition '{}' not available", status);
+    }
+    let message = error.to_string();
+    message.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default().to_string()
+}
+
+/// `3 succeeded, 1 failed: PROJ-9 — transition 'Done' not available`, or
+/// `3 would be changed` for a dry run
+pub fn summary(outcomes: &[BulkOutcome], dry_run: bool) -> String {
+    let failures: Vec<String> = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.error.as_ref().map(|error| format!("{} — {}", outcome.key, error)))
+        .collect();
+    let succeeded = outcomes.len() - failures.len();
+    let succeeded = if dry_run {
+        format!("{} would be changed", succeeded)
+    } else {
+        format!("{} succeeded", succeeded)
+    };
+
+    if failures.is_empty() {
+        succeeded
+    } else {
+        format!("{}, {} failed: {}", succeeded, failures.len(), failures.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records each call; tickets in `failing` reject every change
+    #[derive(Default)]
+    struct FakeJira {
+        calls: RefCell<Vec<String>>,
+        failing: Vec<&'static str>,
+    }
+
+    impl FakeJira {
+        fn record(&self, key: &str, call: String) -> anyhow::Result<()> {
+            self.calls.borrow_mut().push(format!("{} {}", key, call));
+            if self.failing.contains(&key) {
+                return Err(anyhow::anyhow!("Jira API error (400)\n   details"));
+            }
+            Ok(())
+        }
+    }
+
+    impl BulkTarget for FakeJira {
+        async fn transition(&self, key: &str, status: &str) -> anyhow::Result<()> {
+            if key == "WAB-9" {
+                return Err(DevFlowError::JiraTransitionNotFound(status.to_string(), vec!["Reopen".to_string()]).into());
+            }
+            self.record(key, format!("transition {}", status))
+        }
+
+        async fn update_labels(&self, key: &str, add: &[String], remove: &[String]) -> anyhow::Result<()> {
+            self.record(key, format!("labels +{:?} -{:?}", add, remove))
+        }
+
+        async fn assign(&self, key: &str, user: Option<&JiraUser>) -> anyhow::Result<()> {
+            self.record(key, format!("assign {:?}", user.map(|user| user.account_id.as_str())))
+        }
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_run_continues_past_failures() {
+        let jira = FakeJira::default();
+        let action = BulkAction::Transition("Done".to_string());
+        let outcomes = run(&jira, &keys(&["WAB-1", "WAB-9", "WAB-2", "WAB-3"]), &action, false).await;
+
+        assert_eq!(*jira.calls.borrow(), vec!["WAB-1 transition Done", "WAB-2 transition Done", "WAB-3 transition Done"]);
+        assert_eq!(summary(&outcomes, false), "3 succeeded, 1 failed: WAB-9 — transition 'Done' not available");
+    }
+
+    #[tokio::test]
+    async fn test_run_labels_and_assign() {
+        let jira = FakeJira { failing: vec!["WAB-2"], ..Default::default() };
+        let outcomes = run(&jira, &keys(&["WAB-1", "WAB-2"]), &BulkAction::RemoveLabel("stale".to_string()), false).await;
+        assert_eq!(summary(&outcomes, false), "1 succeeded, 1 failed: WAB-2 — Jira API error (400)");
+
+        let jira = FakeJira::default();
+        run(&jira, &keys(&["WAB-1"]), &BulkAction::AddLabel("triaged".to_string()), false).await;
+        run(&jira, &keys(&["WAB-1"]), &BulkAction::Assign(None), false).await;
+        assert_eq!(*jira.calls.borrow(), vec!["WAB-1 labels +[\"triaged\"] -[]", "WAB-1 assign None"]);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_changes_nothing() {
+        let jira = FakeJira::default();
+        let action = BulkAction::Transition("Done".to_string());
+        let outcomes = run(&jira, &keys(&["WAB-1", "WAB-9"]), &action, true).await;
+
+        assert!(jira.calls.borrow().is_empty());
+        assert_eq!(summary(&outcomes, true), "2 would be changed");
+        assert_eq!(action.describe(), "transition to Done");
+    }
+}
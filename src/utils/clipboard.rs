@@ -0,0 +1,61 @@
+//! Copying text to the clipboard: the system clipboard through arboard, or
+//! the local terminal's clipboard via OSC 52 when running over SSH
+
+use base64::Engine;
+use std::io::{IsTerminal, Write};
+
+/// Where copied text ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Copied {
+    System,
+    /// Handed to the terminal emulator with OSC 52
+    Terminal,
+}
+
+/// Copy `text`. Over SSH the system clipboard (if any) belongs to the remote
+/// machine, so the terminal on the local machine is asked instead.
+pub fn copy(text: &str) -> anyhow::Result<Copied> {
+    if is_ssh_session() && std::io::stdout().is_terminal() {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(osc52(text).as_bytes())?;
+        stdout.flush()?;
+        return Ok(Copied::Terminal);
+    }
+
+    copy_to_system(text)?;
+    Ok(Copied::System)
+}
+
+fn is_ssh_session() -> bool {
+    std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some()
+}
+
+/// Escape sequence asking the terminal to put `text` on its clipboard
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64::engine::general_purpose::STANDARD.encode(text))
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_to_system(text: &str) -> anyhow::Result<()> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| anyhow::anyhow!("Could not access the clipboard: {}", e))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_system(_text: &str) -> anyhow::Result<()> {
+    anyhow::bail!("devflow was built without clipboard support (enable the `clipboard` feature)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_encodes_text() {
+        assert_eq!(
+            osc52("https://jira.example.com/browse/WAB-1"),
+            "\x1b]52;c;aHR0cHM6Ly9qaXJhLmV4YW1wbGUuY29tL2Jyb3dzZS9XQUItMQ==\x07"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! CODEOWNERS parsing and matching, following GitHub's rules: patterns use
+//! gitignore-style globs and the last matching line wins.
+
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Where GitHub looks for CODEOWNERS, in order
+const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug)]
+struct Rule {
+    pattern: Regex,
+    owners: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+/// A CODEOWNERS owner as a GitHub review request target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Owner {
+    User(String),
+    /// Team slug, without the `org/` prefix
+    Team(String),
+}
+
+impl Owner {
+    /// `@user` or `@org/team`; emails can't be requested as reviewers
+    pub fn parse(owner: &str) -> Option<Self> {
+        let name = owner.strip_prefix('@')?;
+        match name.split_once('/') {
+            Some((_, team)) if !team.is_empty() => Some(Owner::Team(team.to_string())),
+            Some(_) => None,
+            None => Some(Owner::User(name.to_string())),
+        }
+    }
+}
+
+impl CodeOwners {
+    /// Find and parse the repository's CODEOWNERS file, if it has one
+    pub fn load(workdir: &Path) -> Option<(PathBuf, Self)> {
+        LOCATIONS.iter().map(|location| workdir.join(location)).find_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            Some((path, Self::parse(&content)))
+        })
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = glob_to_regex(parts.next()?)?;
+                let owners = parts
+                    .take_while(|part| !part.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some(Rule { pattern, owners })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Owners of `path` (relative to the repository root). A matching line
+    /// without owners means nobody owns it.
+    pub fn owners_for(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.is_match(path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Owners of any of `paths`, deduplicated in first-seen order
+    pub fn owners_for_all<'a>(&'a self, paths: &[String]) -> Vec<&'a str> {
+        let mut owners: Vec<&str> = Vec::new();
+        for path in paths {
+            for owner in self.owners_for(path) {
+                if !owners.contains(&owner.as_str()) {
+                    owners.push(owner);
+                }
+            }
+        }
+        owners
+    }
+}
+
+/// Translate a CODEOWNERS pattern into an anchored regex over repo paths
+fn glob_to_regex(pattern: &str) -> Option<Regex> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // A slash anywhere but the end anchors the pattern to the repo root
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    if dir_only {
+        regex.push_str("/.*");
+    } else if !trimmed.ends_with("/*") {
+        // Matching a directory matches everything beneath it, but `docs/*`
+        // only covers files directly inside `docs`
+        regex.push_str("(?:/.*)?");
+    }
+    regex.push('$');
+
+    Regex::new(&regex).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners(codeowners: &CodeOwners, path: &str) -> Vec<String> {
+        codeowners.owners_for(path).to_vec()
+    }
+
+    #[test]
+    fn test_last_match_wins() {
+        let codeowners = CodeOwners::parse(
+            "# Default owners\n\
+             *       @org/core\n\
+             *.rs    @rustacean\n\
+             /src/api/ @org/api-team @alice\n",
+        );
+
+        assert_eq!(owners(&codeowners, "README.md"), vec!["@org/core"]);
+        assert_eq!(owners(&codeowners, "src/main.rs"), vec!["@rustacean"]);
+        assert_eq!(owners(&codeowners, "src/api/jira.rs"), vec!["@org/api-team", "@alice"]);
+    }
+
+    #[test]
+    fn test_star_does_not_cross_directories() {
+        let codeowners = CodeOwners::parse("docs/*  @writer\n");
+        assert_eq!(owners(&codeowners, "docs/intro.md"), vec!["@writer"]);
+        assert!(owners(&codeowners, "docs/guides/setup.md").is_empty());
+    }
+
+    #[test]
+    fn test_directory_patterns() {
+        let codeowners = CodeOwners::parse("apps/  @app-dev\n/scripts @ops\n");
+        // Unanchored directory matches at any depth
+        assert_eq!(owners(&codeowners, "apps/web/index.ts"), vec!["@app-dev"]);
+        assert_eq!(owners(&codeowners, "services/apps/api.ts"), vec!["@app-dev"]);
+        // Anchored pattern only matches at the root, including nested files
+        assert_eq!(owners(&codeowners, "scripts/deploy/run.sh"), vec!["@ops"]);
+        assert!(owners(&codeowners, "tools/scripts/run.sh").is_empty());
+    }
+
+    #[test]
+    fn test_double_star_and_unowned_override() {
+        let codeowners = CodeOwners::parse(
+            "**/migrations/**  @dba\n\
+             /vendor/**  @org/core\n\
+             /vendor/generated/\n",
+        );
+        assert_eq!(owners(&codeowners, "db/migrations/001.sql"), vec!["@dba"]);
+        assert_eq!(owners(&codeowners, "migrations/002.sql"), vec!["@dba"]);
+        assert_eq!(owners(&codeowners, "vendor/lib/a.rs"), vec!["@org/core"]);
+        assert!(owners(&codeowners, "vendor/generated/b.rs").is_empty());
+    }
+
+    #[test]
+    fn test_owners_for_all_deduplicates() {
+        let codeowners = CodeOwners::parse("*.rs @alice @bob\n*.md @bob @org/docs # trailing comment\n");
+        let files = vec!["a.rs".to_string(), "b.md".to_string(), "c.txt".to_string()];
+        assert_eq!(codeowners.owners_for_all(&files), vec!["@alice", "@bob", "@org/docs"]);
+    }
+
+    #[test]
+    fn test_owner_parse() {
+        assert_eq!(Owner::parse("@alice"), Some(Owner::User("alice".to_string())));
+        assert_eq!(Owner::parse("@org/api-team"), Some(Owner::Team("api-team".to_string())));
+        assert_eq!(Owner::parse("alice@example.com"), None);
+    }
+
+    #[test]
+    fn test_load_prefers_github_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".github")).unwrap();
+        std::fs::write(dir.path().join("CODEOWNERS"), "* @root\n").unwrap();
+        std::fs::write(dir.path().join(".github/CODEOWNERS"), "* @github\n").unwrap();
+
+        let (path, codeowners) = CodeOwners::load(dir.path()).unwrap();
+        assert!(path.ends_with(".github/CODEOWNERS"));
+        assert_eq!(owners(&codeowners, "x"), vec!["@github"]);
+        assert!(CodeOwners::load(&dir.path().join("missing")).is_none());
+    }
+}
@@ -0,0 +1,74 @@
+use regex::Regex;
+
+/// Commit types accepted when `preferences.conventional_commits` is on
+pub const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "test", "chore", "ci",
+];
+
+/// Check the subject line against `type(scope): description`. The scope is
+/// optional and a `!` before the colon marks a breaking change.
+pub fn validate_conventional_commit(message: &str) -> Result<(), String> {
+    let subject = message.lines().next().unwrap_or_default().trim();
+
+    let pattern = Regex::new(r"^([a-z]+)(\([^()\s]+\))?!?: (.*)$").expect("valid regex");
+
+    let captures = pattern
+        .captures(subject)
+        .ok_or_else(|| format!("'{}' is not in the form type(scope): description", subject))?;
+
+    let commit_type = &captures[1];
+    if !CONVENTIONAL_TYPES.contains(&commit_type) {
+        return Err(format!(
+            "Unknown commit type '{}' (expected one of: {})",
+            commit_type,
+            CONVENTIONAL_TYPES.join(", ")
+        ));
+    }
+
+    if captures[3].trim().is_empty() {
+        return Err("Commit description is empty".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_messages() {
+        assert!(validate_conventional_commit("feat: add login").is_ok());
+        assert!(validate_conventional_commit("fix(auth): refresh expired tokens").is_ok());
+        assert!(validate_conventional_commit("refactor(api)!: drop v1 endpoints").is_ok());
+        assert!(validate_conventional_commit("docs: readme\n\nLonger body here").is_ok());
+    }
+
+    #[test]
+    fn test_every_type_is_accepted() {
+        for commit_type in CONVENTIONAL_TYPES {
+            let message = format!("{}: something", commit_type);
+            assert!(validate_conventional_commit(&message).is_ok(), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_missing_type() {
+        let err = validate_conventional_commit("Add login").unwrap_err();
+        assert!(err.contains("type(scope): description"));
+    }
+
+    #[test]
+    fn test_unknown_type() {
+        let err = validate_conventional_commit("feature: add login").unwrap_err();
+        assert!(err.contains("Unknown commit type 'feature'"));
+    }
+
+    #[test]
+    fn test_empty_description_and_bad_scope() {
+        assert!(validate_conventional_commit("fix: ").is_err());
+        assert!(validate_conventional_commit("fix(): broken").is_err());
+        assert!(validate_conventional_commit("fix(a b): broken").is_err());
+        assert!(validate_conventional_commit("fix:missing space").is_err());
+    }
+}
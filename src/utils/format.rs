@@ -0,0 +1,405 @@
+//! `--format` templates for `list` and `search`, e.g. `"{key}\t{status}\t{summary}"`,
+//! `list --export-csv` and `search --fields`
+
+use crate::models::ticket::JiraTicket;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Key,
+    Summary,
+    Status,
+    Assignee,
+    Priority,
+    Type,
+    Labels,
+    Components,
+    Updated,
+    UpdatedRelative,
+}
+
+/// Placeholder names, in the order they are listed in errors
+const FIELDS: &[(&str, Field)] = &[
+    ("key", Field::Key),
+    ("summary", Field::Summary),
+    ("status", Field::Status),
+    ("assignee", Field::Assignee),
+    ("priority", Field::Priority),
+    ("type", Field::Type),
+    ("labels", Field::Labels),
+    ("components", Field::Components),
+    ("updated", Field::Updated),
+    ("updated_relative", Field::UpdatedRelative),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(Field),
+}
+
+/// A parsed `--format` template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TicketFormat {
+    parts: Vec<Part>,
+}
+
+impl TicketFormat {
+    /// clap value parser. `\t`, `\n` and `\\` are unescaped, and `{{` / `}}`
+    /// are literal braces.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('t') => literal.push('\t'),
+                    Some('n') => literal.push('\n'),
+                    Some('\\') => literal.push('\\'),
+                    Some(other) => {
+                        literal.push('\\');
+                        literal.push(other);
+                    }
+                    None => literal.push('\\'),
+                },
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("unterminated placeholder {{{}; close it with }}", name)),
+                        }
+                    }
+                    let field = FIELDS
+                        .iter()
+                        .find(|(placeholder, _)| *placeholder == name)
+                        .map(|(_, field)| *field)
+                        .ok_or_else(|| {
+                            let valid: Vec<String> = FIELDS.iter().map(|(name, _)| format!("{{{}}}", name)).collect();
+                            format!("unknown placeholder {{{}}}; valid placeholders: {}", name, valid.join(", "))
+                        })?;
+
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field(field));
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Fill in the template; missing values (e.g. no assignee) are empty.
+    /// `now` is Unix seconds, for `{updated_relative}`.
+    pub fn render(&self, ticket: &JiraTicket, now: u64) -> String {
+        let mut out = String::new();
+
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Field(field) => out.push_str(&field.value(ticket, now)),
+            }
+        }
+
+        out
+    }
+}
+
+impl Field {
+    fn value(self, ticket: &JiraTicket, now: u64) -> String {
+        let fields = &ticket.fields;
+        match self {
+            Field::Key => ticket.key.clone(),
+            Field::Summary => fields.summary.clone(),
+            Field::Status => fields.status.name.clone(),
+            Field::Assignee => fields.assignee.as_ref().map(|a| a.display_name.clone()).unwrap_or_default(),
+            Field::Priority => fields.priority.as_ref().map(|p| p.name.clone()).unwrap_or_default(),
+            Field::Type => fields.issuetype.as_ref().map(|t| t.name.clone()).unwrap_or_default(),
+            Field::Labels => fields.labels.join(","),
+            Field::Components => fields.components.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(","),
+            Field::Updated => fields.updated.as_deref().and_then(to_rfc3339).unwrap_or_default(),
+            Field::UpdatedRelative => fields
+                .updated
+                .as_deref()
+                .and_then(unix_seconds)
+                .map(|at| crate::config::history::describe_age(at, now))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// `search --fields`: Jira fields fetched on top of the defaults and shown
+/// under each result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExtraField {
+    Priority,
+    Assignee,
+    Reporter,
+    Duedate,
+    Labels,
+    Type,
+}
+
+impl ExtraField {
+    /// Field name in Jira's search API
+    pub fn api_name(self) -> &'static str {
+        match self {
+            ExtraField::Priority => "priority",
+            ExtraField::Assignee => "assignee",
+            ExtraField::Reporter => "reporter",
+            ExtraField::Duedate => "duedate",
+            ExtraField::Labels => "labels",
+            ExtraField::Type => "issuetype",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ExtraField::Priority => "Priority",
+            ExtraField::Assignee => "Assignee",
+            ExtraField::Reporter => "Reporter",
+            ExtraField::Duedate => "Due",
+            ExtraField::Labels => "Labels",
+            ExtraField::Type => "Type",
+        }
+    }
+
+    fn value(self, ticket: &JiraTicket) -> String {
+        let fields = &ticket.fields;
+        let value = match self {
+            ExtraField::Priority => Field::Priority.value(ticket, 0),
+            ExtraField::Assignee => Field::Assignee.value(ticket, 0),
+            ExtraField::Reporter => fields.reporter.as_ref().map(|r| r.display_name.clone()).unwrap_or_default(),
+            ExtraField::Duedate => fields.duedate.clone().unwrap_or_default(),
+            ExtraField::Labels => fields.labels.join(", "),
+            ExtraField::Type => Field::Type.value(ticket, 0),
+        };
+        if value.is_empty() { "none".to_string() } else { value }
+    }
+}
+
+/// The `--fields` line under a search result, e.g. `Priority: High · Due: 2024-05-01`
+pub fn extra_fields_line(ticket: &JiraTicket, fields: &[ExtraField]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.label(), field.value(ticket)))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// `--export-csv` columns, in order
+const CSV_COLUMNS: &[(&str, Field)] = &[
+    ("key", Field::Key),
+    ("summary", Field::Summary),
+    ("status", Field::Status),
+    ("assignee", Field::Assignee),
+    ("priority", Field::Priority),
+    ("issue_type", Field::Type),
+    ("updated", Field::Updated),
+];
+
+/// Write a header row and one row per ticket; returns the number of tickets
+/// written. Fields containing commas, quotes or newlines are quoted.
+pub fn write_csv<W: std::io::Write>(writer: W, tickets: &[JiraTicket]) -> csv::Result<usize> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(CSV_COLUMNS.iter().map(|(name, _)| *name))?;
+    for ticket in tickets {
+        writer.write_record(CSV_COLUMNS.iter().map(|(_, field)| field.value(ticket, 0)))?;
+    }
+    writer.flush()?;
+    Ok(tickets.len())
+}
+
+/// Jira timestamps look like `2024-01-15T10:30:00.000+0000`; RFC 3339 wants
+/// a colon in the offset
+fn to_rfc3339(timestamp: &str) -> Option<String> {
+    let (datetime, offset) = split_offset(timestamp)?;
+    Some(format!("{}{}:{}", datetime, &offset[..3], &offset[3..]))
+}
+
+fn split_offset(timestamp: &str) -> Option<(&str, &str)> {
+    let split = timestamp.len().checked_sub(5)?;
+    let (datetime, offset) = timestamp.split_at(split);
+    let valid = offset.starts_with(['+', '-']) && offset[1..].chars().all(|c| c.is_ascii_digit());
+    valid.then_some((datetime, offset))
+}
+
+fn unix_seconds(timestamp: &str) -> Option<u64> {
+    let (datetime, offset) = split_offset(timestamp)?;
+    let (date, time) = datetime.split_once('T')?;
+
+    let mut date_parts = date.split('-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+
+    let time = time.split('.').next()?;
+    let mut time_parts = time.split(':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (time_parts.next()??, time_parts.next()??, time_parts.next()??);
+
+    let offset_minutes = offset[1..3].parse::<i64>().ok()? * 60 + offset[3..].parse::<i64>().ok()?;
+    let offset_seconds = if offset.starts_with('-') { -offset_minutes * 60 } else { offset_minutes * 60 };
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset_seconds;
+    u64::try_from(seconds).ok()
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// (year, month, day) of a day count from `days_from_civil`
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket() -> JiraTicket {
+        serde_json::from_value(serde_json::json!({
+            "key": "WAB-1",
+            "fields": {
+                "summary": "Login fails",
+                "status": { "name": "In Progress" },
+                "assignee": { "displayName": "Ada Lovelace" },
+                "priority": { "name": "High" },
+                "issuetype": { "name": "Bug" },
+                "labels": ["auth", "urgent"],
+                "components": [{ "id": "10000", "name": "backend" }, { "id": "10001", "name": "mobile" }],
+                "updated": "2024-01-15T10:30:00.000+0100"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_write_csv_quotes_fields() {
+        let mut unassigned = ticket();
+        unassigned.key = "WAB-2".to_string();
+        unassigned.fields.summary = "Crash, then \"retry\" loop".to_string();
+        unassigned.fields.assignee = None;
+
+        let mut out = Vec::new();
+        assert_eq!(write_csv(&mut out, &[ticket(), unassigned]).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "key,summary,status,assignee,priority,issue_type,updated\n\
+             WAB-1,Login fails,In Progress,Ada Lovelace,High,Bug,2024-01-15T10:30:00.000+01:00\n\
+             WAB-2,\"Crash, then \"\"retry\"\" loop\",In Progress,,High,Bug,2024-01-15T10:30:00.000+01:00\n"
+        );
+    }
+
+    #[test]
+    fn test_render_all_placeholders() {
+        let format = TicketFormat::parse("{key}\\t{status}\\t{summary}|{assignee}|{priority}|{type}|{labels}\\n").unwrap();
+        assert_eq!(
+            format.render(&ticket(), 0),
+            "WAB-1\tIn Progress\tLogin fails|Ada Lovelace|High|Bug|auth,urgent\n"
+        );
+    }
+
+    #[test]
+    fn test_render_components() {
+        let format = TicketFormat::parse("{key}:{components}").unwrap();
+        assert_eq!(format.render(&ticket(), 0), "WAB-1:backend,mobile");
+
+        let mut ticket = ticket();
+        ticket.fields.components.clear();
+        assert_eq!(format.render(&ticket, 0), "WAB-1:");
+    }
+
+    #[test]
+    fn test_updated_rfc3339_and_relative() {
+        let format = TicketFormat::parse("{updated} ({updated_relative})").unwrap();
+        // 2024-01-15T09:30:00Z
+        let updated = 1_705_311_000;
+        assert_eq!(
+            format.render(&ticket(), updated + 7200),
+            "2024-01-15T10:30:00.000+01:00 (2h ago)"
+        );
+        assert_eq!(unix_seconds("1970-01-01T00:00:00.000+0000"), Some(0));
+    }
+
+    #[test]
+    fn test_civil_from_days_round_trips() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29)), (2024, 2, 29));
+        assert_eq!(civil_from_days(days_from_civil(2000, 3, 1) - 1), (2000, 2, 29));
+        assert_eq!(civil_from_days(days_from_civil(2023, 12, 31) + 1), (2024, 1, 1));
+    }
+
+    #[test]
+    fn test_missing_values_are_empty() {
+        let ticket: JiraTicket = serde_json::from_value(serde_json::json!({
+            "key": "WAB-2",
+            "fields": { "summary": "Bare", "status": { "name": "To Do" } }
+        }))
+        .unwrap();
+        let format = TicketFormat::parse("{key}:{assignee}:{priority}:{labels}:{updated}").unwrap();
+        assert_eq!(format.render(&ticket, 0), "WAB-2::::");
+    }
+
+    #[test]
+    fn test_extra_fields_line() {
+        let mut ticket = ticket();
+        ticket.fields.duedate = Some("2024-05-01".to_string());
+        let fields = [ExtraField::Priority, ExtraField::Reporter, ExtraField::Duedate, ExtraField::Labels];
+        assert_eq!(
+            extra_fields_line(&ticket, &fields),
+            "Priority: High · Reporter: none · Due: 2024-05-01 · Labels: auth, urgent"
+        );
+        assert_eq!(ExtraField::Type.api_name(), "issuetype");
+    }
+
+    #[test]
+    fn test_unknown_placeholder_lists_valid_ones() {
+        let err = TicketFormat::parse("{key} {reporter}").unwrap_err();
+        assert!(err.contains("{reporter}"));
+        assert!(err.contains("{key}, {summary}, {status}"));
+    }
+
+    #[test]
+    fn test_unterminated_placeholder_is_an_error() {
+        assert_eq!(
+            TicketFormat::parse("{key}\t{summary").unwrap_err(),
+            "unterminated placeholder {summary; close it with }"
+        );
+        assert!(TicketFormat::parse("{").unwrap_err().starts_with("unterminated placeholder"));
+    }
+
+    #[test]
+    fn test_literal_braces_and_backslashes() {
+        let format = TicketFormat::parse("{{{key}}} \\\\ \\x").unwrap();
+        assert_eq!(format.render(&ticket(), 0), "{WAB-1} \\ \\x");
+    }
+}
@@ -0,0 +1,223 @@
+use crate::errors::{DevFlowError, Result};
+use std::path::{Path, PathBuf};
+
+pub const HOOK_NAME: &str = "prepare-commit-msg";
+
+/// Where a pre-existing hook is moved when installing with `--chain`
+pub const CHAINED_HOOK_NAME: &str = "prepare-commit-msg.devflow-chained";
+
+const HOOK_SCRIPT: &str = include_str!("prepare-commit-msg.sh");
+const HOOK_MARKER: &str = "# devflow prepare-commit-msg hook";
+
+#[derive(Debug, PartialEq)]
+pub enum HookStatus {
+    NotInstalled,
+    Installed { chained: bool },
+    /// Some other tool's hook is in the way
+    Foreign,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InstallOutcome {
+    Installed,
+    Updated,
+    Chained,
+    Replaced,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UninstallOutcome {
+    Removed,
+    /// Removed and the chained hook was put back
+    Restored,
+    NotInstalled,
+}
+
+fn is_devflow_hook(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|content| content.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+pub fn status(hooks_dir: &Path) -> HookStatus {
+    let hook = hooks_dir.join(HOOK_NAME);
+
+    if !hook.exists() {
+        HookStatus::NotInstalled
+    } else if is_devflow_hook(&hook) {
+        HookStatus::Installed {
+            chained: hooks_dir.join(CHAINED_HOOK_NAME).exists(),
+        }
+    } else {
+        HookStatus::Foreign
+    }
+}
+
+/// Install the hook. An existing non-devflow hook is kept and run first when
+/// `chain` is set, overwritten when `force` is set, and refused otherwise.
+pub fn install(hooks_dir: &Path, force: bool, chain: bool) -> Result<InstallOutcome> {
+    let hook = hooks_dir.join(HOOK_NAME);
+
+    std::fs::create_dir_all(hooks_dir)
+        .map_err(|e| DevFlowError::Other(format!("Failed to create hooks directory: {}", e)))?;
+
+    let outcome = match status(hooks_dir) {
+        HookStatus::NotInstalled => InstallOutcome::Installed,
+        HookStatus::Installed { .. } => InstallOutcome::Updated,
+        HookStatus::Foreign if chain => {
+            std::fs::rename(&hook, hooks_dir.join(CHAINED_HOOK_NAME))
+                .map_err(|e| DevFlowError::Other(format!("Failed to move existing hook: {}", e)))?;
+            InstallOutcome::Chained
+        }
+        HookStatus::Foreign if force => InstallOutcome::Replaced,
+        HookStatus::Foreign => return Err(DevFlowError::HookAlreadyExists(hook.display().to_string())),
+    };
+
+    write_executable(&hook, HOOK_SCRIPT)?;
+
+    Ok(outcome)
+}
+
+/// Remove the devflow hook, putting back a chained hook if there was one.
+/// Leaves hooks installed by other tools alone.
+pub fn uninstall(hooks_dir: &Path) -> Result<UninstallOutcome> {
+    let hook = hooks_dir.join(HOOK_NAME);
+    let chained = hooks_dir.join(CHAINED_HOOK_NAME);
+
+    match status(hooks_dir) {
+        HookStatus::NotInstalled => Ok(UninstallOutcome::NotInstalled),
+        HookStatus::Foreign => Err(DevFlowError::Other(format!(
+            "{} was not installed by devflow; leaving it alone",
+            hook.display()
+        ))),
+        HookStatus::Installed { chained: has_chained } => {
+            std::fs::remove_file(&hook)
+                .map_err(|e| DevFlowError::Other(format!("Failed to remove hook: {}", e)))?;
+
+            if has_chained {
+                std::fs::rename(&chained, &hook)
+                    .map_err(|e| DevFlowError::Other(format!("Failed to restore chained hook: {}", e)))?;
+                Ok(UninstallOutcome::Restored)
+            } else {
+                Ok(UninstallOutcome::Removed)
+            }
+        }
+    }
+}
+
+fn write_executable(path: &PathBuf, content: &str) -> Result<()> {
+    std::fs::write(path, content)
+        .map_err(|e| DevFlowError::Other(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(install(dir.path(), false, false).unwrap(), InstallOutcome::Installed);
+        assert_eq!(install(dir.path(), false, false).unwrap(), InstallOutcome::Updated);
+        assert_eq!(status(dir.path()), HookStatus::Installed { chained: false });
+    }
+
+    #[test]
+    fn test_install_refuses_foreign_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(HOOK_NAME), "#!/bin/sh\necho other\n").unwrap();
+
+        let result = install(dir.path(), false, false);
+        assert!(matches!(result, Err(DevFlowError::HookAlreadyExists(_))));
+        assert_eq!(status(dir.path()), HookStatus::Foreign);
+    }
+
+    #[test]
+    fn test_install_force_replaces_foreign_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(HOOK_NAME), "#!/bin/sh\necho other\n").unwrap();
+
+        assert_eq!(install(dir.path(), true, false).unwrap(), InstallOutcome::Replaced);
+        assert_eq!(status(dir.path()), HookStatus::Installed { chained: false });
+    }
+
+    #[test]
+    fn test_chain_then_uninstall_restores_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = "#!/bin/sh\necho other\n";
+        std::fs::write(dir.path().join(HOOK_NAME), original).unwrap();
+
+        assert_eq!(install(dir.path(), false, true).unwrap(), InstallOutcome::Chained);
+        assert_eq!(status(dir.path()), HookStatus::Installed { chained: true });
+
+        assert_eq!(uninstall(dir.path()).unwrap(), UninstallOutcome::Restored);
+        assert_eq!(std::fs::read_to_string(dir.path().join(HOOK_NAME)).unwrap(), original);
+        assert!(!dir.path().join(CHAINED_HOOK_NAME).exists());
+    }
+
+    #[test]
+    fn test_uninstall_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        install(dir.path(), false, false).unwrap();
+
+        assert_eq!(uninstall(dir.path()).unwrap(), UninstallOutcome::Removed);
+        assert_eq!(uninstall(dir.path()).unwrap(), UninstallOutcome::NotInstalled);
+    }
+
+    #[test]
+    fn test_uninstall_leaves_foreign_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(HOOK_NAME), "#!/bin/sh\n").unwrap();
+
+        assert!(uninstall(dir.path()).is_err());
+        assert!(dir.path().join(HOOK_NAME).exists());
+    }
+
+    #[cfg(unix)]
+    fn run_hook(repo_dir: &Path, hook: &Path, branch: &str, message: &str) -> String {
+        let repo = git2::Repository::init(repo_dir).unwrap();
+        repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+
+        let msg_file = repo_dir.join("COMMIT_MSG");
+        std::fs::write(&msg_file, message).unwrap();
+
+        let status = std::process::Command::new("sh")
+            .arg(hook)
+            .arg(&msg_file)
+            .current_dir(repo_dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        std::fs::read_to_string(&msg_file).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_script_prefixes_ticket_id() {
+        let hooks = tempfile::tempdir().unwrap();
+        install(hooks.path(), false, false).unwrap();
+        let hook = hooks.path().join(HOOK_NAME);
+
+        let repo = tempfile::tempdir().unwrap();
+        let message = run_hook(repo.path(), &hook, "feat/WAB-3848/implement_logic", "Add logic\n");
+        assert_eq!(message, "WAB-3848: Add logic\n");
+
+        let repo = tempfile::tempdir().unwrap();
+        let message = run_hook(repo.path(), &hook, "feat/WAB-1/x", "WAB-1: already there\n");
+        assert_eq!(message, "WAB-1: already there\n");
+
+        let repo = tempfile::tempdir().unwrap();
+        let message = run_hook(repo.path(), &hook, "main", "No ticket\n");
+        assert_eq!(message, "No ticket\n");
+    }
+}
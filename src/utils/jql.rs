@@ -0,0 +1,395 @@
+use std::fmt;
+
+/// Field to order results by; each maps to the JQL field of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortField {
+    /// JQL `created`
+    Created,
+    /// JQL `updated`
+    Updated,
+    /// JQL `priority`, in the order of the Jira priority scheme
+    Priority,
+    /// JQL `status`, in the order statuses appear in the workflow
+    Status,
+    /// JQL `key`
+    Key,
+}
+
+impl SortField {
+    fn as_jql(self) -> &'static str {
+        match self {
+            SortField::Created => "created",
+            SortField::Updated => "updated",
+            SortField::Priority => "priority",
+            SortField::Status => "status",
+            SortField::Key => "key",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Ordering appended to every generated query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub field: SortField,
+    pub order: SortOrder,
+}
+
+impl Default for Sort {
+    fn default() -> Self {
+        Self {
+            field: SortField::Updated,
+            order: SortOrder::Desc,
+        }
+    }
+}
+
+impl Sort {
+    fn as_jql(self) -> String {
+        let order = match self.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        format!("ORDER BY {} {}", self.field.as_jql(), order)
+    }
+
+    /// Human description, e.g. "updated, newest first"
+    pub fn describe(self) -> String {
+        let order = match (self.field, self.order) {
+            (SortField::Created | SortField::Updated, SortOrder::Desc) => "newest first",
+            (SortField::Created | SortField::Updated, SortOrder::Asc) => "oldest first",
+            (SortField::Priority, SortOrder::Desc) => "highest first",
+            (SortField::Priority, SortOrder::Asc) => "lowest first",
+            (SortField::Status | SortField::Key, SortOrder::Desc) => "descending",
+            (SortField::Status | SortField::Key, SortOrder::Asc) => "ascending",
+        };
+        format!("{}, {}", self.field.as_jql(), order)
+    }
+}
+
+/// Lower bound for `--updated-since` / `--created-since`: a relative
+/// duration (`7d`, `2w`, `12h`) or a `YYYY-MM-DD` date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Since {
+    Relative { amount: u32, unit: char },
+    Date(String),
+}
+
+impl fmt::Display for Since {
+    /// JQL operand: `-7d` for relative durations, `"2024-01-01"` for dates
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Since::Relative { amount, unit } => write!(f, "-{}{}", amount, unit),
+            Since::Date(date) => write!(f, "\"{}\"", date),
+        }
+    }
+}
+
+/// clap value parser for `Since`
+pub fn parse_since(value: &str) -> Result<Since, String> {
+    let value = value.trim();
+
+    if let Some(date) = parse_date(value) {
+        return Ok(Since::Date(date));
+    }
+
+    let unit = value
+        .chars()
+        .last()
+        .map(|c| c.to_ascii_lowercase())
+        .filter(|c| matches!(c, 'd' | 'w' | 'h'));
+    let amount = value
+        .get(..value.len().saturating_sub(1))
+        .and_then(|n| n.parse::<u32>().ok())
+        .filter(|n| *n > 0);
+
+    match (amount, unit) {
+        (Some(amount), Some(unit)) => Ok(Since::Relative { amount, unit }),
+        _ => Err(format!(
+            "'{}' is not a duration (e.g. 7d, 2w, 12h) or a date (YYYY-MM-DD)",
+            value
+        )),
+    }
+}
+
+fn parse_date(value: &str) -> Option<String> {
+    let mut parts = value.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+
+    year.parse::<u32>().ok()?;
+    let month = month.parse::<u32>().ok()?;
+    let day = day.parse::<u32>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    Some(value.to_string())
+}
+
+/// Quote a value for use as a JQL string literal
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `--sprint`: "current" means any open sprint, anything else is a sprint name
+pub fn sprint_clause(sprint: &str) -> String {
+    if sprint.eq_ignore_ascii_case("current") {
+        "sprint in openSprints()".to_string()
+    } else {
+        format!("sprint = {}", quote(sprint))
+    }
+}
+
+/// `--status`: one status, or any of several
+pub fn status_clause(statuses: &[String]) -> Option<String> {
+    any_of_clause("status", statuses)
+}
+
+/// `--priority`: one priority, or any of several
+pub fn priority_clause(priorities: &[String]) -> Option<String> {
+    any_of_clause("priority", priorities)
+}
+
+/// `--type`: one issue type, or any of several
+pub fn issue_type_clause(types: &[String]) -> Option<String> {
+    any_of_clause("issuetype", types)
+}
+
+fn any_of_clause(field: &str, values: &[String]) -> Option<String> {
+    match list_values(values).as_slice() {
+        [] => None,
+        [value] => Some(format!("{} = {}", field, quote(value))),
+        values => Some(format!("{} in ({})", field, quote_list(values))),
+    }
+}
+
+/// `--exclude-status`: none of these statuses
+pub fn exclude_status_clause(statuses: &[String]) -> Option<String> {
+    let statuses = list_values(statuses);
+    (!statuses.is_empty()).then(|| format!("status not in ({})", quote_list(&statuses)))
+}
+
+/// Comma-separated flags split on the comma alone, so `"To Do, Done"` leaves
+/// a leading space and `"Done,"` an empty value; neither matches anything
+fn list_values(values: &[String]) -> Vec<&str> {
+    values.iter().map(|value| value.trim()).filter(|value| !value.is_empty()).collect()
+}
+
+fn quote_list(values: &[&str]) -> String {
+    values.iter().map(|value| quote(value)).collect::<Vec<_>>().join(", ")
+}
+
+pub fn label_clause(label: &str) -> String {
+    format!("labels = {}", quote(label))
+}
+
+pub fn component_clause(component: &str) -> String {
+    format!("component = {}", quote(component))
+}
+
+/// Sub-tasks of `ticket_id`
+pub fn parent_clause(ticket_id: &str) -> String {
+    format!("parent = {}", quote(ticket_id))
+}
+
+/// Free-text search over summary and description
+pub fn text_clause(query: &str) -> String {
+    let query = quote(query);
+    format!("(summary ~ {} OR description ~ {})", query, query)
+}
+
+pub fn updated_since_clause(since: &Since) -> String {
+    format!("updated >= {}", since)
+}
+
+pub fn created_since_clause(since: &Since) -> String {
+    format!("created >= {}", since)
+}
+
+/// Tickets you moved to `status` between two `YYYY-MM-DD` dates, both inclusive
+pub fn changed_to_by_me_clause(status: &str, since: &str, until: &str) -> String {
+    format!(
+        "status CHANGED TO {} BY currentUser() DURING (\"{}\", \"{} 23:59\")",
+        quote(status),
+        since,
+        until
+    )
+}
+
+/// Builds an `AND`-joined JQL query with a trailing `ORDER BY`
+#[derive(Debug, Default)]
+pub struct JqlBuilder {
+    clauses: Vec<String>,
+    sort: Sort,
+}
+
+impl JqlBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn and(mut self, clause: impl Into<String>) -> Self {
+        self.clauses.push(clause.into());
+        self
+    }
+
+    pub fn and_if(self, clause: Option<String>) -> Self {
+        match clause {
+            Some(clause) => self.and(clause),
+            None => self,
+        }
+    }
+
+    pub fn sort(mut self, sort: Sort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn build(&self) -> String {
+        let order = self.sort.as_jql();
+        if self.clauses.is_empty() {
+            order
+        } else {
+            format!("{} {}", self.clauses.join(" AND "), order)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults_to_updated_desc() {
+        let jql = JqlBuilder::new()
+            .and("project = WAB")
+            .and("status = \"To Do\"")
+            .build();
+        assert_eq!(jql, "project = WAB AND status = \"To Do\" ORDER BY updated DESC");
+    }
+
+    #[test]
+    fn test_builder_custom_sort_and_optional_clauses() {
+        let jql = JqlBuilder::new()
+            .and("project = WAB")
+            .and_if(None)
+            .and_if(Some(sprint_clause("current")))
+            .sort(Sort { field: SortField::Priority, order: SortOrder::Asc })
+            .build();
+        assert_eq!(jql, "project = WAB AND sprint in openSprints() ORDER BY priority ASC");
+        assert_eq!(JqlBuilder::new().build(), "ORDER BY updated DESC");
+    }
+
+    #[test]
+    fn test_parse_since_relative() {
+        assert_eq!(parse_since("7d"), Ok(Since::Relative { amount: 7, unit: 'd' }));
+        assert_eq!(parse_since("2W"), Ok(Since::Relative { amount: 2, unit: 'w' }));
+        assert_eq!(updated_since_clause(&parse_since("12h").unwrap()), "updated >= -12h");
+    }
+
+    #[test]
+    fn test_parse_since_date() {
+        let since = parse_since("2024-01-01").unwrap();
+        assert_eq!(created_since_clause(&since), "created >= \"2024-01-01\"");
+    }
+
+    #[test]
+    fn test_parse_since_rejects_garbage() {
+        for value in ["", "d", "7", "0d", "-3d", "7m", "2024-13-01", "2024-1-1", "yesterday"] {
+            assert!(parse_since(value).is_err(), "{:?} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn test_priority_and_type_clauses() {
+        let values = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(priority_clause(&[]), None);
+        assert_eq!(issue_type_clause(&[]), None);
+
+        let jql = JqlBuilder::new()
+            .and("assignee = currentUser()")
+            .and_if(priority_clause(&values(&["Highest", "High"])))
+            .and_if(issue_type_clause(&values(&["Bug"])))
+            .sort(Sort { field: SortField::Priority, order: SortOrder::Desc })
+            .build();
+        assert_eq!(
+            jql,
+            "assignee = currentUser() AND priority in (\"Highest\", \"High\") AND issuetype = \"Bug\" ORDER BY priority DESC"
+        );
+    }
+
+    #[test]
+    fn test_changed_to_by_me_clause() {
+        let jql = JqlBuilder::new()
+            .and(changed_to_by_me_clause("In Review", "2024-01-08", "2024-01-15"))
+            .build();
+        assert_eq!(
+            jql,
+            "status CHANGED TO \"In Review\" BY currentUser() DURING (\"2024-01-08\", \"2024-01-15 23:59\") \
+             ORDER BY updated DESC"
+        );
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(sprint_clause("current"), "sprint in openSprints()");
+        assert_eq!(sprint_clause("Sprint 12"), "sprint = \"Sprint 12\"");
+        assert_eq!(sprint_clause("Sprint \"12\""), "sprint = \"Sprint \\\"12\\\"\"");
+        assert_eq!(label_clause("needs-qa"), "labels = \"needs-qa\"");
+        assert_eq!(component_clause("backend"), "component = \"backend\"");
+        assert_eq!(component_clause("Mobile \"iOS\""), "component = \"Mobile \\\"iOS\\\"\"");
+        assert_eq!(parent_clause("WAB-1"), "parent = \"WAB-1\"");
+        assert_eq!(text_clause("login"), "(summary ~ \"login\" OR description ~ \"login\")");
+    }
+
+    #[test]
+    fn test_status_clauses() {
+        let statuses = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(status_clause(&[]), None);
+        assert_eq!(status_clause(&statuses(&["To Do"])), Some("status = \"To Do\"".to_string()));
+        assert_eq!(
+            status_clause(&statuses(&["To Do", "In Progress"])),
+            Some("status in (\"To Do\", \"In Progress\")".to_string())
+        );
+        assert_eq!(exclude_status_clause(&[]), None);
+        assert_eq!(
+            exclude_status_clause(&statuses(&["Done", "Closed"])),
+            Some("status not in (\"Done\", \"Closed\")".to_string())
+        );
+    }
+
+    #[test]
+    fn test_list_clauses_trim_and_skip_empty_values() {
+        let values = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        // As clap splits `--status "To Do, In Progress,"`
+        assert_eq!(
+            status_clause(&values(&["To Do", " In Progress", ""])),
+            Some("status in (\"To Do\", \"In Progress\")".to_string())
+        );
+        assert_eq!(priority_clause(&values(&[" High "])), Some("priority = \"High\"".to_string()));
+        assert_eq!(issue_type_clause(&values(&["", " "])), None);
+        assert_eq!(exclude_status_clause(&values(&[" "])), None);
+        assert_eq!(
+            exclude_status_clause(&values(&["Done", " Closed"])),
+            Some("status not in (\"Done\", \"Closed\")".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sort_describe() {
+        assert_eq!(Sort::default().describe(), "updated, newest first");
+        let sort = Sort { field: SortField::Key, order: SortOrder::Asc };
+        assert_eq!(sort.describe(), "key, ascending");
+        let sort = Sort { field: SortField::Status, order: SortOrder::Desc };
+        assert_eq!(sort.describe(), "status, descending");
+        assert_eq!(JqlBuilder::new().sort(sort).build(), "ORDER BY status DESC");
+    }
+}
@@ -0,0 +1,12 @@
+pub mod bulk;
+pub mod clipboard;
+pub mod codeowners;
+pub mod commit;
+pub mod format;
+pub mod hooks;
+pub mod jql;
+pub mod output;
+pub mod progress;
+pub mod prompt;
+pub mod report;
+pub mod watch;
@@ -0,0 +1,53 @@
+//! Where command output goes. With `--json`, stdout carries only the
+//! command's JSON result and everything else is written to stderr.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Set from the running command's `--json` flag before it starts
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// `println!` for progress and decoration: stderr in `--json` mode
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::utils::output::is_json() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+pub(crate) use say;
+
+/// The result of a command, printed as JSON with `--json` and as a
+/// human-readable summary otherwise
+pub trait Report: serde::Serialize {
+    fn print(&self);
+}
+
+/// Turn colors on or off for a test. Tests run in parallel and colored's
+/// override is global, so the guard keeps any other test from changing it
+/// until the caller is done.
+#[cfg(test)]
+pub fn override_colors(on: bool) -> std::sync::MutexGuard<'static, ()> {
+    static COLORS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let guard = COLORS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    colored::control::set_override(on);
+    guard
+}
+
+pub fn present<T: Report>(report: &T) -> anyhow::Result<()> {
+    if is_json() {
+        println!("{}", serde_json::to_string_pretty(report)?);
+    } else {
+        report.print();
+    }
+    Ok(())
+}
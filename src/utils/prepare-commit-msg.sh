@@ -0,0 +1,34 @@
+#!/bin/sh
+# devflow prepare-commit-msg hook
+# Installed by `devflow hook install`; remove with `devflow hook uninstall`.
+# Prefixes commit messages with the ticket ID from branches named
+# <prefix>/<TICKET-123>/<description>.
+
+MSG_FILE="$1"
+SOURCE="$2"
+HOOK_DIR=$(dirname "$0")
+
+# Run the hook that was here before devflow, if we were asked to chain to it
+if [ -x "$HOOK_DIR/prepare-commit-msg.devflow-chained" ]; then
+    "$HOOK_DIR/prepare-commit-msg.devflow-chained" "$@" || exit $?
+fi
+
+case "$SOURCE" in
+    merge|squash) exit 0 ;;
+esac
+
+BRANCH=$(git symbolic-ref --short HEAD 2>/dev/null) || exit 0
+SEGMENT=$(printf '%s\n' "$BRANCH" | cut -d/ -f2 -s)
+
+case "$SEGMENT" in
+    *-*) TICKET=$(printf '%s\n' "$SEGMENT" | cut -d- -f1,2) ;;
+    *) exit 0 ;;
+esac
+
+[ -n "$TICKET" ] || exit 0
+
+if grep -qF "$TICKET" "$MSG_FILE"; then
+    exit 0
+fi
+
+{ printf '%s: ' "$TICKET"; cat "$MSG_FILE"; } > "$MSG_FILE.devflow" && mv "$MSG_FILE.devflow" "$MSG_FILE"
@@ -0,0 +1,239 @@
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::future::Future;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static ANIMATE: AtomicBool = AtomicBool::new(true);
+/// The spinner on screen, which other lines have to be printed above
+static ACTIVE_SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Set from the global `--quiet` / `--no-color` flags before any command runs
+pub fn configure(quiet: bool, no_color: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+    ANIMATE.store(!no_color, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+fn should_animate() -> bool {
+    ANIMATE.load(Ordering::Relaxed) && std::io::stdout().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// `--verbose` (or `DEVFLOW_DEBUG`) trades spinners for detailed logs
+fn is_verbose() -> bool {
+    std::env::var("DEVFLOW_DEBUG").is_ok()
+}
+
+/// How a step's progress is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Hidden,
+    /// Animated spinner with the elapsed time
+    Spinner,
+    /// One line when the step starts, for logs and pipes
+    Plain,
+    /// Start and finish lines with timings and the error on failure
+    Verbose,
+}
+
+impl Mode {
+    fn pick(quiet: bool, verbose: bool, animate: bool) -> Self {
+        match (quiet, verbose, animate) {
+            (true, _, _) => Mode::Hidden,
+            (false, true, _) => Mode::Verbose,
+            (false, false, true) => Mode::Spinner,
+            (false, false, false) => Mode::Plain,
+        }
+    }
+}
+
+/// Progress line for one long-running step. Output goes to stderr so
+/// `--json` output on stdout stays clean.
+pub struct ProgressDisplay {
+    label: String,
+    mode: Mode,
+    spinner: Option<ProgressBar>,
+    /// The spinner this one replaced on screen, shown again once it finishes
+    outer: Option<ProgressBar>,
+    started: Instant,
+}
+
+impl ProgressDisplay {
+    pub fn start(label: &str) -> Self {
+        let mode = Mode::pick(is_quiet(), is_verbose(), should_animate());
+
+        let spinner = match mode {
+            Mode::Spinner => {
+                let spinner = ProgressBar::new_spinner();
+                if let Ok(style) = ProgressStyle::with_template("  {spinner:.cyan} {msg} {elapsed:.dim}") {
+                    spinner.set_style(style.tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "));
+                }
+                spinner.set_message(format!("{}...", label));
+                spinner.enable_steady_tick(Duration::from_millis(80));
+                Some(spinner)
+            }
+            Mode::Plain => {
+                eprintln!("{}", format!("  {}...", label).dimmed());
+                None
+            }
+            Mode::Verbose => {
+                eprintln!("{}", format!("  → {}", label).dimmed());
+                None
+            }
+            Mode::Hidden => None,
+        };
+
+        let outer = match &spinner {
+            Some(spinner) => set_active_spinner(Some(spinner.clone())),
+            None => None,
+        };
+
+        Self {
+            label: label.to_string(),
+            mode,
+            spinner,
+            outer,
+            started: Instant::now(),
+        }
+    }
+
+    /// Replace the progress line with ✓
+    pub fn succeed(self) {
+        self.finish(None);
+    }
+
+    /// Replace the progress line with ✗; verbose mode also shows `error`
+    pub fn fail(self, error: &dyn std::fmt::Display) {
+        self.finish(Some(error));
+    }
+
+    fn finish(self, error: Option<&dyn std::fmt::Display>) {
+        if let Some(spinner) = &self.spinner {
+            spinner.finish_and_clear();
+            set_active_spinner(self.outer.clone());
+        }
+        if self.mode == Mode::Hidden {
+            return;
+        }
+
+        let mut line = match error {
+            None => format!("  ✓ {}", self.label),
+            Some(_) => format!("  ✗ {}", self.label),
+        };
+        if self.mode == Mode::Verbose {
+            line.push_str(&format!(" ({})", format_elapsed(self.started.elapsed())));
+            if let Some(error) = error {
+                line.push_str(&format!(": {}", error));
+            }
+        }
+
+        match error {
+            None => eprintln!("{}", line.green()),
+            Some(_) => eprintln!("{}", line.red()),
+        }
+    }
+}
+
+/// Replace the spinner lines are printed above, returning the previous one
+fn set_active_spinner(spinner: Option<ProgressBar>) -> Option<ProgressBar> {
+    match ACTIVE_SPINNER.lock() {
+        Ok(mut active) => std::mem::replace(&mut active, spinner),
+        Err(_) => None,
+    }
+}
+
+/// Print a line to stderr, above the running spinner if there is one so the
+/// two don't overwrite each other
+fn print_line(line: &str) {
+    let active = ACTIVE_SPINNER.lock().ok().and_then(|active| active.clone());
+    match active {
+        Some(spinner) => spinner.println(line),
+        None => eprintln!("{}", line),
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    if elapsed < Duration::from_secs(1) {
+        format!("{}ms", elapsed.as_millis())
+    } else {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    }
+}
+
+/// Await `fut` while showing `label` with a `ProgressDisplay`, then replace
+/// it with a ✓ or ✗ line. Without a terminal the label is printed once
+/// instead of animated; with `--quiet` nothing is printed.
+pub async fn with_spinner<T, E, F>(label: &str, fut: F) -> Result<T, E>
+where
+    E: std::fmt::Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let progress = ProgressDisplay::start(label);
+    let result = fut.await;
+
+    match &result {
+        Ok(_) => progress.succeed(),
+        Err(e) => progress.fail(e),
+    }
+
+    result
+}
+
+/// Await one of several steps running together under a single
+/// `with_spinner`. Only `--verbose` shows it: a line with its timing once it
+/// finishes, so you can see which of them took the time.
+pub async fn timed<T, E, F>(label: &str, fut: F) -> Result<T, E>
+where
+    E: std::fmt::Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = fut.await;
+
+    if !is_quiet() && is_verbose() {
+        let elapsed = format_elapsed(started.elapsed());
+        match &result {
+            Ok(_) => print_line(&format!("    · {} ({})", label, elapsed).dimmed().to_string()),
+            Err(e) => print_line(&format!("    · {} failed ({}): {}", label, elapsed, e).dimmed().to_string()),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_spinner_passes_results_through() {
+        let ok: Result<u32, String> = with_spinner("Working", async { Ok(42) }).await;
+        assert_eq!(ok, Ok(42));
+
+        let err: Result<u32, String> = with_spinner("Working", async { Err("boom".to_string()) }).await;
+        assert_eq!(err, Err("boom".to_string()));
+
+        let err: Result<u32, String> = timed("Step", async { Err("boom".to_string()) }).await;
+        assert_eq!(err, Err("boom".to_string()));
+    }
+
+    #[test]
+    fn test_mode_pick() {
+        assert_eq!(Mode::pick(true, true, true), Mode::Hidden);
+        assert_eq!(Mode::pick(false, true, true), Mode::Verbose);
+        assert_eq!(Mode::pick(false, false, true), Mode::Spinner);
+        assert_eq!(Mode::pick(false, false, false), Mode::Plain);
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(350)), "350ms");
+        assert_eq!(format_elapsed(Duration::from_millis(1250)), "1.2s");
+    }
+}
@@ -0,0 +1,63 @@
+//! Confirmation before destructive operations, honouring the global `--yes`
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Set from the global `--yes` flag before any command runs
+pub fn configure(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// Ask a yes/no question, defaulting to no. `--yes` accepts without asking;
+/// without a terminal (and without `--yes`) this fails instead of guessing.
+pub fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    decide(prompt, ASSUME_YES.load(Ordering::Relaxed), std::io::stdin().is_terminal(), || {
+        Ok(dialoguer::Confirm::new().with_prompt(prompt).default(false).interact()?)
+    })
+}
+
+fn decide(
+    prompt: &str,
+    assume_yes: bool,
+    interactive: bool,
+    ask: impl FnOnce() -> anyhow::Result<bool>,
+) -> anyhow::Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    if !interactive {
+        anyhow::bail!("{}\n   Not attached to a terminal; pass --yes to confirm", prompt);
+    }
+    ask()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn never_asked() -> anyhow::Result<bool> {
+        panic!("should not prompt")
+    }
+
+    #[test]
+    fn test_yes_accepts_without_asking() {
+        assert!(decide("Overwrite?", true, true, never_asked).unwrap());
+        assert!(decide("Overwrite?", true, false, never_asked).unwrap());
+    }
+
+    #[test]
+    fn test_no_terminal_without_yes_errors() {
+        let err = decide("Overwrite the existing config?", false, false, never_asked).unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("Overwrite the existing config?"));
+        assert!(message.contains("--yes"));
+    }
+
+    #[test]
+    fn test_terminal_uses_the_answer() {
+        assert!(decide("Overwrite?", false, true, || Ok(true)).unwrap());
+        assert!(!decide("Overwrite?", false, true, || Ok(false)).unwrap());
+    }
+}
@@ -0,0 +1,262 @@
+//! `devflow report`: the tickets finished and PRs merged in a date window,
+//! as markdown to paste into a doc or as JSON
+
+use crate::models::ticket::JiraTicket;
+use crate::utils::format::{civil_from_days, days_from_civil};
+use crate::utils::jql::Since;
+use crate::utils::output::Report;
+use serde::Serialize;
+
+/// Inclusive range of UTC dates, as `YYYY-MM-DD`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Window {
+    pub since: String,
+    pub until: String,
+}
+
+impl Window {
+    /// `since` defaults to 7 days before `now` and `until` to `now`'s date.
+    /// Relative values (`7d`, `2w`, `12h`) count back from `now`.
+    pub fn resolve(since: Option<&Since>, until: Option<&Since>, now: u64) -> Result<Self, String> {
+        let day_of = |value: Option<&Since>, default_days_back: u64| -> i64 {
+            let seconds_back = match value {
+                None => default_days_back * 86_400,
+                Some(Since::Date(date)) => return parse_day(date),
+                Some(Since::Relative { amount, unit }) => {
+                    let unit_seconds = match unit {
+                        'h' => 3_600,
+                        'w' => 7 * 86_400,
+                        _ => 86_400,
+                    };
+                    u64::from(*amount) * unit_seconds
+                }
+            };
+            (now.saturating_sub(seconds_back) / 86_400) as i64
+        };
+
+        let (since, until) = (day_of(since, 7), day_of(until, 0));
+        if since > until {
+            return Err(format!("--since ({}) is after --until ({})", format_day(since), format_day(until)));
+        }
+        Ok(Self { since: format_day(since), until: format_day(until) })
+    }
+
+    /// Whether an ISO 8601 UTC timestamp (e.g. `2024-01-15T10:30:00Z`) falls
+    /// on one of the window's days
+    pub fn contains(&self, timestamp: &str) -> bool {
+        timestamp.get(..10).is_some_and(|date| self.since.as_str() <= date && date <= self.until.as_str())
+    }
+
+    /// Unix seconds from the start of `since` to the end of `until`
+    pub fn seconds(&self) -> (i64, i64) {
+        (parse_day(&self.since) * 86_400, (parse_day(&self.until) + 1) * 86_400)
+    }
+}
+
+/// `YYYY-MM-DD`, already validated by `jql::parse_since`
+fn parse_day(date: &str) -> i64 {
+    let mut parts = date.split('-').map(|part| part.parse::<i64>().unwrap_or(1));
+    let (year, month, day) = (parts.next().unwrap_or(1970), parts.next().unwrap_or(1), parts.next().unwrap_or(1));
+    days_from_civil(year, month, day)
+}
+
+fn format_day(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportTicket {
+    pub key: String,
+    pub summary: String,
+    /// Current Jira status
+    pub status: String,
+    pub url: String,
+    /// Your commits in the window on the ticket's local branches
+    pub commits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergedPr {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub merged_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeeklyReport {
+    #[serde(flatten)]
+    pub window: Window,
+    pub done: Vec<ReportTicket>,
+    pub in_review: Vec<ReportTicket>,
+    /// `None` when the git provider can't list merged PRs
+    pub merged_prs: Option<Vec<MergedPr>>,
+}
+
+/// Split the tickets moved to Done and to In Review into the report's
+/// sections. A ticket in both went on to Done and is only listed there;
+/// each keeps the order Jira returned it in.
+pub fn group_tickets(
+    done: Vec<JiraTicket>,
+    in_review: Vec<JiraTicket>,
+    jira_url: &str,
+    commits: impl Fn(&str) -> usize,
+) -> (Vec<ReportTicket>, Vec<ReportTicket>) {
+    let mut seen: Vec<String> = Vec::new();
+    let mut section = |tickets: Vec<JiraTicket>| -> Vec<ReportTicket> {
+        tickets
+            .into_iter()
+            .filter(|ticket| {
+                let new = !seen.contains(&ticket.key);
+                seen.push(ticket.key.clone());
+                new
+            })
+            .map(|ticket| ReportTicket {
+                url: format!("{}/browse/{}", jira_url.trim_end_matches('/'), ticket.key),
+                commits: commits(&ticket.key),
+                summary: ticket.fields.summary,
+                status: ticket.fields.status.name,
+                key: ticket.key,
+            })
+            .collect()
+    };
+
+    let done = section(done);
+    let in_review = section(in_review);
+    (done, in_review)
+}
+
+impl WeeklyReport {
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Report {} to {}\n", self.window.since, self.window.until);
+
+        for (heading, tickets) in [("Done", &self.done), ("In Review", &self.in_review)] {
+            out.push_str(&format!("\n## {}\n\n", heading));
+            if tickets.is_empty() {
+                out.push_str("_None_\n");
+            }
+            for ticket in tickets {
+                out.push_str(&format!("- [{}]({}) {}", ticket.key, ticket.url, ticket.summary));
+                match ticket.commits {
+                    0 => {}
+                    1 => out.push_str(" (1 commit)"),
+                    n => out.push_str(&format!(" ({} commits)", n)),
+                }
+                out.push('\n');
+            }
+        }
+
+        if let Some(prs) = &self.merged_prs {
+            out.push_str("\n## Merged PRs\n\n");
+            if prs.is_empty() {
+                out.push_str("_None_\n");
+            }
+            for pr in prs {
+                let date = pr.merged_at.get(..10).unwrap_or(&pr.merged_at);
+                out.push_str(&format!("- [#{}]({}) {} (merged {})\n", pr.number, pr.url, pr.title, date));
+            }
+        }
+
+        out
+    }
+}
+
+impl Report for WeeklyReport {
+    fn print(&self) {
+        print!("{}", self.to_markdown());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-15T09:30:00Z, a Monday
+    const NOW: u64 = 1_705_311_000;
+
+    fn ticket(key: &str, summary: &str, status: &str) -> JiraTicket {
+        serde_json::from_value(serde_json::json!({
+            "key": key,
+            "fields": { "summary": summary, "status": { "name": status } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_window_defaults_to_last_seven_days() {
+        let window = Window::resolve(None, None, NOW).unwrap();
+        assert_eq!((window.since.as_str(), window.until.as_str()), ("2024-01-08", "2024-01-15"));
+        assert_eq!(window.seconds(), (1_704_672_000, 1_705_363_200));
+
+        assert!(window.contains("2024-01-08T00:00:00Z"));
+        assert!(window.contains("2024-01-15T23:59:59.000Z"));
+        assert!(!window.contains("2024-01-07T23:59:59Z"));
+        assert!(!window.contains("2024-01-16T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_window_from_dates_and_durations() {
+        let since = Since::Date("2023-12-28".to_string());
+        let until = Since::Relative { amount: 1, unit: 'w' };
+        let window = Window::resolve(Some(&since), Some(&until), NOW).unwrap();
+        assert_eq!((window.since.as_str(), window.until.as_str()), ("2023-12-28", "2024-01-08"));
+
+        let since = Since::Relative { amount: 12, unit: 'h' };
+        assert_eq!(Window::resolve(Some(&since), None, NOW).unwrap().since, "2024-01-14");
+
+        let err = Window::resolve(None, Some(&Since::Date("2024-01-01".to_string())), NOW).unwrap_err();
+        assert_eq!(err, "--since (2024-01-08) is after --until (2024-01-01)");
+    }
+
+    #[test]
+    fn test_group_tickets_prefers_done() {
+        let done = vec![ticket("WAB-1", "Fix login", "Done")];
+        let in_review = vec![
+            ticket("WAB-1", "Fix login", "Done"),
+            ticket("WAB-2", "Add SSO", "In Review"),
+            ticket("WAB-2", "Add SSO", "In Review"),
+        ];
+        let (done, in_review) =
+            group_tickets(done, in_review, "https://jira.example.com/", |key| if key == "WAB-2" { 3 } else { 0 });
+
+        assert_eq!(done.iter().map(|t| t.key.as_str()).collect::<Vec<_>>(), vec!["WAB-1"]);
+        assert_eq!(in_review.len(), 1);
+        assert_eq!(in_review[0].url, "https://jira.example.com/browse/WAB-2");
+        assert_eq!((in_review[0].status.as_str(), in_review[0].commits), ("In Review", 3));
+    }
+
+    #[test]
+    fn test_markdown_sections() {
+        let (done, in_review) = group_tickets(
+            vec![ticket("WAB-1", "Fix login", "Done")],
+            Vec::new(),
+            "https://jira.example.com",
+            |_| 1,
+        );
+        let report = WeeklyReport {
+            window: Window { since: "2024-01-08".to_string(), until: "2024-01-15".to_string() },
+            done,
+            in_review,
+            merged_prs: Some(vec![MergedPr {
+                number: 12,
+                title: "Fix login".to_string(),
+                url: "https://github.com/acme/api/pull/12".to_string(),
+                merged_at: "2024-01-12T16:00:00Z".to_string(),
+            }]),
+        };
+
+        assert_eq!(
+            report.to_markdown(),
+            "# Report 2024-01-08 to 2024-01-15\n\
+             \n## Done\n\n- [WAB-1](https://jira.example.com/browse/WAB-1) Fix login (1 commit)\n\
+             \n## In Review\n\n_None_\n\
+             \n## Merged PRs\n\n- [#12](https://github.com/acme/api/pull/12) Fix login (merged 2024-01-12)\n"
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["since"], "2024-01-08");
+        assert_eq!(json["done"][0]["commits"], 1);
+        assert_eq!(json["merged_prs"][0]["number"], 12);
+    }
+}
@@ -0,0 +1,250 @@
+//! Change detection for `devflow watch`: each poll yields a `Snapshot`, which
+//! is folded into the last one to find what changed and whether to stop.
+//! `devflow list --watch` compares whole searches with `status_changes`.
+
+use crate::models::ticket::JiraTicket;
+use std::fmt;
+
+/// Ticket statuses `--until done` stops at
+const DONE_STATUSES: &[&str] = &["done", "closed", "resolved"];
+
+/// A PR/MR as `devflow watch` tracks it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrSnapshot {
+    /// `open`, `draft`, `merged` or `closed`
+    pub state: String,
+    /// e.g. "approved (2)" or "1 of 2 approvals"; `None` once merged or closed
+    pub review: Option<String>,
+    pub approved: bool,
+}
+
+/// What one poll saw. A `None` field wasn't watched, or couldn't be fetched
+/// this time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    pub ticket_status: Option<String>,
+    pub pr: Option<PrSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    TicketStatus { from: Option<String>, to: String },
+    PrState { from: Option<String>, to: String },
+    Review { from: Option<String>, to: String },
+}
+
+impl fmt::Display for Change {
+    /// "Ticket: In Progress → In Review", or just "Ticket: In Progress" for
+    /// the first value seen
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (what, from, to) = match self {
+            Change::TicketStatus { from, to } => ("Ticket", from, to),
+            Change::PrState { from, to } => ("PR", from, to),
+            Change::Review { from, to } => ("Review", from, to),
+        };
+        match from {
+            Some(from) => write!(f, "{}: {} → {}", what, from, to),
+            None => write!(f, "{}: {}", what, to),
+        }
+    }
+}
+
+impl Snapshot {
+    /// Fold in a poll and return what changed. Fields the poll couldn't
+    /// fetch keep their last value, so a flaky API doesn't look like a
+    /// change.
+    pub fn update(&mut self, poll: Snapshot) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        if let Some(status) = poll.ticket_status {
+            if self.ticket_status.as_ref() != Some(&status) {
+                changes.push(Change::TicketStatus { from: self.ticket_status.take(), to: status.clone() });
+                self.ticket_status = Some(status);
+            }
+        }
+
+        if let Some(pr) = poll.pr {
+            let previous = self.pr.take();
+            if previous.as_ref().map(|p| &p.state) != Some(&pr.state) {
+                changes.push(Change::PrState {
+                    from: previous.as_ref().map(|p| p.state.clone()),
+                    to: pr.state.clone(),
+                });
+            }
+            if let Some(review) = &pr.review {
+                let from = previous.and_then(|p| p.review);
+                if from.as_ref() != Some(review) {
+                    changes.push(Change::Review { from, to: review.clone() });
+                }
+            }
+            self.pr = Some(pr);
+        }
+
+        changes
+    }
+}
+
+/// Where `devflow watch --until` stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Until {
+    /// The PR/MR is merged
+    Merged,
+    /// The PR/MR is approved (or already merged)
+    Approved,
+    /// The ticket reaches Done, Closed or Resolved
+    Done,
+}
+
+impl fmt::Display for Until {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Until::Merged => "merged",
+            Until::Approved => "approved",
+            Until::Done => "done",
+        })
+    }
+}
+
+impl Until {
+    pub fn reached(self, snapshot: &Snapshot) -> bool {
+        let pr = snapshot.pr.as_ref();
+        match self {
+            Until::Merged => pr.is_some_and(|pr| pr.state == "merged"),
+            Until::Approved => pr.is_some_and(|pr| pr.approved || pr.state == "merged"),
+            Until::Done => snapshot
+                .ticket_status
+                .as_ref()
+                .is_some_and(|status| DONE_STATUSES.iter().any(|done| status.eq_ignore_ascii_case(done))),
+        }
+    }
+}
+
+/// Pop up a desktop notification
+#[cfg(feature = "notifications")]
+pub fn notify_desktop(summary: &str, body: &str) -> anyhow::Result<()> {
+    notify_rust::Notification::new()
+        .appname("devflow")
+        .summary(summary)
+        .body(body)
+        .show()
+        .map(|_| ())
+        .map_err(|e| anyhow::anyhow!("Could not show a desktop notification: {}", e))
+}
+
+#[cfg(not(feature = "notifications"))]
+pub fn notify_desktop(_summary: &str, _body: &str) -> anyhow::Result<()> {
+    anyhow::bail!("devflow was built without desktop notifications (enable the `notifications` feature)")
+}
+
+/// A ticket that was in two searches in a row with a different status each time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusChange {
+    pub key: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl fmt::Display for StatusChange {
+    /// `WAB-123 changed from "In Progress" to "In Review"`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} changed from \"{}\" to \"{}\"", self.key, self.from, self.to)
+    }
+}
+
+/// Tickets whose status differs between two searches, matched by key, in
+/// `current`'s order. Tickets that joined or left the results aren't changes.
+pub fn status_changes(previous: &[JiraTicket], current: &[JiraTicket]) -> Vec<StatusChange> {
+    current
+        .iter()
+        .filter_map(|ticket| {
+            let before = previous.iter().find(|old| old.key == ticket.key)?;
+            let (from, to) = (&before.fields.status.name, &ticket.fields.status.name);
+            (from != to).then(|| StatusChange { key: ticket.key.clone(), from: from.clone(), to: to.clone() })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ticket::listed;
+
+    fn pr(state: &str, review: Option<&str>, approved: bool) -> Option<PrSnapshot> {
+        Some(PrSnapshot { state: state.to_string(), review: review.map(str::to_string), approved })
+    }
+
+    fn ticket(status: &str) -> Option<String> {
+        Some(status.to_string())
+    }
+
+    #[test]
+    fn test_status_changes_match_by_key() {
+        let previous = [listed("WAB-1", "In Progress"), listed("WAB-2", "To Do"), listed("WAB-3", "To Do")];
+        let current = [listed("WAB-4", "To Do"), listed("WAB-2", "To Do"), listed("WAB-1", "In Review")];
+
+        let changes = status_changes(&previous, &current);
+        let lines: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(lines, vec!["WAB-1 changed from \"In Progress\" to \"In Review\""]);
+        assert!(status_changes(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_first_poll_reports_everything() {
+        let mut last = Snapshot::default();
+        let changes = last.update(Snapshot { ticket_status: ticket("In Review"), pr: pr("open", Some("review pending"), false) });
+        let lines: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(lines, vec!["Ticket: In Review", "PR: open", "Review: review pending"]);
+    }
+
+    #[test]
+    fn test_unchanged_poll_reports_nothing() {
+        let poll = Snapshot { ticket_status: ticket("In Review"), pr: pr("open", Some("review pending"), false) };
+        let mut last = Snapshot::default();
+        last.update(poll.clone());
+        assert!(last.update(poll).is_empty());
+    }
+
+    #[test]
+    fn test_review_and_state_changes() {
+        let mut last = Snapshot::default();
+        last.update(Snapshot { ticket_status: ticket("In Review"), pr: pr("open", Some("review pending"), false) });
+
+        let changes = last.update(Snapshot { ticket_status: ticket("In Review"), pr: pr("open", Some("approved (1)"), true) });
+        assert_eq!(
+            changes,
+            vec![Change::Review { from: Some("review pending".to_string()), to: "approved (1)".to_string() }]
+        );
+
+        // Merged PRs have no review progress; that isn't a change
+        let changes = last.update(Snapshot { ticket_status: ticket("Done"), pr: pr("merged", None, false) });
+        let lines: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(lines, vec!["Ticket: In Review → Done", "PR: open → merged"]);
+    }
+
+    #[test]
+    fn test_failed_fetch_keeps_last_value() {
+        let mut last = Snapshot::default();
+        last.update(Snapshot { ticket_status: ticket("In Progress"), pr: pr("open", Some("review pending"), false) });
+
+        // Jira down, then back with the same status: nothing to report
+        assert!(last.update(Snapshot { ticket_status: None, pr: pr("open", Some("review pending"), false) }).is_empty());
+        assert_eq!(last.ticket_status.as_deref(), Some("In Progress"));
+        assert!(last.update(Snapshot { ticket_status: ticket("In Progress"), pr: None }).is_empty());
+        assert!(last.pr.is_some());
+    }
+
+    #[test]
+    fn test_until() {
+        let open = Snapshot { ticket_status: ticket("In Review"), pr: pr("open", Some("approved (1)"), true) };
+        assert!(Until::Approved.reached(&open));
+        assert!(!Until::Merged.reached(&open));
+        assert!(!Until::Done.reached(&open));
+
+        let merged = Snapshot { ticket_status: ticket("closed"), pr: pr("merged", None, false) };
+        assert!(Until::Approved.reached(&merged));
+        assert!(Until::Merged.reached(&merged));
+        assert!(Until::Done.reached(&merged));
+
+        assert!(!Until::Merged.reached(&Snapshot::default()));
+    }
+}